@@ -0,0 +1,79 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use tonality::batch;
+use tonality::spelling::RespellPolicy;
+use tonality::{Key, Pitch, Step, Tpc};
+
+const STEPS: [Step; 7] = [
+    Step::C,
+    Step::D,
+    Step::E,
+    Step::F,
+    Step::G,
+    Step::A,
+    Step::B,
+];
+
+fn tpc_in_key(c: &mut Criterion) {
+    let tpcs: Vec<Tpc> = Tpc::all().collect();
+    let keys: Vec<Key> = Key::all().collect();
+    c.bench_function("Tpc::alteration", |b| {
+        b.iter(|| {
+            for &key in &keys {
+                for &tpc in &tpcs {
+                    black_box(black_box(tpc).alteration(key));
+                }
+            }
+        })
+    });
+    c.bench_function("Tpc::altered_step", |b| {
+        b.iter(|| {
+            for &key in &keys {
+                for &tpc in &tpcs {
+                    let _ = black_box(black_box(tpc).altered_step(Some(key)));
+                }
+            }
+        })
+    });
+    let mut alterations = vec![0; tpcs.len()];
+    c.bench_function("batch::alterations", |b| {
+        b.iter(|| {
+            for &key in &keys {
+                batch::alterations(black_box(&tpcs), key, &mut alterations);
+                black_box(&alterations);
+            }
+        })
+    });
+    let mut steps = vec![(Step::C, None); tpcs.len()];
+    c.bench_function("batch::altered_steps", |b| {
+        b.iter(|| {
+            for &key in &keys {
+                batch::altered_steps(black_box(&tpcs), key, &mut steps);
+                black_box(&steps);
+            }
+        })
+    });
+    c.bench_function("Step::with_key", |b| {
+        b.iter(|| {
+            for &key in &keys {
+                for &step in &STEPS {
+                    let _ = black_box(black_box(step).with_key(key));
+                }
+            }
+        })
+    });
+}
+
+fn midi(c: &mut Criterion) {
+    let policy = RespellPolicy::KeyRelative(Key::Eb);
+    c.bench_function("Pitch::from_midi", |b| {
+        b.iter(|| {
+            for note in 0..128 {
+                let _ = black_box(Pitch::from_midi(black_box(note), policy));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, tpc_in_key, midi);
+criterion_main!(benches);
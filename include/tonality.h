@@ -0,0 +1,91 @@
+#ifndef TONALITY_H
+#define TONALITY_H
+
+/* Generated with cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The value returned for invalid input, or results outside the domain of the
+ * library
+ */
+#define TONALITY_INVALID INT8_MIN
+
+/**
+ * A tpc with the octave of its step, in scientific pitch notation
+ */
+typedef struct TonalityPitch {
+  /**
+   * The tpc, or `TONALITY_INVALID`
+   */
+  int8_t tpc;
+  /**
+   * The octave of the written step, where middle C is in octave 4, as in
+   * the `octave` module
+   */
+  int8_t octave;
+} TonalityPitch;
+
+/**
+ * The accidentals of a key signature in the order they are written
+ */
+typedef struct TonalityKeySignature {
+  /**
+   * The number of accidentals, or `TONALITY_INVALID`
+   */
+  int8_t count;
+  /**
+   * The tpcs of the accidentals. Only the first `count` are used, and the
+   * rest are `TONALITY_INVALID`.
+   */
+  int8_t tpcs[7];
+} TonalityKeySignature;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Spell a MIDI note number with its octave, as it would be written in a key
+ */
+struct TonalityPitch tonality_spell_midi(uint8_t note, int8_t key);
+
+/**
+ * Transpose a tpc up by an interval
+ */
+int8_t tonality_transpose(int8_t tpc, int8_t interval);
+
+/**
+ * The interval from one tpc up to another
+ */
+int8_t tonality_interval(int8_t from, int8_t to);
+
+/**
+ * The step of a tpc
+ */
+int8_t tonality_step(int8_t tpc);
+
+/**
+ * The tpc of a step in a key
+ */
+int8_t tonality_spell_step(int8_t step, int8_t key);
+
+/**
+ * The alteration of a tpc in a key, from -3 to 3
+ */
+int8_t tonality_alteration(int8_t tpc, int8_t key);
+
+/**
+ * The accidentals of a key signature, sharps from F sharp and flats from B
+ * flat
+ */
+struct TonalityKeySignature tonality_key_signature(int8_t key);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TONALITY_H */
@@ -0,0 +1,120 @@
+//! Pitches in ABC notation
+//!
+//! An ABC pitch is a letter, uppercase from middle C and lowercase an octave
+//! above, followed by commas to lower it or apostrophes to raise it by
+//! octaves. It is preceded by `^` for sharp, `_` for flat and `=` for natural,
+//! doubled for double sharps and flats. Without an accidental, a note follows
+//! the key signature of the tune.
+//!
+//! ```
+//! # use tonality::{Key, Pitch, Tpc};
+//! # use tonality::abc;
+//! let f_sharp = Pitch::new(Tpc::Fs, 4);
+//! assert_eq!("^F", abc::format_pitch(f_sharp, None));
+//! assert_eq!("F", abc::format_pitch(f_sharp, Some(Key::G)));
+//! assert_eq!(Some(Pitch::new(Tpc::Bb, 3)), abc::parse_pitch("B,", Some(Key::F)));
+//! assert_eq!(Some(Pitch::new(Tpc::C, 6)), abc::parse_pitch("=c'", Some(Key::D)));
+//! ```
+use std::convert::TryFrom;
+
+use crate::{Accidental, Key, Pitch, Step};
+
+/// Format a pitch as an ABC token, with an accidental only where the key
+/// signature doesn't imply it. No key means C major.
+#[must_use]
+pub fn format_pitch(pitch: Pitch, key: Option<Key>) -> String {
+    let (step, accidental) = pitch.tpc.altered_step(key);
+    let mut token = String::from(match accidental {
+        None => "",
+        Some(Accidental::DblFlat) => "__",
+        Some(Accidental::Flat) => "_",
+        Some(Accidental::Natural) => "=",
+        Some(Accidental::Sharp) => "^",
+        Some(Accidental::DblSharp) => "^^",
+    });
+    let letter = step.to_string();
+    if pitch.octave >= 5 {
+        token.push_str(&letter.to_lowercase());
+        token.extend((5..pitch.octave).map(|_| '\''));
+    } else {
+        token.push_str(&letter);
+        token.extend((pitch.octave..4).map(|_| ','));
+    }
+    token
+}
+
+/// Parse an ABC token as a pitch in a key signature. No key means C major.
+/// Returns None if the token is not a single ABC pitch.
+#[must_use]
+pub fn parse_pitch(token: &str, key: Option<Key>) -> Option<Pitch> {
+    let rest = token.trim_start_matches(&['^', '_', '='][..]);
+    let accidental = match &token[..token.len() - rest.len()] {
+        "" => None,
+        "__" => Some(Accidental::DblFlat),
+        "_" => Some(Accidental::Flat),
+        "=" => Some(Accidental::Natural),
+        "^" => Some(Accidental::Sharp),
+        "^^" => Some(Accidental::DblSharp),
+        _ => return None,
+    };
+    let mut chars = rest.chars();
+    let letter = chars.next()?;
+    let step = match letter.to_ascii_uppercase() {
+        'C' => Step::C,
+        'D' => Step::D,
+        'E' => Step::E,
+        'F' => Step::F,
+        'G' => Step::G,
+        'A' => Step::A,
+        'B' => Step::B,
+        _ => return None,
+    };
+    let marks = chars.as_str();
+    let octave: i8 = if letter.is_ascii_lowercase() { 5 } else { 4 };
+    let octave = if marks.chars().all(|c| c == '\'') {
+        octave.checked_add(i8::try_from(marks.len()).ok()?)?
+    } else if marks.chars().all(|c| c == ',') {
+        octave.checked_sub(i8::try_from(marks.len()).ok()?)?
+    } else {
+        return None;
+    };
+    let tpc = step.resolve(accidental, key.unwrap_or_default());
+    Some(Pitch::new(tpc, octave))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tpc;
+
+    #[test]
+    fn test_octaves() {
+        assert_eq!("C,,", format_pitch(Pitch::new(Tpc::C, 2), None));
+        assert_eq!("b''", format_pitch(Pitch::new(Tpc::B, 7), None));
+        assert_eq!("__e", format_pitch(Pitch::new(Tpc::Ebb, 5), None));
+        assert_eq!("=B,", format_pitch(Pitch::new(Tpc::B, 3), Some(Key::Eb)));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let keys = [None, Some(Key::Cb), Some(Key::A)];
+        for tpc in Tpc::all() {
+            for octave in 1..8 {
+                for &key in &keys {
+                    let pitch = Pitch::new(tpc, octave);
+                    let token = format_pitch(pitch, key);
+                    assert_eq!(Some(pitch), parse_pitch(&token, key), "{token}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(None, parse_pitch("^_C", None));
+        assert_eq!(None, parse_pitch("^^^C", None));
+        assert_eq!(None, parse_pitch("H", None));
+        assert_eq!(None, parse_pitch("c',", None));
+        assert_eq!(None, parse_pitch("", None));
+    }
+}
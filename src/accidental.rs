@@ -1,9 +1,24 @@
 //! Accidentals
+use std::fmt;
+
 use num_derive::FromPrimitive;
 
+use crate::repr::Contiguous;
+
 /// Double or single flat, natural, double or single sharp
-#[derive(Clone, Copy, Debug, PartialEq, FromPrimitive)]
+///
+/// Displayed with Unicode accidental glyphs, or with ASCII characters when
+/// using the alternate flag.
+/// ```
+/// # use tonality::Accidental;
+/// assert_eq!("𝄫", format!("{}", Accidental::DblFlat));
+/// assert_eq!("bb", format!("{:#}", Accidental::DblFlat));
+/// assert_eq!("♯", format!("{}", Accidental::Sharp));
+/// assert_eq!("#", format!("{:#}", Accidental::Sharp));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive)]
 #[allow(missing_docs)]
+#[repr(i8)]
 pub enum Accidental {
     DblFlat = -2,
     Flat,
@@ -11,3 +26,38 @@ pub enum Accidental {
     Sharp,
     DblSharp,
 }
+
+impl Accidental {
+    /// Every accidental, from double flat to double sharp
+    /// ```
+    /// # use tonality::Accidental;
+    /// assert_eq!(5, Accidental::all().count());
+    /// assert_eq!(Some(Accidental::DblFlat), Accidental::all().next());
+    /// ```
+    #[must_use]
+    pub fn all() -> impl DoubleEndedIterator<Item = Accidental> {
+        Self::VARIANTS.iter().copied()
+    }
+
+    /// The symbol for the accidental, either as a Unicode glyph or in ASCII
+    pub(crate) fn symbol(self, ascii: bool) -> &'static str {
+        match (self, ascii) {
+            (Accidental::DblFlat, false) => "𝄫",
+            (Accidental::DblFlat, true) => "bb",
+            (Accidental::Flat, false) => "♭",
+            (Accidental::Flat, true) => "b",
+            (Accidental::Natural, false) => "♮",
+            (Accidental::Natural, true) => "n",
+            (Accidental::Sharp, false) => "♯",
+            (Accidental::Sharp, true) => "#",
+            (Accidental::DblSharp, false) => "𝄪",
+            (Accidental::DblSharp, true) => "##",
+        }
+    }
+}
+
+impl fmt::Display for Accidental {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol(f.alternate()))
+    }
+}
@@ -1,4 +1,11 @@
 //! The difference from the normal value of the step in the key, in semitones
 
 /// The difference from the normal value of the step in the key, in semitones
+///
+/// Being a plain integer, alterations can be negated and added to each other,
+/// and added to or subtracted from a `Tpc` with the `+` and `-` operators.
+///
+/// As an alias of `i8`, `Alteration::MIN` and `Alteration::MAX` are -128 and
+/// 127, the bounds of the integer. The alteration of a tpc in a key is within
+/// `Tpc::MIN_ALTERATION` and `Tpc::MAX_ALTERATION` instead.
 pub type Alteration = i8;
@@ -0,0 +1,301 @@
+//! Streaming analysis of sequences of notes
+//!
+//! The functions take any iterator of tpcs, so they work directly on note
+//! streams read from a file or generated on the fly. Streams of pitches, or
+//! of `element::Element`s with rests and unpitched notes, work as well, with
+//! the rules for rests given in the `element` module.
+//! ```
+//! # use tonality::{Interval, Key, Tpc};
+//! # use tonality::analysis;
+//! let melody = [Tpc::G, Tpc::A, Tpc::B, Tpc::C, Tpc::D, Tpc::Fs];
+//! let intervals: Vec<_> = analysis::intervals(melody.iter().copied()).collect();
+//! assert_eq!(Some(Interval::Maj2), intervals[0]);
+//! assert_eq!(Some(Interval::Min2), intervals[2]);
+//! assert_eq!(Some((Tpc::C, Tpc::B)), analysis::ambitus(melody.iter().copied()));
+//! assert_eq!(Some(Key::G), analysis::running_key(melody.iter().copied()).last());
+//! let weighted = melody.iter().map(|&tpc| (tpc, 1.0));
+//! assert_eq!(Some(Key::G), analysis::weighted_key(weighted));
+//! ```
+use std::collections::BTreeMap;
+
+use crate::element::{self, Note};
+use crate::repr::Contiguous;
+use crate::{Interval, Key, Tpc};
+
+/// The interval from each note to the next, or None where the interval is
+/// more than augmented or diminished, or a rest comes between the notes.
+/// Yields one item less than the pitched notes.
+/// ```
+/// # use tonality::{Interval, Tpc};
+/// # use tonality::analysis::intervals;
+/// let melody = [Tpc::C, Tpc::Ds, Tpc::Fb];
+/// let intervals: Vec<_> = intervals(melody.iter().copied()).collect();
+/// // D sharp to F flat would be doubly diminished
+/// assert_eq!(vec![Some(Interval::Aug2), None], intervals);
+/// ```
+pub fn intervals<I>(notes: I) -> impl Iterator<Item = Option<Interval>>
+where
+    I: IntoIterator,
+    I::Item: Note,
+{
+    element::pairs(notes, Note::tpc)
+        .map(|pair| pair.and_then(|(previous, tpc)| previous.try_interval_to(tpc).ok()))
+}
+
+/// The number of occurrences of each tpc, ordered along the line of fifths
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::analysis::histogram;
+/// let counts = histogram(vec![Tpc::E, Tpc::C, Tpc::E, Tpc::G]);
+/// let counts: Vec<_> = counts.into_iter().collect();
+/// assert_eq!(vec![(Tpc::C, 1), (Tpc::G, 1), (Tpc::E, 2)], counts);
+/// ```
+#[must_use]
+pub fn histogram<I>(notes: I) -> BTreeMap<Tpc, usize>
+where
+    I: IntoIterator,
+    I::Item: Note,
+{
+    let mut counts = BTreeMap::new();
+    for tpc in notes.into_iter().filter_map(Note::tpc) {
+        *counts.entry(tpc).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The estimated major key after each pitched note.
+///
+/// The notes of a major scale center two fifths above the key, so the
+/// estimate is the key nearest to two fifths below the mean position of the
+/// notes so far on the line of fifths.
+/// ```
+/// # use tonality::{Key, Tpc};
+/// # use tonality::analysis::running_key;
+/// let melody = [Tpc::D, Tpc::E, Tpc::Fs, Tpc::Cs];
+/// let keys: Vec<_> = running_key(melody.iter().copied()).collect();
+/// assert_eq!(vec![Key::C, Key::G, Key::D, Key::A], keys);
+/// ```
+pub fn running_key<I>(notes: I) -> impl Iterator<Item = Key>
+where
+    I: IntoIterator,
+    I::Item: Note,
+{
+    let mut sum = 0_i32;
+    let mut count = 0_i32;
+    notes.into_iter().filter_map(Note::tpc).map(move |tpc| {
+        sum += i32::from(tpc as i8);
+        count += 1;
+        let mean = f64::from(sum) / f64::from(count);
+        let estimate = (mean - 2.0)
+            .round()
+            .clamp(f64::from(Key::MIN as i8), f64::from(Key::MAX as i8));
+        // Clamped to the domain of Key
+        #[allow(clippy::cast_possible_truncation)]
+        let estimate = estimate as i8;
+        Key::in_range(estimate)
+    })
+}
+
+/// Weights of the tpcs along the line of fifths, from F double flat at index
+/// 0 up to B double sharp at index 34
+pub type FifthsHistogram = [f32; 35];
+
+/// The index of the tpc in a `FifthsHistogram`
+fn histogram_index(tpc: Tpc) -> usize {
+    // Tpc::MIN is the lowest tpc, so the difference is never negative
+    #[allow(clippy::cast_sign_loss)]
+    let index = (tpc as i8 - Tpc::MIN as i8) as usize;
+    index
+}
+
+/// The total weight of each tpc, such as the duration or velocity of notes
+/// read from a MIDI file. A tpc may occur several times.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::analysis::weighted_histogram;
+/// let histogram = weighted_histogram(vec![(Tpc::C, 1.0), (Tpc::E, 0.5), (Tpc::C, 2.0)]);
+/// assert_eq!(3.0, histogram[15]);
+/// assert_eq!(0.5, histogram[19]);
+/// ```
+#[must_use]
+pub fn weighted_histogram<I>(notes: I) -> FifthsHistogram
+where
+    I: IntoIterator<Item = (Tpc, f32)>,
+{
+    let mut histogram = [0.0; 35];
+    for (tpc, weight) in notes {
+        histogram[histogram_index(tpc)] += weight;
+    }
+    histogram
+}
+
+/// The score of every major key for a histogram, from C flat up to C sharp.
+///
+/// The score of a key is the share of the total weight on the notes of its
+/// scale, from 0 to 1. All scores are 0 if the total weight is 0.
+/// ```
+/// # use tonality::{Key, Tpc};
+/// # use tonality::analysis::{key_scores, weighted_histogram};
+/// let histogram = weighted_histogram(vec![(Tpc::G, 3.0), (Tpc::Fs, 1.0), (Tpc::F, 1.0)]);
+/// let scores = key_scores(&histogram);
+/// assert_eq!(15, scores.len());
+/// assert_eq!((Key::C, 0.8), scores[7]);
+/// assert_eq!((Key::G, 0.8), scores[8]);
+/// assert_eq!((Key::D, 0.8), scores[9]);
+/// ```
+#[must_use]
+pub fn key_scores(histogram: &FifthsHistogram) -> Vec<(Key, f32)> {
+    let total: f32 = histogram.iter().sum();
+    Key::all()
+        .map(|key| {
+            // The scale spans the seven fifths from the fourth up to the seventh
+            let fourth = histogram_index(key.scale_degree(3));
+            let weight: f32 = histogram[fourth..fourth + 7].iter().sum();
+            let score = if total == 0.0 { 0.0 } else { weight / total };
+            (key, score)
+        })
+        .collect()
+}
+
+/// The major key with the highest score for weighted notes, or None if the
+/// total weight is 0. Of keys with the same score, the one nearest to the
+/// estimate of `running_key` wins.
+/// ```
+/// # use tonality::{Key, Tpc};
+/// # use tonality::analysis::weighted_key;
+/// // A long F sharp outweighs the short F naturals
+/// let notes = vec![(Tpc::G, 2.0), (Tpc::F, 0.5), (Tpc::Fs, 4.0), (Tpc::F, 0.5), (Tpc::C, 1.0)];
+/// assert_eq!(Some(Key::G), weighted_key(notes));
+/// ```
+#[must_use]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn weighted_key<I>(notes: I) -> Option<Key>
+where
+    I: IntoIterator<Item = (Tpc, f32)>,
+{
+    let histogram = weighted_histogram(notes);
+    let total: f32 = histogram.iter().sum();
+    if total == 0.0 {
+        return None;
+    }
+    let mean = Tpc::all()
+        .zip(histogram.iter())
+        .map(|(tpc, weight)| f32::from(tpc as i8) * weight)
+        .sum::<f32>()
+        / total;
+    let distance = |key: Key| (f32::from(key as i8) - (mean - 2.0)).abs();
+    let best = key_scores(&histogram)
+        .into_iter()
+        .max_by(|(a, a_score), (b, b_score)| {
+            a_score
+                .partial_cmp(b_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    distance(*b)
+                        .partial_cmp(&distance(*a))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        });
+    #[cfg(feature = "tracing")]
+    if let Some((key, score)) = best {
+        tracing::debug!(key = %key, score, mean, total, "estimated key");
+    }
+    best.map(|(key, _)| key)
+}
+
+/// The lowest and highest notes in chromatic order within the octave, from C
+/// flat up to B sharp, or None if there are no pitched notes. Enharmonic notes are
+/// ordered by their position on the line of fifths, so D flat is below C
+/// sharp.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::analysis::ambitus;
+/// assert_eq!(Some((Tpc::Cb, Tpc::Bs)), ambitus(vec![Tpc::Bs, Tpc::G, Tpc::Cb]));
+/// assert_eq!(Some((Tpc::Db, Tpc::Cs)), ambitus(vec![Tpc::Cs, Tpc::Db]));
+/// assert_eq!(None, ambitus(Vec::<Tpc>::new()));
+/// ```
+#[must_use]
+pub fn ambitus<I>(notes: I) -> Option<(Tpc, Tpc)>
+where
+    I: IntoIterator,
+    I::Item: Note,
+{
+    let chromatic = |tpc: &Tpc| (tpc.step().semitones() + tpc.alteration(Key::C), *tpc);
+    let tpcs = notes.into_iter().filter_map(Note::tpc);
+    let (lowest, highest) = tpcs.fold(None, |range, tpc| match range {
+        None => Some((tpc, tpc)),
+        Some((lowest, highest)) => Some((
+            std::cmp::min_by_key(lowest, tpc, chromatic),
+            std::cmp::max_by_key(highest, tpc, chromatic),
+        )),
+    })?;
+    Some((lowest, highest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intervals_of_short_streams() {
+        assert_eq!(0, intervals(Vec::<Tpc>::new()).count());
+        assert_eq!(0, intervals(vec![Tpc::C]).count());
+    }
+
+    #[test]
+    fn test_elements() {
+        use crate::element::Element;
+        use crate::Pitch;
+
+        let note = |tpc| Element::Pitched(Pitch::new(tpc, 4));
+        let stream = [
+            note(Tpc::E),
+            Element::Rest,
+            note(Tpc::C),
+            Element::Unpitched,
+            note(Tpc::G),
+        ];
+        let intervals: Vec<_> = intervals(stream.iter().copied()).collect();
+        assert_eq!(vec![None, Some(Interval::P5)], intervals);
+        assert_eq!(3, histogram(stream.iter().copied()).len());
+        assert_eq!(3, running_key(stream.iter().copied()).count());
+        assert_eq!(Some((Tpc::C, Tpc::G)), ambitus(stream.iter().copied()));
+        assert_eq!(None, ambitus(vec![Element::Rest, Element::Unpitched]));
+    }
+
+    #[test]
+    fn test_running_key_is_clamped() {
+        let flats = vec![Tpc::Fbb; 3];
+        assert!(running_key(flats).all(|key| key == Key::MIN));
+        let sharps = vec![Tpc::Bss; 3];
+        assert!(running_key(sharps).all(|key| key == Key::MAX));
+    }
+
+    #[test]
+    fn test_running_key_of_scales() {
+        for key in Key::C
+            .circle_of_fifths(crate::key::Rotation::Clockwise)
+            .take(8)
+        {
+            let scale = (0..7).map(|degree| key.scale_degree(degree));
+            assert_eq!(Some(key), running_key(scale).last());
+        }
+    }
+
+    #[test]
+    fn test_weighted_key_of_scales() {
+        for key in Key::all() {
+            let scale = (0..7).map(|degree| (key.scale_degree(degree), 1.0));
+            assert_eq!(Some(key), weighted_key(scale));
+        }
+    }
+
+    #[test]
+    fn test_weighted_key_without_weight() {
+        assert_eq!(None, weighted_key(Vec::new()));
+        assert_eq!(None, weighted_key(vec![(Tpc::C, 0.0)]));
+        assert!(key_scores(&[0.0; 35])
+            .iter()
+            .all(|&(_, score)| score == 0.0));
+    }
+}
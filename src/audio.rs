@@ -0,0 +1,213 @@
+//! Rendering pitches to sine waves, for hearing tunings
+//!
+//! A `SineRenderer` mixes one sine wave per note into mono PCM samples between
+//! -1 and 1, with the frequencies of a tuning from the `tuning` module. There
+//! are no envelopes or timbres beyond a short fade at each end of a note, so
+//! the difference between temperaments is all there is to hear.
+//! ```
+//! # use tonality::{Pitch, Tpc};
+//! # use tonality::audio::SineRenderer;
+//! # use tonality::tuning::Edo;
+//! let renderer = SineRenderer::new(8000, 440.0);
+//! let chord = [Pitch::new(Tpc::C, 4), Pitch::new(Tpc::E, 4), Pitch::new(Tpc::G, 4)];
+//! let samples = renderer.render_chord(&chord, Edo::THIRTY_ONE, 0.5);
+//! assert_eq!(4000, samples.len());
+//! assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+//! ```
+use crate::tuning::{Edo, TunedNote};
+use crate::Pitch;
+
+/// The length of the fade at each end of a note, in seconds
+const FADE: f64 = 0.005;
+
+/// Renders notes as mixed sine waves
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct SineRenderer {
+    /// The number of samples per second
+    pub sample_rate: u32,
+    /// The frequency of A4 in hertz
+    pub a4: f64,
+}
+
+impl SineRenderer {
+    /// A renderer with a sample rate and a frequency of A4
+    pub const fn new(sample_rate: u32, a4: f64) -> Self {
+        SineRenderer { sample_rate, a4 }
+    }
+
+    /// The number of samples in a duration
+    fn samples(self, seconds: f64) -> usize {
+        // Durations are positive and far below the range of usize
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let samples = (seconds * f64::from(self.sample_rate)).round().max(0.0) as usize;
+        samples
+    }
+
+    /// The notes sounding together for a duration in seconds. Each note has
+    /// the same amplitude, and the mix stays between -1 and 1. A duration
+    /// shorter than half a sample, or too long to allocate, gives no samples.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::audio::SineRenderer;
+    /// # use tonality::tuning::TunedNote;
+    /// let renderer = SineRenderer::new(8000, 440.0);
+    /// let a = TunedNote::new(Pitch::new(Tpc::A, 4), 0.0);
+    /// let samples = renderer.render_tuned(&[a], 1.0);
+    /// // A sine wave of 440 Hz crosses zero upwards 440 times a second
+    /// let crossings = samples.windows(2).filter(|w| w[0] < 0.0 && w[1] > 0.0).count();
+    /// assert!((439..=440).contains(&crossings));
+    /// ```
+    #[must_use]
+    pub fn render_tuned(self, notes: &[TunedNote], seconds: f64) -> Vec<f32> {
+        let len = self.samples(seconds);
+        let mut samples = Vec::new();
+        // A duration too long to allocate gives no samples rather than a panic
+        if samples.try_reserve_exact(len).is_err() {
+            return samples;
+        }
+        samples.resize(len, 0.0);
+        if notes.is_empty() || len == 0 {
+            return samples;
+        }
+        let rate = f64::from(self.sample_rate);
+        // The number of notes in a chord is far below the precision of f64
+        #[allow(clippy::cast_precision_loss)]
+        let amplitude = 1.0 / notes.len() as f64;
+        let fade = self.samples(FADE).min(len / 2).max(1);
+        for note in notes {
+            let step = std::f64::consts::TAU * note.frequency(self.a4) / rate;
+            for (i, sample) in samples.iter_mut().enumerate() {
+                // Sample indices are far below the precision of f64
+                #[allow(clippy::cast_precision_loss)]
+                let value = (step * i as f64).sin() * amplitude;
+                // The mix is within -1 and 1, so the precision is enough
+                #[allow(clippy::cast_possible_truncation)]
+                let value = value as f32;
+                *sample += value;
+            }
+        }
+        for i in 0..fade {
+            // The fade is a few milliseconds of samples
+            #[allow(clippy::cast_precision_loss)]
+            let gain = i as f32 / fade as f32;
+            samples[i] *= gain;
+            samples[len - 1 - i] *= gain;
+        }
+        samples
+    }
+
+    /// The pitches sounding together in an equal temperament, for a duration
+    /// in seconds
+    #[must_use]
+    pub fn render_chord(self, pitches: &[Pitch], edo: Edo, seconds: f64) -> Vec<f32> {
+        let notes: Vec<TunedNote> = pitches.iter().map(|&pitch| edo.tuned(pitch)).collect();
+        self.render_tuned(&notes, seconds)
+    }
+
+    /// The pitches one after another in an equal temperament, each sounding
+    /// for a duration in seconds
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::audio::SineRenderer;
+    /// # use tonality::tuning::Edo;
+    /// let renderer = SineRenderer::new(8000, 440.0);
+    /// let melody = [Pitch::new(Tpc::C, 4), Pitch::new(Tpc::D, 4)];
+    /// assert_eq!(8000, renderer.render_melody(&melody, Edo::TWELVE, 0.5).len());
+    /// ```
+    #[must_use]
+    pub fn render_melody(self, pitches: &[Pitch], edo: Edo, seconds: f64) -> Vec<f32> {
+        pitches
+            .iter()
+            .flat_map(|&pitch| self.render_tuned(&[edo.tuned(pitch)], seconds))
+            .collect()
+    }
+}
+
+/// Convert samples between -1 and 1 to 16-bit PCM, clamping samples outside
+/// the range
+/// ```
+/// # use tonality::audio::to_i16;
+/// assert_eq!(vec![0, i16::MAX, -i16::MAX, i16::MAX], to_i16(&[0.0, 1.0, -1.0, 2.0]));
+/// ```
+#[must_use]
+pub fn to_i16(samples: &[f32]) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|sample| {
+            // Clamped to the range of i16
+            #[allow(clippy::cast_possible_truncation)]
+            let sample = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)).round() as i16;
+            sample
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::Tpc;
+
+    /// The frequency of a rendered sine wave, from its upward zero crossings
+    fn frequency(samples: &[f32], sample_rate: u32) -> f64 {
+        let crossings: Vec<usize> = samples
+            .windows(2)
+            .enumerate()
+            .filter(|(_, w)| w[0] < 0.0 && w[1] > 0.0)
+            .map(|(i, _)| i)
+            .collect();
+        let periods = f64::from(u32::try_from(crossings.len() - 1).unwrap());
+        let span = f64::from(u32::try_from(crossings[crossings.len() - 1] - crossings[0]).unwrap());
+        periods * f64::from(sample_rate) / span
+    }
+
+    #[test]
+    fn test_tunings_are_audible() {
+        let renderer = SineRenderer::new(48000, 440.0);
+        let e = [Pitch::new(Tpc::E, 4)];
+        let twelve = frequency(&renderer.render_chord(&e, Edo::TWELVE, 1.0), 48000);
+        let thirty_one = frequency(&renderer.render_chord(&e, Edo::THIRTY_ONE, 1.0), 48000);
+        assert!((twelve - 329.63).abs() < 0.1);
+        // The major third of 31-EDO is lower relative to A4, so E is higher
+        assert!((thirty_one - Edo::THIRTY_ONE.frequency(e[0], 440.0)).abs() < 0.1);
+        assert!((twelve - thirty_one).abs() > 0.5);
+    }
+
+    #[test]
+    fn test_fades_and_silence() {
+        let renderer = SineRenderer::new(8000, 440.0);
+        let samples = renderer.render_chord(&[Pitch::new(Tpc::A, 4)], Edo::TWELVE, 0.1);
+        assert!(samples[0].abs() < f32::EPSILON);
+        assert!(samples[samples.len() - 1].abs() < 0.05);
+        assert!(renderer.render_tuned(&[], 0.1).iter().all(|&s| s == 0.0));
+        assert!(renderer.render_melody(&[], Edo::TWELVE, 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_short_durations() {
+        let renderer = SineRenderer::new(8000, 440.0);
+        let a = [Pitch::new(Tpc::A, 4)];
+        assert!(renderer.render_chord(&a, Edo::TWELVE, 0.0).is_empty());
+        // A twentieth of a sample rounds to none
+        assert!(renderer.render_chord(&a, Edo::TWELVE, 0.000_006).is_empty());
+        assert!(renderer
+            .render_melody(&a, Edo::TWELVE, 0.000_006)
+            .is_empty());
+        // Most of a sample rounds to one, which the fade silences
+        assert_eq!(vec![0.0], renderer.render_chord(&a, Edo::TWELVE, 0.0001));
+        assert_eq!(2, renderer.render_chord(&a, Edo::TWELVE, 0.00025).len());
+    }
+
+    #[test]
+    fn test_long_durations() {
+        let renderer = SineRenderer::new(8000, 440.0);
+        let a = [Pitch::new(Tpc::A, 4)];
+        // Beyond the range of usize, so there is no buffer to allocate
+        assert!(renderer.render_chord(&a, Edo::TWELVE, f64::MAX).is_empty());
+        assert!(renderer
+            .render_chord(&a, Edo::TWELVE, f64::INFINITY)
+            .is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+//! Spelling many notes in the same key at once
+//!
+//! The functions fill an output slice with the result for each note, as
+//! `Tpc::alteration` and `Tpc::altered_step` would give it. Both look the
+//! alteration up in the same table, so a batch is a loop of lookups.
+//! ```
+//! # use tonality::{Accidental, Key, Step, Tpc};
+//! # use tonality::batch;
+//! let notes = [Tpc::Bb, Tpc::B, Tpc::Fs];
+//! let mut alterations = [0; 3];
+//! batch::alterations(&notes, Key::F, &mut alterations);
+//! assert_eq!([0, 1, 1], alterations);
+//!
+//! let mut steps = [(Step::C, None); 3];
+//! batch::altered_steps(&notes, Key::F, &mut steps);
+//! assert_eq!((Step::B, Some(Accidental::Natural)), steps[1]);
+//! ```
+use crate::{Accidental, Alteration, Key, Step, Tpc};
+
+/// Write the alteration of each note in the key to the output, as
+/// `Tpc::alteration`
+///
+/// # Panics
+///
+/// Panics if the output is not as long as the notes.
+pub fn alterations(notes: &[Tpc], key: Key, out: &mut [Alteration]) {
+    assert_eq!(notes.len(), out.len(), "output length differs from notes");
+    for (alteration, &tpc) in out.iter_mut().zip(notes) {
+        *alteration = tpc.alteration(key);
+    }
+}
+
+/// Write the step of each note, and the accidental it needs in the key, to
+/// the output, as `Tpc::altered_step`
+///
+/// # Panics
+///
+/// Panics if the output is not as long as the notes.
+pub fn altered_steps(notes: &[Tpc], key: Key, out: &mut [(Step, Option<Accidental>)]) {
+    assert_eq!(notes.len(), out.len(), "output length differs from notes");
+    for (altered_step, &tpc) in out.iter_mut().zip(notes) {
+        let accidental = if tpc.alteration(key) == 0 {
+            None
+        } else {
+            Some(tpc.accidental())
+        };
+        *altered_step = (tpc.step(), accidental);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_as_note_by_note() {
+        let tpcs: Vec<Tpc> = Tpc::all().collect();
+        let mut alterations = vec![0; tpcs.len()];
+        let mut steps = vec![(Step::C, None); tpcs.len()];
+        for key in Key::all() {
+            super::alterations(&tpcs, key, &mut alterations);
+            altered_steps(&tpcs, key, &mut steps);
+            for (i, &tpc) in tpcs.iter().enumerate() {
+                assert_eq!(tpc.alteration(key), alterations[i]);
+                assert_eq!(tpc.altered_step(Some(key)), steps[i]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "output length")]
+    fn test_output_length() {
+        alterations(&[Tpc::C], Key::C, &mut []);
+    }
+}
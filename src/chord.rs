@@ -0,0 +1,632 @@
+//! Chords: a root, a quality and an optional bass
+//!
+//! ```
+//! # use tonality::{Pitch, Tpc};
+//! # use tonality::chord::{Chord, ChordQuality};
+//! let chord = Chord::new(Tpc::C, ChordQuality::Major).with_inversion(1).unwrap();
+//! assert_eq!("C/E", chord.to_string());
+//! let voicing = chord.voicing(3).unwrap();
+//! assert_eq!(
+//!     vec![Pitch::new(Tpc::E, 3), Pitch::new(Tpc::G, 3), Pitch::new(Tpc::C, 4)],
+//!     voicing
+//! );
+//! ```
+use std::fmt;
+
+use crate::repr::Contiguous;
+use crate::spelling::RespellPolicy;
+use crate::{Interval, Key, Pitch, Step, Tpc};
+
+/// The quality of a chord, which determines the intervals above its root
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChordQuality {
+    /// Major triad: C, E, G
+    Major,
+    /// Minor triad: C, E♭, G
+    Minor,
+    /// Diminished triad: C, E♭, G♭
+    Diminished,
+    /// Augmented triad: C, E, G♯
+    Augmented,
+    /// Suspended second: C, D, G
+    Sus2,
+    /// Suspended fourth: C, F, G
+    Sus4,
+    /// Dominant seventh: C, E, G, B♭
+    Dominant7,
+    /// Major seventh: C, E, G, B
+    Major7,
+    /// Minor seventh: C, E♭, G, B♭
+    Minor7,
+    /// Minor triad with a major seventh: C, E♭, G, B
+    MinorMajor7,
+    /// Half-diminished seventh: C, E♭, G♭, B♭
+    HalfDiminished7,
+    /// Diminished seventh: C, E♭, G♭, B𝄫
+    Diminished7,
+}
+
+impl ChordQuality {
+    /// All qualities, triads first
+    pub const ALL: [ChordQuality; 12] = [
+        ChordQuality::Major,
+        ChordQuality::Minor,
+        ChordQuality::Diminished,
+        ChordQuality::Augmented,
+        ChordQuality::Sus2,
+        ChordQuality::Sus4,
+        ChordQuality::Dominant7,
+        ChordQuality::Major7,
+        ChordQuality::Minor7,
+        ChordQuality::MinorMajor7,
+        ChordQuality::HalfDiminished7,
+        ChordQuality::Diminished7,
+    ];
+
+    /// The intervals above the root, starting with the unison
+    #[must_use]
+    pub fn intervals(self) -> &'static [Interval] {
+        use Interval::{Aug5, Dim5, Dim7, Maj2, Maj3, Maj7, Min3, Min7, Unison, P4, P5};
+
+        match self {
+            ChordQuality::Major => &[Unison, Maj3, P5],
+            ChordQuality::Minor => &[Unison, Min3, P5],
+            ChordQuality::Diminished => &[Unison, Min3, Dim5],
+            ChordQuality::Augmented => &[Unison, Maj3, Aug5],
+            ChordQuality::Sus2 => &[Unison, Maj2, P5],
+            ChordQuality::Sus4 => &[Unison, P4, P5],
+            ChordQuality::Dominant7 => &[Unison, Maj3, P5, Min7],
+            ChordQuality::Major7 => &[Unison, Maj3, P5, Maj7],
+            ChordQuality::Minor7 => &[Unison, Min3, P5, Min7],
+            ChordQuality::MinorMajor7 => &[Unison, Min3, P5, Maj7],
+            ChordQuality::HalfDiminished7 => &[Unison, Min3, Dim5, Min7],
+            ChordQuality::Diminished7 => &[Unison, Min3, Dim5, Dim7],
+        }
+    }
+
+    /// The suffix of the chord symbol, either with Unicode glyphs or in ASCII
+    /// ```
+    /// # use tonality::chord::ChordQuality;
+    /// assert_eq!("ø7", ChordQuality::HalfDiminished7.symbol(false));
+    /// assert_eq!("m7b5", ChordQuality::HalfDiminished7.symbol(true));
+    /// ```
+    #[must_use]
+    pub fn symbol(self, ascii: bool) -> &'static str {
+        match (self, ascii) {
+            (ChordQuality::Major, _) => "",
+            (ChordQuality::Minor, _) => "m",
+            (ChordQuality::Diminished, false) => "°",
+            (ChordQuality::Diminished, true) => "dim",
+            (ChordQuality::Augmented, false) => "+",
+            (ChordQuality::Augmented, true) => "aug",
+            (ChordQuality::Sus2, _) => "sus2",
+            (ChordQuality::Sus4, _) => "sus4",
+            (ChordQuality::Dominant7, _) => "7",
+            (ChordQuality::Major7, _) => "maj7",
+            (ChordQuality::Minor7, _) => "m7",
+            (ChordQuality::MinorMajor7, _) => "m(maj7)",
+            (ChordQuality::HalfDiminished7, false) => "ø7",
+            (ChordQuality::HalfDiminished7, true) => "m7b5",
+            (ChordQuality::Diminished7, false) => "°7",
+            (ChordQuality::Diminished7, true) => "dim7",
+        }
+    }
+}
+
+/// A chord with a root and a quality, and a bass if it is inverted or is a
+/// slash chord over a note outside the chord
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[must_use]
+pub struct Chord {
+    /// The root of the chord
+    pub root: Tpc,
+    /// The quality of the chord
+    pub quality: ChordQuality,
+    /// The bass, if different from the root
+    pub bass: Option<Tpc>,
+}
+
+impl Chord {
+    /// A chord in root position
+    pub const fn new(root: Tpc, quality: ChordQuality) -> Self {
+        Self {
+            root,
+            quality,
+            bass: None,
+        }
+    }
+
+    /// The chord made up of the tones, with the first tone as the bass, or
+    /// None if no quality has exactly these tones above any of them. Repeated
+    /// tones are ignored.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// let chord = Chord::identify(&[Tpc::E, Tpc::C, Tpc::G, Tpc::C]).unwrap();
+    /// assert_eq!(Chord::new(Tpc::C, ChordQuality::Major).with_bass(Tpc::E), chord);
+    /// // Spelling matters: C, F flat and G is not a major triad
+    /// assert_eq!(None, Chord::identify(&[Tpc::C, Tpc::Fb, Tpc::G]));
+    /// ```
+    #[must_use]
+    pub fn identify(tones: &[Tpc]) -> Option<Self> {
+        let bass = *tones.first()?;
+        let mut unique: Vec<Tpc> = tones.to_vec();
+        unique.sort_by_key(|&tpc| tpc as i8);
+        unique.dedup();
+        let chord = unique.iter().find_map(|&root| {
+            let mut intervals: Vec<Interval> = unique
+                .iter()
+                .map(|&tone| root.try_interval_to(tone).ok())
+                .collect::<Option<_>>()?;
+            intervals.sort_by_key(|&interval| interval.semitones());
+            let quality = ChordQuality::ALL
+                .iter()
+                .copied()
+                .find(|quality| quality.intervals() == intervals.as_slice())?;
+            Some(Self::new(root, quality).with_bass(bass))
+        });
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?chord, "identified chord");
+        chord
+    }
+
+    /// The same chord over another bass. A bass equal to the root gives the
+    /// chord in root position.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// let chord = Chord::new(Tpc::D, ChordQuality::Minor7).with_bass(Tpc::G);
+    /// assert_eq!("Dm7/G", chord.to_string());
+    /// assert_eq!(None, chord.inversion());
+    /// ```
+    pub fn with_bass(self, bass: Tpc) -> Self {
+        Self {
+            bass: if bass == self.root { None } else { Some(bass) },
+            ..self
+        }
+    }
+
+    /// The same chord with the root and bass respelled by a policy. The
+    /// quality is unchanged, so the tones follow the new root.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// # use tonality::spelling::RespellPolicy;
+    /// let chord = Chord::new(Tpc::As, ChordQuality::Minor).with_bass(Tpc::Cs);
+    /// assert_eq!("B♭m/D♭", chord.respell(RespellPolicy::PreferFlats).to_string());
+    /// ```
+    pub fn respell(self, policy: RespellPolicy) -> Self {
+        Self {
+            root: self.root.respell(policy),
+            bass: self.bass.map(|bass| bass.respell(policy)),
+            ..self
+        }
+    }
+
+    /// The chord symbol with its root and bass spelled to fit a key, as
+    /// `Tpc::respell_in_key`. Another policy can override the key with
+    /// `ChordSymbol::with_policy`.
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// // The IV chord of G flat major has its root on C flat
+    /// let chord = Chord::new(Tpc::B, ChordQuality::Major7);
+    /// assert_eq!("C♭maj7", chord.symbol_in_key(Key::Gb).to_string());
+    /// assert_eq!("Bmaj7", chord.symbol_in_key(Key::E).to_string());
+    /// ```
+    pub fn symbol_in_key(self, key: Key) -> ChordSymbol {
+        ChordSymbol {
+            chord: self,
+            policy: RespellPolicy::KeyRelative(key),
+        }
+    }
+
+    /// The chord with one of its tones in the bass: 0 is root position, 1 is
+    /// the first inversion with the third in the bass, and so on. Returns None
+    /// if the chord has no such tone, or a tone is outside the domain of `Tpc`.
+    #[must_use]
+    pub fn with_inversion(self, inversion: usize) -> Option<Self> {
+        let bass = *self.tones()?.get(inversion)?;
+        Some(self.with_bass(bass))
+    }
+
+    /// The chord tones from the root up, or None if any of them is outside
+    /// the domain of `Tpc`
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// let chord = Chord::new(Tpc::B, ChordQuality::Diminished7);
+    /// assert_eq!(Some(vec![Tpc::B, Tpc::D, Tpc::F, Tpc::Ab]), chord.tones());
+    /// ```
+    #[must_use]
+    pub fn tones(self) -> Option<Vec<Tpc>> {
+        self.quality
+            .intervals()
+            .iter()
+            .map(|&interval| self.root + interval)
+            .collect()
+    }
+
+    /// The lowest note of the chord
+    pub fn bass(self) -> Tpc {
+        self.bass.unwrap_or(self.root)
+    }
+
+    /// Which chord tone is in the bass, as in `with_inversion`, or None if the
+    /// bass is not a chord tone
+    #[must_use]
+    pub fn inversion(self) -> Option<usize> {
+        let bass = self.bass();
+        self.tones()?.iter().position(|&tone| tone == bass)
+    }
+
+    /// The notes of the chord from the bass up. An inverted chord starts with
+    /// its bass and continues upwards through the chord tones, while the bass
+    /// of a slash chord is added below the chord in root position.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// let chord = Chord::new(Tpc::C, ChordQuality::Dominant7).with_bass(Tpc::Bb);
+    /// assert_eq!(Some(vec![Tpc::Bb, Tpc::C, Tpc::E, Tpc::G]), chord.tones_from_bass());
+    /// let chord = Chord::new(Tpc::C, ChordQuality::Major).with_bass(Tpc::D);
+    /// assert_eq!(Some(vec![Tpc::D, Tpc::C, Tpc::E, Tpc::G]), chord.tones_from_bass());
+    /// ```
+    #[must_use]
+    pub fn tones_from_bass(self) -> Option<Vec<Tpc>> {
+        let mut tones = self.tones()?;
+        match self.inversion() {
+            Some(inversion) => tones.rotate_left(inversion),
+            None => tones.insert(0, self.bass()),
+        }
+        Some(tones)
+    }
+
+    /// Spell the chord in closed position above its bass, which is placed in
+    /// the given octave. Returns None if a chord tone is outside the domain of
+    /// `Tpc`, or if the voicing would go above the highest octave.
+    #[must_use]
+    pub fn voicing(self, octave: i8) -> Option<Vec<Pitch>> {
+        let tones = self.tones_from_bass()?;
+        let mut voicing = Vec::with_capacity(tones.len());
+        let mut previous = Pitch::new(tones[0], octave);
+        voicing.push(previous);
+        for &tone in &tones[1..] {
+            previous = previous.next_above(tone)?;
+            voicing.push(previous);
+        }
+        Some(voicing)
+    }
+}
+
+/// Displayed as a chord symbol with the bass after a slash, using Unicode
+/// glyphs or ASCII characters with the alternate flag.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::chord::{Chord, ChordQuality};
+/// let chord = Chord::new(Tpc::Fs, ChordQuality::HalfDiminished7).with_bass(Tpc::E);
+/// assert_eq!("F♯ø7/E", chord.to_string());
+/// assert_eq!("F#m7b5/E", format!("{:#}", chord));
+/// ```
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.root, f)?;
+        f.write_str(self.quality.symbol(f.alternate()))?;
+        if let Some(bass) = self.bass {
+            f.write_str("/")?;
+            fmt::Display::fmt(&bass, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A chord symbol whose root and bass are spelled by a policy when
+/// displayed, as returned by `Chord::symbol_in_key`
+/// ```
+/// # use tonality::{Key, Tpc};
+/// # use tonality::chord::{Chord, ChordQuality};
+/// # use tonality::spelling::RespellPolicy;
+/// let symbol = Chord::new(Tpc::Cb, ChordQuality::Minor).symbol_in_key(Key::Gb);
+/// assert_eq!("C♭m", symbol.to_string());
+/// assert_eq!("Bm", symbol.with_policy(RespellPolicy::PreferSharps).to_string());
+/// assert_eq!("Cbm", format!("{:#}", symbol));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct ChordSymbol {
+    chord: Chord,
+    policy: RespellPolicy,
+}
+
+impl ChordSymbol {
+    /// The same symbol spelled by another policy instead of the key
+    pub fn with_policy(self, policy: RespellPolicy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// The chord as it is spelled in the symbol
+    pub fn chord(self) -> Chord {
+        self.chord.respell(self.policy)
+    }
+}
+
+impl fmt::Display for ChordSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.chord(), f)
+    }
+}
+
+/// The notes of a chord respelled as a whole: stacked in thirds where
+/// possible, then as close together on the line of fifths as possible, so
+/// that the thirds are major or minor, then with as few accidentals as
+/// possible, and then with as few notes as possible changed. Every note keeps
+/// its pitch class, and notes with the same pitch class are spelled the same.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::chord;
+/// let notes = [Tpc::C, Tpc::Ds, Tpc::G];
+/// assert_eq!(vec![Tpc::C, Tpc::Eb, Tpc::G], chord::respell_minimal(&notes));
+/// // G sharp is kept, as A flat would stack in thirds no better
+/// let notes = [Tpc::Bs, Tpc::E, Tpc::Gs];
+/// assert_eq!(vec![Tpc::C, Tpc::E, Tpc::Gs], chord::respell_minimal(&notes));
+/// ```
+#[must_use]
+pub fn respell_minimal(notes: &[Tpc]) -> Vec<Tpc> {
+    // Each spelling within twelve fifths is a candidate, so that no two notes
+    // are more than an augmented or diminished interval apart
+    (Tpc::MIN as i8..=Tpc::MAX as i8 - Tpc::DELTA_ENHARMONIC + 1)
+        .map(|flattest| {
+            notes
+                .iter()
+                .map(|&tpc| {
+                    let offset = (tpc as i8 - flattest).rem_euclid(Tpc::DELTA_ENHARMONIC);
+                    Tpc::in_range(flattest + offset)
+                })
+                .collect::<Vec<_>>()
+        })
+        .min_by_key(|spelling| respelling_cost(notes, spelling))
+        .unwrap_or_default()
+}
+
+/// The cost of a spelling of notes, compared in order: the notes sharing a
+/// step, the length of the shortest chain of thirds holding the steps, the
+/// distance between the outermost notes on the line of fifths, the number of
+/// accidentals, and the number of notes changed from the original
+fn respelling_cost(original: &[Tpc], spelling: &[Tpc]) -> (usize, i8, i8, usize, usize) {
+    let mut tones = spelling.to_vec();
+    tones.sort_by_key(|&tpc| tpc as i8);
+    tones.dedup();
+    let mut steps: Vec<Step> = tones.iter().map(|&tpc| tpc.step()).collect();
+    steps.sort_by_key(|&step| step as i8);
+    steps.dedup();
+    // A step n steps above the root is at 4n mod 7 in the chain of thirds
+    let chain = steps
+        .iter()
+        .map(|&root| {
+            steps
+                .iter()
+                .map(|&step| root.diatonic_distance(step) * 4 % 7 + 1)
+                .max()
+                .unwrap_or(0)
+        })
+        .min()
+        .unwrap_or(0);
+    let span = match (tones.first(), tones.last()) {
+        (Some(&flattest), Some(&sharpest)) => sharpest as i8 - flattest as i8,
+        _ => 0,
+    };
+    let accidentals = tones
+        .iter()
+        .map(|&tpc| usize::from((tpc.accidental() as i8).unsigned_abs()))
+        .sum();
+    let changed = original
+        .iter()
+        .zip(spelling)
+        .filter(|(original, respelled)| original != respelled)
+        .count();
+    (tones.len() - steps.len(), chain, span, accidentals, changed)
+}
+
+/// A fixed number of intervals above a root, such as a chord template in a
+/// dictionary, kept on the stack instead of in a `Vec`
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::chord::IntervalStack;
+/// assert_eq!(Some([Tpc::D, Tpc::Fs, Tpc::A]), IntervalStack::MAJOR.apply(Tpc::D));
+/// let seventh = IntervalStack::DOMINANT7.apply(Tpc::Fs);
+/// assert_eq!(Some([Tpc::Fs, Tpc::As, Tpc::Cs, Tpc::E]), seventh);
+/// // The augmented fifth above A double sharp would be E triple sharp
+/// assert_eq!(None, IntervalStack::AUGMENTED.apply(Tpc::Ass));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct IntervalStack<const N: usize>(pub [Interval; N]);
+
+impl<const N: usize> IntervalStack<N> {
+    /// The stack of intervals above the root
+    pub const fn new(intervals: [Interval; N]) -> Self {
+        Self(intervals)
+    }
+
+    /// The intervals above the root
+    #[must_use]
+    pub const fn intervals(&self) -> &[Interval; N] {
+        &self.0
+    }
+
+    /// The notes at each interval above a root, or None if a note would be
+    /// beyond double sharp or double flat
+    #[must_use]
+    pub fn apply(self, root: Tpc) -> Option<[Tpc; N]> {
+        let mut tones = [root; N];
+        for (tone, &interval) in tones.iter_mut().zip(&self.0) {
+            *tone = (root + interval)?;
+        }
+        Some(tones)
+    }
+}
+
+impl IntervalStack<3> {
+    /// Major triad: C, E, G
+    pub const MAJOR: Self = Self([Interval::Unison, Interval::Maj3, Interval::P5]);
+    /// Minor triad: C, E♭, G
+    pub const MINOR: Self = Self([Interval::Unison, Interval::Min3, Interval::P5]);
+    /// Diminished triad: C, E♭, G♭
+    pub const DIMINISHED: Self = Self([Interval::Unison, Interval::Min3, Interval::Dim5]);
+    /// Augmented triad: C, E, G♯
+    pub const AUGMENTED: Self = Self([Interval::Unison, Interval::Maj3, Interval::Aug5]);
+    /// Suspended second: C, D, G
+    pub const SUS2: Self = Self([Interval::Unison, Interval::Maj2, Interval::P5]);
+    /// Suspended fourth: C, F, G
+    pub const SUS4: Self = Self([Interval::Unison, Interval::P4, Interval::P5]);
+}
+
+impl IntervalStack<4> {
+    /// Dominant seventh: C, E, G, B♭
+    pub const DOMINANT7: Self = Self([
+        Interval::Unison,
+        Interval::Maj3,
+        Interval::P5,
+        Interval::Min7,
+    ]);
+    /// Major seventh: C, E, G, B
+    pub const MAJOR7: Self = Self([
+        Interval::Unison,
+        Interval::Maj3,
+        Interval::P5,
+        Interval::Maj7,
+    ]);
+    /// Minor seventh: C, E♭, G, B♭
+    pub const MINOR7: Self = Self([
+        Interval::Unison,
+        Interval::Min3,
+        Interval::P5,
+        Interval::Min7,
+    ]);
+    /// Minor triad with a major seventh: C, E♭, G, B
+    pub const MINOR_MAJOR7: Self = Self([
+        Interval::Unison,
+        Interval::Min3,
+        Interval::P5,
+        Interval::Maj7,
+    ]);
+    /// Half-diminished seventh: C, E♭, G♭, B♭
+    pub const HALF_DIMINISHED7: Self = Self([
+        Interval::Unison,
+        Interval::Min3,
+        Interval::Dim5,
+        Interval::Min7,
+    ]);
+    /// Diminished seventh: C, E♭, G♭, B𝄫
+    pub const DIMINISHED7: Self = Self([
+        Interval::Unison,
+        Interval::Min3,
+        Interval::Dim5,
+        Interval::Dim7,
+    ]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respell_minimal() {
+        // D flat major has fewer accidentals than C sharp major
+        let notes = [Tpc::Cs, Tpc::F, Tpc::Gs];
+        assert_eq!(vec![Tpc::Db, Tpc::F, Tpc::Ab], respell_minimal(&notes));
+        // A diminished seventh on G sharp is stacked in thirds as well
+        let diminished = [Tpc::B, Tpc::D, Tpc::F, Tpc::Gs];
+        assert_eq!(diminished.to_vec(), respell_minimal(&diminished));
+        // Notes of the same pitch class are spelled alike
+        let doubled = [Tpc::Ds, Tpc::C, Tpc::Eb, Tpc::G];
+        let respelled = vec![Tpc::Eb, Tpc::C, Tpc::Eb, Tpc::G];
+        assert_eq!(respelled, respell_minimal(&doubled));
+        // Two notes on the same step are avoided
+        assert_eq!(vec![Tpc::C, Tpc::Db], respell_minimal(&[Tpc::C, Tpc::Cs]));
+        assert_eq!(Vec::<Tpc>::new(), respell_minimal(&[]));
+        // Spellings which are already stacked in thirds are kept
+        let chord = Chord::new(Tpc::Fs, ChordQuality::Dominant7);
+        let tones = chord.tones().unwrap();
+        assert_eq!(tones, respell_minimal(&tones));
+    }
+
+    #[test]
+    fn test_interval_stacks_match_qualities() {
+        let triads = [
+            IntervalStack::MAJOR,
+            IntervalStack::MINOR,
+            IntervalStack::DIMINISHED,
+            IntervalStack::AUGMENTED,
+            IntervalStack::SUS2,
+            IntervalStack::SUS4,
+        ];
+        let sevenths = [
+            IntervalStack::DOMINANT7,
+            IntervalStack::MAJOR7,
+            IntervalStack::MINOR7,
+            IntervalStack::MINOR_MAJOR7,
+            IntervalStack::HALF_DIMINISHED7,
+            IntervalStack::DIMINISHED7,
+        ];
+        let stacks = triads
+            .iter()
+            .map(|stack| &stack.intervals()[..])
+            .chain(sevenths.iter().map(|stack| &stack.intervals()[..]));
+        for (intervals, quality) in stacks.zip(ChordQuality::ALL.iter()) {
+            assert_eq!(quality.intervals(), intervals);
+        }
+        let chord = Chord::new(Tpc::Eb, ChordQuality::Minor7);
+        assert_eq!(
+            chord.tones(),
+            IntervalStack::MINOR7
+                .apply(Tpc::Eb)
+                .map(|tones| tones.to_vec())
+        );
+    }
+
+    #[test]
+    fn test_inversions() {
+        let chord = Chord::new(Tpc::G, ChordQuality::Dominant7);
+        for inversion in 0..4 {
+            let inverted = chord.with_inversion(inversion).unwrap();
+            assert_eq!(Some(inversion), inverted.inversion());
+        }
+        assert_eq!(None, chord.with_inversion(4));
+        assert_eq!(Tpc::F, chord.with_inversion(3).unwrap().bass());
+    }
+
+    #[test]
+    fn test_inverted_voicing() {
+        let chord = Chord::new(Tpc::Ab, ChordQuality::Major7)
+            .with_inversion(2)
+            .unwrap();
+        let expected = vec![
+            Pitch::new(Tpc::Eb, 3),
+            Pitch::new(Tpc::G, 3),
+            Pitch::new(Tpc::Ab, 3),
+            Pitch::new(Tpc::C, 4),
+        ];
+        assert_eq!(Some(expected), chord.voicing(3));
+        // The root is above the highest octave
+        assert_eq!(None, chord.voicing(i8::MAX));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let chord = Chord::new(Tpc::Bss, ChordQuality::Augmented);
+        assert_eq!(None, chord.tones());
+        assert_eq!(None, chord.with_inversion(0));
+    }
+
+    #[test]
+    fn test_symbols_in_flat_keys() {
+        let chord = Chord::new(Tpc::B, ChordQuality::Major).with_bass(Tpc::Ds);
+        assert_eq!("C♭/E♭", chord.symbol_in_key(Key::Gb).to_string());
+        assert_eq!("B/D♯", chord.symbol_in_key(Key::B).to_string());
+        let spelled = chord.symbol_in_key(Key::Cb).chord();
+        assert_eq!(Some(vec![Tpc::Cb, Tpc::Eb, Tpc::Gb]), spelled.tones());
+        assert_eq!(Some(1), spelled.inversion());
+    }
+}
@@ -0,0 +1,141 @@
+//! Clefs and the positions of notes on the staff
+//!
+//! A staff position counts lines and spaces from the bottom line of a five
+//! line staff: 0 is the bottom line, 1 the space above it, and 8 the top line.
+//! Notes on ledger lines below the staff have negative positions.
+//! ```
+//! # use tonality::{Pitch, Step, Tpc};
+//! # use tonality::clef::Clef;
+//! // Middle C is on the first ledger line below the treble staff
+//! assert_eq!(-2, Clef::Treble.position(Step::C, 4));
+//! // and on the middle line of the alto staff
+//! assert_eq!(4, Clef::Alto.pitch_position(Pitch::new(Tpc::Cs, 4)));
+//! assert_eq!(Some((Step::C, 4)), Clef::Alto.at_position(4));
+//! ```
+use std::convert::TryFrom;
+
+use crate::repr::Contiguous;
+use crate::{Pitch, Step};
+
+/// A clef, which fixes the note on each line and space of the staff
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub enum Clef {
+    /// G clef on the second line
+    Treble,
+    /// Treble clef sounding an octave lower, as for tenor voices and guitar
+    TrebleOctaveDown,
+    /// Treble clef sounding an octave higher, as for piccolo
+    TrebleOctaveUp,
+    /// F clef on the fourth line
+    Bass,
+    /// Bass clef sounding an octave lower, as for double bass
+    BassOctaveDown,
+    /// C clef on the middle line, as for viola
+    Alto,
+    /// C clef on the fourth line, as for the high register of cello and
+    /// bassoon
+    Tenor,
+}
+
+impl Clef {
+    /// The steps from C0 up to the bottom line of the staff
+    fn bottom_line(self) -> i16 {
+        let (step, octave) = match self {
+            Clef::Treble => (Step::E, 4),
+            Clef::TrebleOctaveDown => (Step::E, 3),
+            Clef::TrebleOctaveUp => (Step::E, 5),
+            Clef::Bass => (Step::G, 2),
+            Clef::BassOctaveDown => (Step::G, 1),
+            Clef::Alto => (Step::F, 3),
+            Clef::Tenor => (Step::D, 3),
+        };
+        diatonic_index(step, octave)
+    }
+
+    /// The staff position of a step in an octave, numbered in scientific
+    /// pitch notation as in the `octave` module. Ledger lines may reach
+    /// octaves outside the range of `Octave`.
+    /// ```
+    /// # use tonality::Step;
+    /// # use tonality::clef::Clef;
+    /// assert_eq!(8, Clef::Treble.position(Step::F, 5));
+    /// assert_eq!(10, Clef::Bass.position(Step::C, 4));
+    /// ```
+    #[must_use]
+    pub fn position(self, step: Step, octave: i8) -> i16 {
+        diatonic_index(step, octave) - self.bottom_line()
+    }
+
+    /// The staff position of a written pitch. The accidental does not move
+    /// the note on the staff.
+    #[must_use]
+    pub fn pitch_position(self, pitch: Pitch) -> i16 {
+        self.position(pitch.step(), pitch.octave)
+    }
+
+    /// The step and octave at a staff position, or None if the octave would be
+    /// outside the range of `i8`
+    /// ```
+    /// # use tonality::Step;
+    /// # use tonality::clef::Clef;
+    /// assert_eq!(Some((Step::G, 2)), Clef::Bass.at_position(0));
+    /// assert_eq!(Some((Step::B, 3)), Clef::Tenor.at_position(5));
+    /// ```
+    #[must_use]
+    pub fn at_position(self, position: i16) -> Option<(Step, i8)> {
+        let index = self.bottom_line().checked_add(position)?;
+        // Between 0 and 6
+        #[allow(clippy::cast_possible_truncation)]
+        let step = Step::in_range(index.rem_euclid(7) as i8);
+        let octave = i8::try_from(index.div_euclid(7)).ok()?;
+        Some((step, octave))
+    }
+}
+
+/// The steps from C0 up to a step in an octave
+fn diatonic_index(step: Step, octave: i8) -> i16 {
+    i16::from(octave) * 7 + step as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLEFS: [Clef; 7] = [
+        Clef::Treble,
+        Clef::TrebleOctaveDown,
+        Clef::TrebleOctaveUp,
+        Clef::Bass,
+        Clef::BassOctaveDown,
+        Clef::Alto,
+        Clef::Tenor,
+    ];
+
+    #[test]
+    fn test_roundtrip() {
+        for &clef in &CLEFS {
+            for position in -20..30 {
+                let (step, octave) = clef.at_position(position).unwrap();
+                assert_eq!(position, clef.position(step, octave));
+            }
+        }
+    }
+
+    #[test]
+    fn test_octave_range() {
+        assert_eq!(None, Clef::Treble.at_position(i16::MAX));
+        assert_eq!(None, Clef::Bass.at_position(i16::MIN));
+        let (step, octave) = Clef::Treble.at_position(-800).unwrap();
+        assert_eq!(-800, Clef::Treble.position(step, octave));
+    }
+
+    #[test]
+    fn test_clefs_share_middle_c() {
+        // The grand staff: middle C is a ledger line between the staves
+        assert_eq!(-2, Clef::Treble.position(Step::C, 4));
+        assert_eq!(10, Clef::Bass.position(Step::C, 4));
+        // The tenor C clef puts middle C on the fourth line
+        assert_eq!(6, Clef::Tenor.position(Step::C, 4));
+    }
+}
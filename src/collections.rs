@@ -0,0 +1,412 @@
+//! Maps and sets of tpcs, backed by arrays indexed by the line of fifths
+//!
+//! There are only 35 tpcs, so a `TpcMap` holds a slot for each of them and a
+//! `TpcSet` is a bit set. Neither allocates or hashes, and both iterate
+//! either along the line of fifths or in chromatic order.
+//! ```
+//! # use tonality::Tpc;
+//! # use tonality::collections::{TpcMap, TpcSet};
+//! let mut counts = TpcMap::new();
+//! for &tpc in &[Tpc::E, Tpc::C, Tpc::E, Tpc::G] {
+//!     *counts.get_or_insert(tpc, 0) += 1;
+//! }
+//! let counts: Vec<(Tpc, &i32)> = counts.iter().collect();
+//! assert_eq!(vec![(Tpc::C, &1), (Tpc::G, &1), (Tpc::E, &2)], counts);
+//!
+//! let triad: TpcSet = [Tpc::G, Tpc::C, Tpc::E].iter().copied().collect();
+//! let chromatic: Vec<Tpc> = triad.iter_chromatic().collect();
+//! assert_eq!(vec![Tpc::C, Tpc::E, Tpc::G], chromatic);
+//! ```
+use std::fmt;
+use std::iter::FromIterator;
+
+use crate::Tpc;
+
+/// Every tpc in chromatic order, from C, with enharmonic tpcs in order along
+/// the line of fifths
+#[rustfmt::skip]
+const CHROMATIC: [Tpc; 35] = [
+    Tpc::Dbb, Tpc::C,   Tpc::Bs,
+    Tpc::Db,  Tpc::Cs,  Tpc::Bss,
+    Tpc::Ebb, Tpc::D,   Tpc::Css,
+    Tpc::Fbb, Tpc::Eb,  Tpc::Ds,
+    Tpc::Fb,  Tpc::E,   Tpc::Dss,
+    Tpc::Gbb, Tpc::F,   Tpc::Es,
+    Tpc::Gb,  Tpc::Fs,  Tpc::Ess,
+    Tpc::Abb, Tpc::G,   Tpc::Fss,
+    Tpc::Ab,  Tpc::Gs,
+    Tpc::Bbb, Tpc::A,   Tpc::Gss,
+    Tpc::Cbb, Tpc::Bb,  Tpc::As,
+    Tpc::Cb,  Tpc::B,   Tpc::Ass,
+];
+
+/// A map from tpcs to values, with a slot for every tpc
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::collections::TpcMap;
+/// let mut names = TpcMap::new();
+/// assert_eq!(None, names.insert(Tpc::Bb, "B"));
+/// assert_eq!(Some("B"), names.insert(Tpc::Bb, "Bes"));
+/// assert_eq!(Some(&"Bes"), names.get(Tpc::Bb));
+/// assert_eq!(None, names.get(Tpc::B));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TpcMap<V> {
+    slots: [Option<V>; 35],
+}
+
+impl<V> TpcMap<V> {
+    /// An empty map
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            slots: std::array::from_fn(|_| None),
+        }
+    }
+
+    /// The value of a tpc
+    #[must_use]
+    pub fn get(&self, tpc: Tpc) -> Option<&V> {
+        self.slots[tpc.index()].as_ref()
+    }
+
+    /// The value of a tpc, mutably
+    #[must_use]
+    pub fn get_mut(&mut self, tpc: Tpc) -> Option<&mut V> {
+        self.slots[tpc.index()].as_mut()
+    }
+
+    /// The value of a tpc, after inserting a default if there was none
+    pub fn get_or_insert(&mut self, tpc: Tpc, default: V) -> &mut V {
+        self.get_or_insert_with(tpc, || default)
+    }
+
+    /// The value of a tpc, after inserting the result of a function if there
+    /// was none
+    pub fn get_or_insert_with<F>(&mut self, tpc: Tpc, default: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        self.slots[tpc.index()].get_or_insert_with(default)
+    }
+
+    /// Set the value of a tpc. Returns the previous value.
+    pub fn insert(&mut self, tpc: Tpc, value: V) -> Option<V> {
+        self.slots[tpc.index()].replace(value)
+    }
+
+    /// Remove the value of a tpc. Returns the removed value.
+    pub fn remove(&mut self, tpc: Tpc) -> Option<V> {
+        self.slots[tpc.index()].take()
+    }
+
+    /// Whether the tpc has a value
+    #[must_use]
+    pub fn contains_key(&self, tpc: Tpc) -> bool {
+        self.get(tpc).is_some()
+    }
+
+    /// The number of tpcs with values
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Whether no tpc has a value
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Remove every value
+    pub fn clear(&mut self) {
+        for slot in &mut self.slots {
+            *slot = None;
+        }
+    }
+
+    /// The tpcs with values, as a set
+    pub fn keys(&self) -> TpcSet {
+        self.iter().map(|(tpc, _)| tpc).collect()
+    }
+
+    /// The tpcs and their values along the line of fifths, from the flattest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Tpc, &V)> + '_ {
+        Tpc::LINE_OF_FIFTHS
+            .iter()
+            .zip(&self.slots)
+            .filter_map(|(&tpc, slot)| Some((tpc, slot.as_ref()?)))
+    }
+
+    /// The tpcs and their values along the line of fifths, with the values
+    /// mutable
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (Tpc, &mut V)> + '_ {
+        Tpc::LINE_OF_FIFTHS
+            .iter()
+            .zip(&mut self.slots)
+            .filter_map(|(&tpc, slot)| Some((tpc, slot.as_mut()?)))
+    }
+
+    /// The tpcs and their values in chromatic order, from C, with enharmonic
+    /// tpcs along the line of fifths
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::collections::TpcMap;
+    /// let map: TpcMap<u8> = vec![(Tpc::Gs, 1), (Tpc::Ab, 2), (Tpc::D, 3)].into_iter().collect();
+    /// let tpcs: Vec<Tpc> = map.iter_chromatic().map(|(tpc, _)| tpc).collect();
+    /// assert_eq!(vec![Tpc::D, Tpc::Ab, Tpc::Gs], tpcs);
+    /// ```
+    pub fn iter_chromatic(&self) -> impl DoubleEndedIterator<Item = (Tpc, &V)> + '_ {
+        CHROMATIC
+            .iter()
+            .filter_map(move |&tpc| Some((tpc, self.get(tpc)?)))
+    }
+
+    /// The values along the line of fifths
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+        self.slots.iter().filter_map(Option::as_ref)
+    }
+}
+
+impl<V> Default for TpcMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for TpcMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<V> FromIterator<(Tpc, V)> for TpcMap<V> {
+    fn from_iter<I: IntoIterator<Item = (Tpc, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<V> Extend<(Tpc, V)> for TpcMap<V> {
+    fn extend<I: IntoIterator<Item = (Tpc, V)>>(&mut self, iter: I) {
+        for (tpc, value) in iter {
+            self.insert(tpc, value);
+        }
+    }
+}
+
+/// A set of tpcs, as a bit for every tpc
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::collections::TpcSet;
+/// let mut set = TpcSet::new();
+/// assert!(set.insert(Tpc::Fs));
+/// assert!(!set.insert(Tpc::Fs));
+/// assert!(set.contains(Tpc::Fs));
+/// assert!(!set.contains(Tpc::Gb));
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct TpcSet {
+    bits: u64,
+}
+
+impl TpcSet {
+    /// An empty set
+    pub const fn new() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// The set of every tpc
+    pub const fn all() -> Self {
+        Self {
+            bits: (1 << Tpc::LINE_OF_FIFTHS.len()) - 1,
+        }
+    }
+
+    fn bit(tpc: Tpc) -> u64 {
+        1 << tpc.index()
+    }
+
+    /// Add a tpc. Returns whether it was not already in the set.
+    pub fn insert(&mut self, tpc: Tpc) -> bool {
+        let added = !self.contains(tpc);
+        self.bits |= Self::bit(tpc);
+        added
+    }
+
+    /// Remove a tpc. Returns whether it was in the set.
+    pub fn remove(&mut self, tpc: Tpc) -> bool {
+        let removed = self.contains(tpc);
+        self.bits &= !Self::bit(tpc);
+        removed
+    }
+
+    /// Whether the tpc is in the set
+    #[must_use]
+    pub fn contains(self, tpc: Tpc) -> bool {
+        self.bits & Self::bit(tpc) != 0
+    }
+
+    /// The number of tpcs in the set
+    #[must_use]
+    pub const fn len(self) -> usize {
+        // At most 35
+        self.bits.count_ones() as usize
+    }
+
+    /// Whether the set is empty
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Remove every tpc
+    pub fn clear(&mut self) {
+        self.bits = 0;
+    }
+
+    /// The tpcs in either set
+    pub const fn union(self, other: TpcSet) -> Self {
+        Self {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    /// The tpcs in both sets
+    pub const fn intersection(self, other: TpcSet) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+
+    /// The tpcs in this set but not the other
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::collections::TpcSet;
+    /// let scale: TpcSet = [Tpc::C, Tpc::D, Tpc::E, Tpc::F].iter().copied().collect();
+    /// let triad: TpcSet = [Tpc::C, Tpc::E, Tpc::G].iter().copied().collect();
+    /// let passing: Vec<Tpc> = scale.difference(triad).iter().collect();
+    /// assert_eq!(vec![Tpc::F, Tpc::D], passing);
+    /// assert!(!triad.is_subset(scale));
+    /// ```
+    pub const fn difference(self, other: TpcSet) -> Self {
+        Self {
+            bits: self.bits & !other.bits,
+        }
+    }
+
+    /// Whether every tpc in this set is in the other
+    #[must_use]
+    pub const fn is_subset(self, other: TpcSet) -> bool {
+        self.bits & !other.bits == 0
+    }
+
+    /// The tpcs along the line of fifths, from the flattest
+    #[must_use]
+    pub fn iter(self) -> impl DoubleEndedIterator<Item = Tpc> {
+        Tpc::LINE_OF_FIFTHS
+            .iter()
+            .copied()
+            .filter(move |&tpc| self.contains(tpc))
+    }
+
+    /// The tpcs in chromatic order, from C, with enharmonic tpcs along the
+    /// line of fifths
+    #[must_use]
+    pub fn iter_chromatic(self) -> impl DoubleEndedIterator<Item = Tpc> {
+        CHROMATIC
+            .iter()
+            .copied()
+            .filter(move |&tpc| self.contains(tpc))
+    }
+}
+
+impl fmt::Debug for TpcSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Tpc> for TpcSet {
+    fn from_iter<I: IntoIterator<Item = Tpc>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Tpc> for TpcSet {
+    fn extend<I: IntoIterator<Item = Tpc>>(&mut self, iter: I) {
+        for tpc in iter {
+            self.insert(tpc);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chromatic_order() {
+        let mut sorted = Tpc::LINE_OF_FIFTHS;
+        sorted.sort_by_key(|&tpc| (tpc.pitch_class(), tpc));
+        assert_eq!(sorted, CHROMATIC);
+        let all: Vec<Tpc> = TpcSet::all().iter_chromatic().collect();
+        assert_eq!(CHROMATIC.to_vec(), all);
+    }
+
+    #[test]
+    fn test_map_matches_btree_map() {
+        let mut map = TpcMap::new();
+        let mut expected = std::collections::BTreeMap::new();
+        for (i, &tpc) in CHROMATIC.iter().enumerate().filter(|(i, _)| i % 3 != 1) {
+            assert_eq!(expected.insert(tpc, i), map.insert(tpc, i));
+        }
+        assert_eq!(expected.remove(&Tpc::C), map.remove(Tpc::C));
+        assert_eq!(expected.remove(&Tpc::C), map.remove(Tpc::C));
+        *map.get_or_insert(Tpc::Bss, 0) += 100;
+        *expected.entry(Tpc::Bss).or_insert(0) += 100;
+        let actual: Vec<(Tpc, &usize)> = map.iter().collect();
+        assert_eq!(
+            expected
+                .iter()
+                .map(|(&tpc, v)| (tpc, v))
+                .collect::<Vec<_>>(),
+            actual
+        );
+        assert_eq!(expected.len(), map.len());
+        assert_eq!(expected.len(), map.keys().len());
+        for (_, value) in map.iter_mut() {
+            *value = 0;
+        }
+        assert!(map.values().all(|&value| value == 0));
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(TpcMap::default(), map);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let flats: TpcSet = Tpc::LINE_OF_FIFTHS[..14].iter().copied().collect();
+        let naturals: TpcSet = Tpc::LINE_OF_FIFTHS[14..21].iter().copied().collect();
+        assert_eq!(21, flats.union(naturals).len());
+        assert!(flats.intersection(naturals).is_empty());
+        assert_eq!(flats, flats.union(naturals).difference(naturals));
+        assert!(naturals.is_subset(TpcSet::all()));
+        assert_eq!(35, TpcSet::all().len());
+        let mut set = naturals;
+        assert!(set.remove(Tpc::F));
+        assert!(!set.remove(Tpc::F));
+        assert_eq!(Some(Tpc::C), set.iter().next());
+        assert_eq!(
+            "{F, C, G}",
+            format!(
+                "{:?}",
+                naturals.intersection([Tpc::G, Tpc::C, Tpc::F].iter().copied().collect())
+            )
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Definitions of which intervals are consonant
+
+use crate::Interval;
+
+/// A set of intervals considered consonant. All other intervals are
+/// dissonant.
+///
+/// Different styles and voice pairs use different definitions: the perfect
+/// fourth is dissonant against the bass, but consonant between upper voices.
+/// ```
+/// # use tonality::Interval;
+/// # use tonality::consonance::ConsonanceTable;
+/// let table = ConsonanceTable::against_bass();
+/// assert!(table.is_dissonant(Interval::P4));
+/// let table = table.with_consonant(Interval::P4);
+/// assert!(table.is_consonant(Interval::P4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct ConsonanceTable {
+    consonant: [bool; Interval::NUM_OF as usize],
+}
+
+impl ConsonanceTable {
+    /// A table where every interval is dissonant
+    pub const fn empty() -> Self {
+        Self {
+            consonant: [false; Interval::NUM_OF as usize],
+        }
+    }
+
+    /// Common practice consonances against the bass: perfect unisons and
+    /// fifths, and major and minor thirds and sixths
+    pub fn against_bass() -> Self {
+        use Interval::{Maj3, Maj6, Min3, Min6, Unison, P5};
+
+        [Unison, P5, Maj3, Min3, Maj6, Min6]
+            .iter()
+            .fold(Self::empty(), |table, &interval| {
+                table.with_consonant(interval)
+            })
+    }
+
+    /// Common practice consonances between upper voices, which in addition to
+    /// the consonances against the bass include the perfect fourth
+    pub fn between_upper_voices() -> Self {
+        Self::against_bass().with_consonant(Interval::P4)
+    }
+
+    /// The same table, with the interval considered consonant
+    pub fn with_consonant(mut self, interval: Interval) -> Self {
+        self.consonant[Self::index(interval)] = true;
+        self
+    }
+
+    /// The same table, with the interval considered dissonant
+    pub fn with_dissonant(mut self, interval: Interval) -> Self {
+        self.consonant[Self::index(interval)] = false;
+        self
+    }
+
+    /// Whether the interval is consonant
+    #[must_use]
+    pub fn is_consonant(&self, interval: Interval) -> bool {
+        self.consonant[Self::index(interval)]
+    }
+
+    /// Whether the interval is dissonant
+    #[must_use]
+    pub fn is_dissonant(&self, interval: Interval) -> bool {
+        !self.is_consonant(interval)
+    }
+
+    fn index(interval: Interval) -> usize {
+        usize::from((interval as i8 - Interval::MIN as i8).unsigned_abs())
+    }
+}
+
+impl Default for ConsonanceTable {
+    fn default() -> Self {
+        Self::against_bass()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_against_bass() {
+        let table = ConsonanceTable::against_bass();
+        assert!(table.is_consonant(Interval::Unison));
+        assert!(table.is_consonant(Interval::Min6));
+        assert!(table.is_dissonant(Interval::Aug1));
+        assert!(table.is_dissonant(Interval::Dim5));
+        assert!(table.is_dissonant(Interval::Maj7));
+    }
+
+    #[test]
+    fn test_with_dissonant() {
+        let table = ConsonanceTable::between_upper_voices().with_dissonant(Interval::Maj6);
+        assert!(table.is_consonant(Interval::P4));
+        assert!(table.is_dissonant(Interval::Maj6));
+    }
+}
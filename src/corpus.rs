@@ -0,0 +1,140 @@
+//! Statistics over a corpus of note sequences
+//!
+//! A `CorpusReport` adds up, over many sequences, the estimated keys, the
+//! accidentals, the melodic intervals and how consistently the notes are
+//! spelled for their keys. With the `serde` feature, the report can be
+//! serialized, with tpcs, keys and intervals in the format of the `wire`
+//! module.
+//! ```
+//! # use tonality::{Interval, Key, Tpc};
+//! # use tonality::corpus::CorpusReport;
+//! let corpus = vec![
+//!     vec![Tpc::G, Tpc::A, Tpc::B, Tpc::C, Tpc::D, Tpc::E, Tpc::Fs, Tpc::G],
+//!     vec![Tpc::F, Tpc::A, Tpc::C, Tpc::As],
+//! ];
+//! let report = CorpusReport::from_sequences(corpus);
+//! assert_eq!(2, report.sequences);
+//! assert_eq!(12, report.notes);
+//! assert_eq!(Some(&1), report.keys.get(&Key::G));
+//! assert_eq!(Some(&5), report.intervals.get(&Interval::Maj2));
+//! // A sharp would be spelled B flat in F major
+//! assert_eq!(11, report.consistently_spelled);
+//! ```
+use std::collections::BTreeMap;
+
+use crate::analysis;
+use crate::{Interval, Key, Tpc};
+
+/// Aggregate statistics over a corpus of note sequences
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CorpusReport {
+    /// The number of sequences
+    pub sequences: usize,
+    /// The number of notes in all sequences
+    pub notes: usize,
+    /// The number of sequences in each estimated major key, as given by
+    /// `analysis::weighted_key` with every note weighted equally. Empty
+    /// sequences have no key.
+    pub keys: BTreeMap<Key, usize>,
+    /// The number of notes with each accidental, from double flat at index 0
+    /// to double sharp at index 4
+    pub accidentals: [usize; 5],
+    /// The number of times each interval occurs between consecutive notes of
+    /// a sequence
+    pub intervals: BTreeMap<Interval, usize>,
+    /// The number of consecutive notes with no interval between them, because
+    /// it would be more than augmented or diminished
+    pub unspellable_intervals: usize,
+    /// The number of notes spelled as `Tpc::respell_in_key` would spell them
+    /// in the estimated key of their sequence
+    pub consistently_spelled: usize,
+}
+
+impl CorpusReport {
+    /// The report over all sequences
+    #[must_use]
+    pub fn from_sequences<I, S>(sequences: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: IntoIterator<Item = Tpc>,
+    {
+        let mut report = Self::default();
+        for sequence in sequences {
+            report.add_sequence(sequence);
+        }
+        report
+    }
+
+    /// Add the statistics of one more sequence to the report
+    pub fn add_sequence<S>(&mut self, sequence: S)
+    where
+        S: IntoIterator<Item = Tpc>,
+    {
+        let notes: Vec<Tpc> = sequence.into_iter().collect();
+        self.sequences += 1;
+        self.notes += notes.len();
+        for &tpc in &notes {
+            // Between 0 and 4
+            #[allow(clippy::cast_sign_loss)]
+            let index = (tpc.accidental() as i8 + 2) as usize;
+            self.accidentals[index] += 1;
+        }
+        for interval in analysis::intervals(notes.iter().copied()) {
+            match interval {
+                Some(interval) => *self.intervals.entry(interval).or_insert(0) += 1,
+                None => self.unspellable_intervals += 1,
+            }
+        }
+        if let Some(key) = analysis::weighted_key(notes.iter().map(|&tpc| (tpc, 1.0))) {
+            *self.keys.entry(key).or_insert(0) += 1;
+            self.consistently_spelled += notes
+                .iter()
+                .filter(|tpc| tpc.respell_in_key(key) == **tpc)
+                .count();
+        }
+    }
+
+    /// The share of notes spelled consistently with their keys, from 0 to 1,
+    /// or None if there are no notes
+    #[must_use]
+    pub fn spelling_consistency(&self) -> Option<f64> {
+        if self.notes == 0 {
+            return None;
+        }
+        // Counts of notes are far below the precision of f64
+        #[allow(clippy::cast_precision_loss)]
+        let share = self.consistently_spelled as f64 / self.notes as f64;
+        Some(share)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_corpus() {
+        let report = CorpusReport::from_sequences(Vec::<Vec<Tpc>>::new());
+        assert_eq!(CorpusReport::default(), report);
+        assert_eq!(None, report.spelling_consistency());
+        let report = CorpusReport::from_sequences(vec![Vec::new()]);
+        assert_eq!(1, report.sequences);
+        assert!(report.keys.is_empty());
+    }
+
+    #[test]
+    fn test_counts_add_up() {
+        let corpus = vec![
+            vec![Tpc::Cb, Tpc::Eb, Tpc::Gb, Tpc::Fss],
+            vec![Tpc::Bbb, Tpc::C, Tpc::Ds],
+        ];
+        let report = CorpusReport::from_sequences(corpus);
+        assert_eq!(7, report.accidentals.iter().sum::<usize>());
+        assert_eq!([1, 3, 1, 1, 1], report.accidentals);
+        let intervals: usize = report.intervals.values().sum();
+        assert_eq!(5, intervals + report.unspellable_intervals);
+        assert_eq!(2, report.keys.values().sum::<usize>());
+        assert!(report.consistently_spelled <= report.notes);
+    }
+}
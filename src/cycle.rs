@@ -0,0 +1,86 @@
+//! Common interval cycles
+//!
+//! Each cycle goes once around the octave, respelling notes according to a
+//! policy where they would otherwise be outside the domain of `Tpc`. For
+//! cycles of any interval, see `Tpc::cycle` and `Tpc::wrapping_cycle`.
+//! ```
+//! # use tonality::Tpc;
+//! # use tonality::cycle;
+//! # use tonality::spelling::RespellPolicy;
+//! let policy = RespellPolicy::PreferFlats;
+//! let whole_tone: Vec<_> = cycle::whole_tone(Tpc::Db, policy).collect();
+//! assert_eq!(vec![Tpc::Db, Tpc::Eb, Tpc::F, Tpc::G, Tpc::A, Tpc::B], whole_tone);
+//! ```
+use crate::spelling::RespellPolicy;
+use crate::{Interval, Tpc};
+
+/// The twelve tpcs of the circle of fifths, starting with the given one
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::cycle;
+/// # use tonality::spelling::RespellPolicy;
+/// let circle: Vec<_> = cycle::fifths(Tpc::Ds, RespellPolicy::PreferFlats).collect();
+/// // B double sharp would be followed by F triple sharp, which is out of range
+/// assert_eq!(Tpc::Bss, circle[10]);
+/// assert_eq!(Tpc::Ab, circle[11]);
+/// ```
+pub fn fifths(start: Tpc, policy: RespellPolicy) -> impl Iterator<Item = Tpc> {
+    start.wrapping_cycle(Interval::P5, policy).take(12)
+}
+
+/// The twelve pitch classes ascending by semitones from the given tpc. Every
+/// note after the first is spelled according to the policy.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::cycle;
+/// # use tonality::spelling::RespellPolicy;
+/// let chromatic: Vec<_> = cycle::chromatic(Tpc::A, RespellPolicy::PreferSharps)
+///     .take(4)
+///     .collect();
+/// assert_eq!(vec![Tpc::A, Tpc::As, Tpc::B, Tpc::C], chromatic);
+/// ```
+pub fn chromatic(start: Tpc, policy: RespellPolicy) -> impl Iterator<Item = Tpc> {
+    let pitch_class = start.pitch_class();
+    std::iter::once(start)
+        .chain((1..12).map(move |semitones| Tpc::from_pitch_class(pitch_class + semitones, policy)))
+}
+
+/// The six tpcs of the whole-tone scale starting with the given one, each a
+/// major second above the previous
+pub fn whole_tone(start: Tpc, policy: RespellPolicy) -> impl Iterator<Item = Tpc> {
+    start.wrapping_cycle(Interval::Maj2, policy).take(6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_cycles() {
+        let policies = [
+            RespellPolicy::PreferSharps,
+            RespellPolicy::PreferFlats,
+            RespellPolicy::MinimizeAccidentals,
+        ];
+        for &policy in &policies {
+            for &start in &[Tpc::Fbb, Tpc::C, Tpc::Bss] {
+                let mut fifths: Vec<_> = fifths(start, policy).map(Tpc::pitch_class).collect();
+                fifths.sort_unstable();
+                assert_eq!((0..12).collect::<Vec<_>>(), fifths);
+                let mut chromatic: Vec<_> =
+                    chromatic(start, policy).map(Tpc::pitch_class).collect();
+                chromatic.sort_unstable();
+                assert_eq!((0..12).collect::<Vec<_>>(), chromatic);
+            }
+        }
+    }
+
+    #[test]
+    fn test_whole_tone_overflow() {
+        let policy = RespellPolicy::PreferSharps;
+        let whole_tone: Vec<_> = whole_tone(Tpc::Css, policy).collect();
+        // F triple sharp is out of range
+        let expected = vec![Tpc::Css, Tpc::Dss, Tpc::Ess, Tpc::Gs, Tpc::As, Tpc::Bs];
+        assert_eq!(expected, whole_tone);
+    }
+}
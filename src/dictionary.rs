@@ -0,0 +1,403 @@
+//! A dictionary of named chords, which applications can extend at runtime
+//!
+//! A `ChordTemplate` names a type of chord and lists its intervals above the
+//! root, in the order they are stacked in a closed voicing, so that the
+//! ninth of a ninth chord is voiced above its seventh. The built-in
+//! dictionary holds the qualities of `ChordQuality` and the common sixth,
+//! extended, suspended, added-note and altered chords. Templates registered
+//! by an application work with identification, symbols and voicing like the
+//! built-in ones.
+//! ```
+//! # use tonality::{Interval, Tpc};
+//! # use tonality::dictionary::{ChordDictionary, ChordTemplate};
+//! use Interval::*;
+//! let mut dictionary = ChordDictionary::builtin();
+//! let (root, template) = dictionary.identify(&[Tpc::G, Tpc::B, Tpc::D, Tpc::F, Tpc::A]).unwrap();
+//! assert_eq!("G9", template.symbol_for(root));
+//!
+//! let so_what = ChordTemplate::new("So What", "m11(no5)", &[Unison, P4, Min7, Min3, P5]);
+//! assert_eq!(None, dictionary.register(so_what));
+//! let (root, template) = dictionary.parse("Em11(no5)").unwrap();
+//! assert_eq!(Some(vec![Tpc::E, Tpc::A, Tpc::D, Tpc::G, Tpc::B]), template.tones(root));
+//! ```
+use crate::chord::ChordQuality;
+use crate::voicing::VoicingBuilder;
+use crate::{Interval, Tpc};
+
+/// A named type of chord, as intervals above its root
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChordTemplate {
+    /// The name of the chord type, such as "dominant ninth"
+    pub name: String,
+    /// The suffix of the chord symbol after the root, in ASCII, such as "9"
+    pub symbol: String,
+    /// The intervals above the root, starting with the unison, in the order
+    /// they are stacked in a closed voicing
+    pub intervals: Vec<Interval>,
+}
+
+impl ChordTemplate {
+    /// A template with a name, a symbol suffix and intervals above the root
+    #[must_use]
+    pub fn new(name: &str, symbol: &str, intervals: &[Interval]) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            intervals: intervals.to_vec(),
+        }
+    }
+
+    /// The template of a quality, with its ASCII symbol
+    /// ```
+    /// # use tonality::chord::ChordQuality;
+    /// # use tonality::dictionary::ChordTemplate;
+    /// let template = ChordTemplate::from_quality(ChordQuality::HalfDiminished7);
+    /// assert_eq!("half-diminished seventh", template.name);
+    /// assert_eq!("m7b5", template.symbol);
+    /// ```
+    #[must_use]
+    pub fn from_quality(quality: ChordQuality) -> Self {
+        let name = match quality {
+            ChordQuality::Major => "major",
+            ChordQuality::Minor => "minor",
+            ChordQuality::Diminished => "diminished",
+            ChordQuality::Augmented => "augmented",
+            ChordQuality::Sus2 => "suspended second",
+            ChordQuality::Sus4 => "suspended fourth",
+            ChordQuality::Dominant7 => "dominant seventh",
+            ChordQuality::Major7 => "major seventh",
+            ChordQuality::Minor7 => "minor seventh",
+            ChordQuality::MinorMajor7 => "minor major seventh",
+            ChordQuality::HalfDiminished7 => "half-diminished seventh",
+            ChordQuality::Diminished7 => "diminished seventh",
+        };
+        Self::new(name, quality.symbol(true), quality.intervals())
+    }
+
+    /// The chord tones above a root, in the order of the intervals, or None
+    /// if any of them is outside the domain of `Tpc`
+    #[must_use]
+    pub fn tones(&self, root: Tpc) -> Option<Vec<Tpc>> {
+        self.intervals
+            .iter()
+            .map(|&interval| root + interval)
+            .collect()
+    }
+
+    /// The chord symbol on a root, in ASCII
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::dictionary::ChordDictionary;
+    /// let dictionary = ChordDictionary::builtin();
+    /// assert_eq!("Bb13", dictionary.get("13").unwrap().symbol_for(Tpc::Bb));
+    /// ```
+    #[must_use]
+    pub fn symbol_for(&self, root: Tpc) -> String {
+        format!("{:#}{}", root, self.symbol)
+    }
+
+    /// A builder of voicings of the chord on a root
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::dictionary::ChordDictionary;
+    /// let dictionary = ChordDictionary::builtin();
+    /// let voicings = dictionary
+    ///     .get("add9")
+    ///     .unwrap()
+    ///     .voicings(Tpc::C)
+    ///     .range(Pitch::new(Tpc::C, 4), Pitch::new(Tpc::D, 5))
+    ///     .build()
+    ///     .unwrap();
+    /// let expected = vec![
+    ///     Pitch::new(Tpc::C, 4),
+    ///     Pitch::new(Tpc::E, 4),
+    ///     Pitch::new(Tpc::G, 4),
+    ///     Pitch::new(Tpc::D, 5),
+    /// ];
+    /// assert!(voicings.contains(&expected));
+    /// ```
+    pub fn voicings(&self, root: Tpc) -> VoicingBuilder {
+        VoicingBuilder::new(root, &self.intervals)
+    }
+
+    /// The intervals in a canonical order, without repetitions
+    fn interval_set(&self) -> Vec<Interval> {
+        let mut intervals = self.intervals.clone();
+        intervals.sort();
+        intervals.dedup();
+        intervals
+    }
+}
+
+/// Templates of chords, looked up by their symbols
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChordDictionary {
+    templates: Vec<ChordTemplate>,
+}
+
+impl ChordDictionary {
+    /// A dictionary with no templates
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in dictionary: the qualities of `ChordQuality` followed by
+    /// sixth, ninth, eleventh, thirteenth, suspended, added-note and altered
+    /// chords
+    #[must_use]
+    pub fn builtin() -> Self {
+        use Interval::{
+            Aug2, Aug4, Aug5, Dim5, Maj2, Maj3, Maj6, Maj7, Min2, Min3, Min6, Min7, Unison, P4, P5,
+        };
+
+        let mut dictionary = Self::new();
+        for &quality in &ChordQuality::ALL {
+            dictionary.register(ChordTemplate::from_quality(quality));
+        }
+        let extended: [(&str, &str, &[Interval]); 23] = [
+            ("major sixth", "6", &[Unison, Maj3, P5, Maj6]),
+            ("minor sixth", "m6", &[Unison, Min3, P5, Maj6]),
+            ("six nine", "6/9", &[Unison, Maj3, P5, Maj6, Maj2]),
+            ("added ninth", "add9", &[Unison, Maj3, P5, Maj2]),
+            ("minor added ninth", "madd9", &[Unison, Min3, P5, Maj2]),
+            (
+                "dominant seventh suspended fourth",
+                "7sus4",
+                &[Unison, P4, P5, Min7],
+            ),
+            (
+                "dominant ninth suspended fourth",
+                "9sus4",
+                &[Unison, P4, P5, Min7, Maj2],
+            ),
+            ("dominant ninth", "9", &[Unison, Maj3, P5, Min7, Maj2]),
+            ("major ninth", "maj9", &[Unison, Maj3, P5, Maj7, Maj2]),
+            ("minor ninth", "m9", &[Unison, Min3, P5, Min7, Maj2]),
+            (
+                "dominant eleventh",
+                "11",
+                &[Unison, Maj3, P5, Min7, Maj2, P4],
+            ),
+            ("minor eleventh", "m11", &[Unison, Min3, P5, Min7, Maj2, P4]),
+            (
+                "dominant thirteenth",
+                "13",
+                &[Unison, Maj3, P5, Min7, Maj2, Maj6],
+            ),
+            (
+                "major thirteenth",
+                "maj13",
+                &[Unison, Maj3, P5, Maj7, Maj2, Maj6],
+            ),
+            (
+                "minor thirteenth",
+                "m13",
+                &[Unison, Min3, P5, Min7, Maj2, P4, Maj6],
+            ),
+            (
+                "dominant seventh flat five",
+                "7b5",
+                &[Unison, Maj3, Dim5, Min7],
+            ),
+            ("augmented seventh", "7#5", &[Unison, Maj3, Aug5, Min7]),
+            (
+                "dominant seventh flat nine",
+                "7b9",
+                &[Unison, Maj3, P5, Min7, Min2],
+            ),
+            (
+                "dominant seventh sharp nine",
+                "7#9",
+                &[Unison, Maj3, P5, Min7, Aug2],
+            ),
+            (
+                "dominant seventh sharp eleven",
+                "7#11",
+                &[Unison, Maj3, P5, Min7, Aug4],
+            ),
+            (
+                "major seventh sharp eleven",
+                "maj7#11",
+                &[Unison, Maj3, P5, Maj7, Aug4],
+            ),
+            (
+                "dominant seventh flat thirteen",
+                "7b13",
+                &[Unison, Maj3, P5, Min7, Min6],
+            ),
+            (
+                "altered dominant",
+                "7alt",
+                &[Unison, Maj3, Min7, Min2, Aug2, Aug4, Min6],
+            ),
+        ];
+        for &(name, symbol, intervals) in &extended {
+            dictionary.register(ChordTemplate::new(name, symbol, intervals));
+        }
+        dictionary
+    }
+
+    /// Add a template, or replace the template with the same symbol. Returns
+    /// the replaced template.
+    /// ```
+    /// # use tonality::Interval;
+    /// # use tonality::dictionary::{ChordDictionary, ChordTemplate};
+    /// use Interval::*;
+    /// let mut dictionary = ChordDictionary::builtin();
+    /// // An eleventh chord without its third, as it is usually played
+    /// let eleventh = ChordTemplate::new("eleventh", "11", &[Unison, P5, Min7, Maj2, P4]);
+    /// let replaced = dictionary.register(eleventh).unwrap();
+    /// assert_eq!("dominant eleventh", replaced.name);
+    /// ```
+    pub fn register(&mut self, template: ChordTemplate) -> Option<ChordTemplate> {
+        if let Some(existing) = self
+            .templates
+            .iter_mut()
+            .find(|existing| existing.symbol == template.symbol)
+        {
+            return Some(std::mem::replace(existing, template));
+        }
+        self.templates.push(template);
+        None
+    }
+
+    /// The template with a symbol suffix
+    #[must_use]
+    pub fn get(&self, symbol: &str) -> Option<&ChordTemplate> {
+        self.templates
+            .iter()
+            .find(|template| template.symbol == symbol)
+    }
+
+    /// The templates, in the order they were first registered
+    #[must_use]
+    pub fn templates(&self) -> &[ChordTemplate] {
+        &self.templates
+    }
+
+    /// The root and template of a chord made up of the tones, or None if no
+    /// template has exactly these tones above any of them. Repeated tones are
+    /// ignored. The first tone is tried as the root before the others, so
+    /// that A, C, E, G is an A minor seventh rather than a C sixth, and the
+    /// first template that matches is chosen.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::dictionary::ChordDictionary;
+    /// let dictionary = ChordDictionary::builtin();
+    /// let (root, template) = dictionary.identify(&[Tpc::A, Tpc::C, Tpc::E, Tpc::G]).unwrap();
+    /// assert_eq!("Am7", template.symbol_for(root));
+    /// let (root, template) = dictionary.identify(&[Tpc::C, Tpc::E, Tpc::G, Tpc::A]).unwrap();
+    /// assert_eq!("C6", template.symbol_for(root));
+    /// ```
+    #[must_use]
+    pub fn identify(&self, tones: &[Tpc]) -> Option<(Tpc, &ChordTemplate)> {
+        let bass = *tones.first()?;
+        let mut unique: Vec<Tpc> = tones.to_vec();
+        unique.sort_by_key(|&tpc| (tpc != bass, tpc as i8));
+        unique.dedup();
+        let sets: Vec<Vec<Interval>> = self
+            .templates
+            .iter()
+            .map(ChordTemplate::interval_set)
+            .collect();
+        unique.iter().find_map(|&root| {
+            let mut intervals: Vec<Interval> = unique
+                .iter()
+                .map(|&tone| root.try_interval_to(tone).ok())
+                .collect::<Option<_>>()?;
+            intervals.sort();
+            let index = sets.iter().position(|set| *set == intervals)?;
+            Some((root, &self.templates[index]))
+        })
+    }
+
+    /// The root and template of an ASCII chord symbol, such as `"Ebmaj9"`, or
+    /// None if the symbol does not start with a note name followed by the
+    /// symbol suffix of a template
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::dictionary::ChordDictionary;
+    /// let dictionary = ChordDictionary::builtin();
+    /// let (root, template) = dictionary.parse("Bbm7b5").unwrap();
+    /// assert_eq!(Tpc::Bb, root);
+    /// assert_eq!("half-diminished seventh", template.name);
+    /// assert_eq!(None, dictionary.parse("Cmaj15"));
+    /// ```
+    #[must_use]
+    pub fn parse(&self, symbol: &str) -> Option<(Tpc, &ChordTemplate)> {
+        // The root is a step followed by sharps or flats, but a suffix may
+        // start with a flat too, so the longest root with a known suffix wins
+        let accidentals = symbol
+            .get(1..)?
+            .chars()
+            .take_while(|&c| c == '#' || c == 'b')
+            .count();
+        (1..=1 + accidentals).rev().find_map(|length| {
+            let root: Tpc = symbol.get(..length)?.parse().ok()?;
+            Some((root, self.get(symbol.get(length..)?)?))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_symbols_are_unique() {
+        let dictionary = ChordDictionary::builtin();
+        for (i, template) in dictionary.templates().iter().enumerate() {
+            assert_eq!(Some(template), dictionary.get(&template.symbol));
+            assert_eq!(Interval::Unison, template.intervals[0], "{}", template.name);
+            assert!(dictionary.templates()[..i]
+                .iter()
+                .all(|other| other.symbol != template.symbol));
+        }
+        assert_eq!(35, dictionary.templates().len());
+    }
+
+    #[test]
+    fn test_identify_roundtrip() {
+        let dictionary = ChordDictionary::builtin();
+        for template in dictionary.templates() {
+            let tones = template.tones(Tpc::D).unwrap();
+            let (root, identified) = dictionary.identify(&tones).unwrap();
+            // Identified as the same tones, possibly under another name
+            let mut expected = tones.clone();
+            expected.sort_by_key(|&tpc| tpc as i8);
+            let mut actual = identified.tones(root).unwrap();
+            actual.sort_by_key(|&tpc| tpc as i8);
+            actual.dedup();
+            assert_eq!(expected, actual, "{}", template.name);
+            let symbol = template.symbol_for(Tpc::D);
+            assert_eq!(Some((Tpc::D, template)), dictionary.parse(&symbol));
+        }
+    }
+
+    #[test]
+    fn test_same_as_chord_identify() {
+        let dictionary = ChordDictionary::builtin();
+        let tones = [Tpc::E, Tpc::C, Tpc::G, Tpc::Bb];
+        let chord = crate::chord::Chord::identify(&tones).unwrap();
+        let (root, template) = dictionary.identify(&tones).unwrap();
+        assert_eq!(chord.root, root);
+        assert_eq!(ChordTemplate::from_quality(chord.quality), *template);
+        assert_eq!(None, dictionary.identify(&[Tpc::C, Tpc::Cs]));
+        assert_eq!(None, ChordDictionary::new().identify(&tones));
+        assert_eq!(None, dictionary.identify(&[]));
+    }
+
+    #[test]
+    fn test_parse_flat_roots_and_suffixes() {
+        let dictionary = ChordDictionary::builtin();
+        let (root, template) = dictionary.parse("Bb7b9").unwrap();
+        assert_eq!((Tpc::Bb, "7b9"), (root, template.symbol.as_str()));
+        let (root, template) = dictionary.parse("Bbb").unwrap();
+        assert_eq!((Tpc::Bbb, ""), (root, template.symbol.as_str()));
+        assert_eq!(None, dictionary.parse(""));
+        assert_eq!(None, dictionary.parse("H7"));
+    }
+}
@@ -0,0 +1,196 @@
+//! Streams of notes with rests and unpitched notes
+//!
+//! Real scores and MIDI tracks interleave pitched notes with rests and
+//! percussion. An `Element` models all three. The functions of the
+//! `analysis` and `melody` modules take streams of elements as well as of
+//! tpcs or pitches, and `pitches` and `tpcs` take only the pitched notes of a
+//! stream.
+//!
+//! Unpitched notes are skipped. A rest breaks the line of a melody, so the
+//! interval from the last note before a rest to the first note after it is
+//! None. A rest at the start of a stream is ignored.
+//! ```
+//! # use tonality::{Interval, Key, Pitch, Tpc};
+//! # use tonality::analysis;
+//! # use tonality::element::{self, Element};
+//! let stream = vec![
+//!     Element::Pitched(Pitch::new(Tpc::D, 4)),
+//!     Element::Rest,
+//!     Element::Unpitched,
+//!     Element::Pitched(Pitch::new(Tpc::Fs, 4)),
+//!     Element::Pitched(Pitch::new(Tpc::A, 4)),
+//! ];
+//! let notes: Vec<Tpc> = element::tpcs(stream.iter().copied()).collect();
+//! assert_eq!(vec![Tpc::D, Tpc::Fs, Tpc::A], notes);
+//! assert_eq!(Some(Key::D), analysis::running_key(notes).last());
+//!
+//! let intervals: Vec<_> = analysis::intervals(stream.iter().copied()).collect();
+//! assert_eq!(vec![None, Some(Interval::Min3)], intervals);
+//! ```
+use crate::{Pitch, Tpc};
+
+/// An element of a stream of notes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub enum Element {
+    /// A note with a spelled pitch
+    Pitched(Pitch),
+    /// A note without a pitch, such as on a percussion staff
+    Unpitched,
+    /// A rest
+    Rest,
+}
+
+impl Element {
+    /// The pitch of a pitched note, or None for a rest or an unpitched note
+    #[must_use]
+    pub fn pitch(self) -> Option<Pitch> {
+        match self {
+            Element::Pitched(pitch) => Some(pitch),
+            Element::Unpitched | Element::Rest => None,
+        }
+    }
+
+    /// The tpc of a pitched note, or None for a rest or an unpitched note
+    #[must_use]
+    pub fn tpc(self) -> Option<Tpc> {
+        self.pitch().map(|pitch| pitch.tpc)
+    }
+
+    /// Whether the element is a rest
+    #[must_use]
+    pub fn is_rest(self) -> bool {
+        self == Element::Rest
+    }
+}
+
+impl From<Pitch> for Element {
+    fn from(pitch: Pitch) -> Self {
+        Element::Pitched(pitch)
+    }
+}
+
+/// A pitched note, or a rest where there is no pitch
+impl From<Option<Pitch>> for Element {
+    fn from(pitch: Option<Pitch>) -> Self {
+        pitch.map_or(Element::Rest, Element::Pitched)
+    }
+}
+
+/// A note of a stream, which may be a rest or lack a pitch
+pub trait Note: Copy {
+    /// The tpc of the note, or None for a rest or an unpitched note
+    fn tpc(self) -> Option<Tpc>;
+
+    /// Whether the note is a rest
+    fn is_rest(self) -> bool;
+}
+
+impl Note for Tpc {
+    fn tpc(self) -> Option<Tpc> {
+        Some(self)
+    }
+
+    fn is_rest(self) -> bool {
+        false
+    }
+}
+
+impl Note for Pitch {
+    fn tpc(self) -> Option<Tpc> {
+        Some(self.tpc)
+    }
+
+    fn is_rest(self) -> bool {
+        false
+    }
+}
+
+impl Note for Element {
+    fn tpc(self) -> Option<Tpc> {
+        Element::tpc(self)
+    }
+
+    fn is_rest(self) -> bool {
+        Element::is_rest(self)
+    }
+}
+
+/// Each value of a stream paired with the one before it, or None where a
+/// rest comes between them. Notes without a value are skipped. Yields one
+/// item less than the values.
+pub(crate) fn pairs<N, T, F>(notes: N, value: F) -> impl Iterator<Item = Option<(T, T)>>
+where
+    N: IntoIterator,
+    N::Item: Note,
+    T: Copy,
+    F: Fn(N::Item) -> Option<T>,
+{
+    // None before the first value, and Some(None) after a rest
+    let mut previous: Option<Option<T>> = None;
+    notes.into_iter().filter_map(move |note| {
+        if note.is_rest() {
+            previous = previous.map(|_| None);
+            return None;
+        }
+        let current = value(note)?;
+        let pair = previous.map(|previous| previous.map(|previous| (previous, current)));
+        previous = Some(Some(current));
+        pair
+    })
+}
+
+/// The pitches of the pitched notes of a stream, skipping rests and unpitched
+/// notes
+pub fn pitches<I>(elements: I) -> impl Iterator<Item = Pitch>
+where
+    I: IntoIterator<Item = Element>,
+{
+    elements.into_iter().filter_map(Element::pitch)
+}
+
+/// The tpcs of the pitched notes of a stream, skipping rests and unpitched
+/// notes
+pub fn tpcs<I>(elements: I) -> impl Iterator<Item = Tpc>
+where
+    I: IntoIterator<Item = Element>,
+{
+    elements.into_iter().filter_map(Element::tpc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let pitch = Pitch::new(Tpc::Bb, 3);
+        assert_eq!(Element::Pitched(pitch), Element::from(pitch));
+        assert_eq!(Element::Rest, Element::from(None));
+        assert!(Element::from(None).is_rest());
+        assert_eq!(None, Element::Unpitched.pitch());
+        assert!(!Element::Unpitched.is_rest());
+        let stream = [Element::Rest, pitch.into(), Element::Unpitched];
+        assert_eq!(
+            vec![pitch],
+            pitches(stream.iter().copied()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_rests_break_pairs() {
+        let (c, d) = (Pitch::new(Tpc::C, 4), Pitch::new(Tpc::D, 4));
+        let stream = [
+            Element::Rest,
+            c.into(),
+            Element::Unpitched,
+            d.into(),
+            Element::Rest,
+            Element::Rest,
+            c.into(),
+        ];
+        let pairs: Vec<_> = pairs(stream.iter().copied(), Element::pitch).collect();
+        assert_eq!(vec![Some((c, d)), None], pairs);
+        assert_eq!(0, super::pairs(vec![Element::Rest], Element::pitch).count());
+    }
+}
@@ -0,0 +1,309 @@
+//! Errors from operations leaving the domain of a type
+use std::fmt;
+
+use num_traits::FromPrimitive;
+
+use crate::wire::WireFormat;
+use crate::{Interval, Key, Tpc};
+
+/// The types whose values are positions on the line of fifths, and the
+/// alterations that an `Accidental` can write
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Domain {
+    Tpc,
+    Interval,
+    Key,
+    /// Alterations from -2 to 2, where the value of an error is the
+    /// alteration, not a position on the line of fifths
+    Accidental,
+}
+
+impl Domain {
+    /// The flattest and sharpest valid values in the domain
+    pub(crate) fn bounds(self) -> (i8, i8) {
+        match self {
+            Domain::Tpc => (crate::Tpc::MIN as i8, crate::Tpc::MAX as i8),
+            Domain::Interval => (crate::Interval::MIN as i8, crate::Interval::MAX as i8),
+            Domain::Key => (crate::Key::MIN as i8, crate::Key::MAX as i8),
+            Domain::Accidental => (
+                crate::Accidental::DblFlat as i8,
+                crate::Accidental::DblSharp as i8,
+            ),
+        }
+    }
+}
+
+/// A value in range to use instead of a result that was out of range
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Suggestion {
+    Tpc(Tpc),
+    Interval(Interval),
+    Key(Key),
+}
+
+/// The error type for checked operations
+///
+/// The error displays what was out of range and in which direction, and the
+/// enharmonic value in range, which `suggestion` also gives.
+/// ```
+/// # use tonality::{Error, Interval, Tpc};
+/// # use tonality::error::Suggestion;
+/// let err = Tpc::Dss.try_add(Interval::Maj3).unwrap_err();
+/// assert!(err.is_sharpwards());
+/// assert_eq!(Some(Suggestion::Tpc(Tpc::Gs)), err.suggestion());
+/// assert_eq!(
+///     "tpc 20 on the line of fifths is beyond double sharps; its enharmonic G♯ is in range",
+///     err.to_string()
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The result was outside the domain of its type. The value is the
+    /// position on the line of fifths that the result would have had, or the
+    /// alteration for `Domain::Accidental`.
+    OutOfRange {
+        /// The position on the line of fifths
+        value: i8,
+        /// The type of the result
+        domain: Domain,
+    },
+    /// An octave number from another crate does not fit the `i8` octave of a
+    /// `Pitch`
+    OctaveOutOfRange {
+        /// The octave number
+        octave: i32,
+    },
+}
+
+impl Error {
+    /// Whether the operation failed because the result was too sharp, i.e.
+    /// too far up the line of fifths
+    #[must_use]
+    pub fn is_sharpwards(self) -> bool {
+        match self {
+            Error::OutOfRange { value, domain } => value > domain.bounds().1,
+            Error::OctaveOutOfRange { .. } => false,
+        }
+    }
+
+    /// Whether the operation failed because the result was too flat, i.e.
+    /// too far down the line of fifths
+    #[must_use]
+    pub fn is_flatwards(self) -> bool {
+        match self {
+            Error::OutOfRange { value, domain } => value < domain.bounds().0,
+            Error::OctaveOutOfRange { .. } => false,
+        }
+    }
+
+    /// The enharmonic value nearest to the result that is in the domain, or
+    /// None for an alteration, which has no enharmonic accidental, and for an
+    /// octave
+    /// ```
+    /// # use tonality::{Interval, Key};
+    /// # use tonality::error::Suggestion;
+    /// // G sharp major would have eight sharps
+    /// let err = Key::Cs.transposed(Interval::P5).unwrap_err();
+    /// assert_eq!(Some(Suggestion::Key(Key::Ab)), err.suggestion());
+    /// ```
+    #[must_use]
+    pub fn suggestion(self) -> Option<Suggestion> {
+        let Error::OutOfRange { value, domain } = self else {
+            return None;
+        };
+        let (flattest, sharpest) = domain.bounds();
+        let (value, flattest, sharpest) =
+            (i16::from(value), i16::from(flattest), i16::from(sharpest));
+        let delta = i16::from(Tpc::DELTA_ENHARMONIC);
+        // Each domain spans more than twelve fifths
+        let value = if value > sharpest {
+            value - (value - sharpest + delta - 1) / delta * delta
+        } else if value < flattest {
+            value + (flattest - value + delta - 1) / delta * delta
+        } else {
+            value
+        };
+        match domain {
+            Domain::Tpc => Tpc::from_i16(value).map(Suggestion::Tpc),
+            Domain::Interval => Interval::from_i16(value).map(Suggestion::Interval),
+            Domain::Key => Key::from_i16(value).map(Suggestion::Key),
+            Domain::Accidental => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (value, domain) = match *self {
+            Error::OutOfRange { value, domain } => (value, domain),
+            Error::OctaveOutOfRange { octave } => {
+                return write!(f, "octave {octave} is outside the range of i8");
+            }
+        };
+        // Callers can build an error with a value in range, which is then
+        // described as such, without a suggestion
+        let direction = if self.is_sharpwards() {
+            Some(true)
+        } else if self.is_flatwards() {
+            Some(false)
+        } else {
+            None
+        };
+        match (domain, direction) {
+            (Domain::Tpc, Some(true)) => write!(
+                f,
+                "tpc {value} on the line of fifths is beyond double sharps"
+            ),
+            (Domain::Tpc, Some(false)) => write!(
+                f,
+                "tpc {value} on the line of fifths is beyond double flats"
+            ),
+            (Domain::Tpc, None) => write!(f, "tpc {value} on the line of fifths is in range"),
+            (Domain::Interval, Some(true)) => write!(
+                f,
+                "interval {value} on the line of fifths is more than augmented"
+            ),
+            (Domain::Interval, Some(false)) => write!(
+                f,
+                "interval {value} on the line of fifths is more than diminished"
+            ),
+            (Domain::Interval, None) => {
+                write!(f, "interval {value} on the line of fifths is in range")
+            }
+            (Domain::Key, Some(true)) => write!(f, "key with {value} sharps has more than seven"),
+            (Domain::Key, Some(false)) => write!(
+                f,
+                "key with {} flats has more than seven",
+                value.unsigned_abs()
+            ),
+            (Domain::Key, None) if value < 0 => {
+                write!(f, "key with {} flats is in range", value.unsigned_abs())
+            }
+            (Domain::Key, None) => write!(f, "key with {value} sharps is in range"),
+            (Domain::Accidental, Some(true)) => {
+                write!(f, "alteration {value} is beyond a double sharp")
+            }
+            (Domain::Accidental, Some(false)) => {
+                write!(f, "alteration {value} is beyond a double flat")
+            }
+            (Domain::Accidental, None) => write!(f, "alteration {value} is in range"),
+        }?;
+        if direction.is_none() {
+            return Ok(());
+        }
+        match self.suggestion() {
+            Some(Suggestion::Tpc(tpc)) => write!(f, "; its enharmonic {tpc} is in range"),
+            Some(Suggestion::Interval(interval)) => {
+                write!(f, "; its enharmonic {} is in range", interval.to_wire())
+            }
+            Some(Suggestion::Key(key)) => write!(f, "; its enharmonic {key} major is in range"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Convert a position on the line of fifths to a value in the domain
+pub(crate) fn from_fifths<T: FromPrimitive>(value: i8, domain: Domain) -> Result<T, Error> {
+    T::from_i8(value).ok_or(Error::OutOfRange { value, domain })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Interval, Tpc};
+
+    #[test]
+    fn test_direction() {
+        let err = Tpc::Fbb.try_sub(Interval::P5).unwrap_err();
+        assert!(err.is_flatwards());
+        assert!(!err.is_sharpwards());
+        assert_eq!(
+            Error::OutOfRange {
+                value: -16,
+                domain: Domain::Tpc
+            },
+            err
+        );
+        let err = Interval::Aug7.try_add(Interval::P5).unwrap_err();
+        assert!(err.is_sharpwards());
+        assert!(!err.is_flatwards());
+    }
+
+    #[test]
+    fn test_suggestions_are_enharmonic() {
+        for &domain in &[Domain::Tpc, Domain::Interval, Domain::Key] {
+            let (flattest, sharpest) = domain.bounds();
+            for value in (i8::MIN..flattest).chain(sharpest + 1..=i8::MAX) {
+                let err = Error::OutOfRange { value, domain };
+                let suggested = match err.suggestion() {
+                    Some(Suggestion::Tpc(tpc)) => tpc as i8,
+                    Some(Suggestion::Interval(interval)) => interval as i8,
+                    Some(Suggestion::Key(key)) => key as i8,
+                    None => panic!("no suggestion for {} in {:?}", value, domain),
+                };
+                assert_eq!(0, (i16::from(value) - i16::from(suggested)) % 12);
+                assert!((flattest..=sharpest).contains(&suggested));
+            }
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let err = Error::OutOfRange {
+            value: -9,
+            domain: Domain::Key,
+        };
+        assert_eq!(
+            "key with 9 flats has more than seven; its enharmonic A major is in range",
+            err.to_string()
+        );
+        let err = Error::OutOfRange {
+            value: 3,
+            domain: Domain::Accidental,
+        };
+        assert_eq!("alteration 3 is beyond a double sharp", err.to_string());
+        assert_eq!(None, err.suggestion());
+        let err = Error::OutOfRange {
+            value: i8::MIN,
+            domain: Domain::Key,
+        };
+        assert_eq!(
+            "key with 128 flats has more than seven; its enharmonic E major is in range",
+            err.to_string()
+        );
+        let err = Error::OutOfRange {
+            value: -3,
+            domain: Domain::Key,
+        };
+        assert_eq!("key with 3 flats is in range", err.to_string());
+        let err = Error::OctaveOutOfRange { octave: 200 };
+        assert_eq!("octave 200 is outside the range of i8", err.to_string());
+        assert!(!err.is_sharpwards() && !err.is_flatwards());
+    }
+
+    #[test]
+    fn test_display_every_value() {
+        let domains = [
+            Domain::Tpc,
+            Domain::Interval,
+            Domain::Key,
+            Domain::Accidental,
+        ];
+        for &domain in &domains {
+            let (flattest, sharpest) = domain.bounds();
+            for value in i8::MIN..=i8::MAX {
+                let text = Error::OutOfRange { value, domain }.to_string();
+                let in_range = (flattest..=sharpest).contains(&value);
+                let beyond = text.contains("beyond") || text.contains("more than");
+                assert_eq!(in_range, !beyond, "{text}");
+                if domain == Domain::Key {
+                    assert_eq!(None, text.find('-'), "{text}");
+                }
+            }
+        }
+    }
+}
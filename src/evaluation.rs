@@ -0,0 +1,215 @@
+//! Scoring estimated keys against labeled keys
+//!
+//! An estimated key, such as from `analysis::weighted_key`, is compared to
+//! the labeled key of the same piece with the conventions of the MIREX key
+//! detection task: an estimate a perfect fifth above the label, or in its
+//! relative or parallel mode, gets partial credit. Keys are compared by the
+//! pitch classes of their tonics, so enharmonic labels such as G♭ and F♯
+//! major are the same key.
+//! ```
+//! # use tonality::{Key, Mode};
+//! # use tonality::evaluation::{Evaluation, KeyRelation};
+//! // G major estimated for a piece in C major is a fifth error
+//! let relation = KeyRelation::between((Key::G, Mode::Major), (Key::C, Mode::Major));
+//! assert_eq!(KeyRelation::Fifth, relation);
+//! assert!((relation.score() - 0.5).abs() < 1e-9);
+//!
+//! let evaluation = Evaluation::from_pairs(vec![
+//!     ((Key::C, Mode::Major), (Key::C, Mode::Major)),
+//!     // C minor estimated for E♭ major is the relative minor
+//!     ((Key::Eb, Mode::Minor), (Key::Eb, Mode::Major)),
+//! ]);
+//! assert_eq!(Some(0.5), evaluation.accuracy());
+//! assert!((evaluation.weighted_score().unwrap() - 0.65).abs() < 1e-9);
+//! ```
+use crate::{Key, Mode};
+
+/// How an estimated key relates to the labeled key, from the best to the
+/// worst
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[must_use]
+pub enum KeyRelation {
+    /// The same tonic and mode
+    Correct,
+    /// The same mode, with the tonic a perfect fifth above the labeled
+    /// tonic
+    Fifth,
+    /// A different mode with the same key signature, such as A minor for C
+    /// major
+    Relative,
+    /// A different mode on the same tonic, such as C minor for C major
+    Parallel,
+    /// Any other key
+    Other,
+}
+
+impl KeyRelation {
+    /// The relation of an estimated key to the labeled key, each given as a
+    /// key signature and a mode
+    /// ```
+    /// # use tonality::{Key, Mode};
+    /// # use tonality::evaluation::KeyRelation;
+    /// let c_major = (Key::C, Mode::Major);
+    /// assert_eq!(KeyRelation::Relative, KeyRelation::between((Key::C, Mode::Minor), c_major));
+    /// assert_eq!(KeyRelation::Parallel, KeyRelation::between((Key::Eb, Mode::Minor), c_major));
+    /// // F major is a fifth below, which gets no credit
+    /// assert_eq!(KeyRelation::Other, KeyRelation::between((Key::F, Mode::Major), c_major));
+    /// ```
+    pub fn between(estimate: (Key, Mode), label: (Key, Mode)) -> KeyRelation {
+        let (estimate_key, estimate_mode) = estimate;
+        let (label_key, label_mode) = label;
+        let estimate_tonic = estimate_key.tonic(estimate_mode).pitch_class();
+        let label_tonic = label_key.tonic(label_mode).pitch_class();
+        let same_signature = estimate_key.root().pitch_class() == label_key.root().pitch_class();
+        if estimate_mode == label_mode {
+            if estimate_tonic == label_tonic {
+                KeyRelation::Correct
+            } else if (estimate_tonic + 12 - label_tonic) % 12 == 7 {
+                KeyRelation::Fifth
+            } else {
+                KeyRelation::Other
+            }
+        } else if same_signature {
+            KeyRelation::Relative
+        } else if estimate_tonic == label_tonic {
+            KeyRelation::Parallel
+        } else {
+            KeyRelation::Other
+        }
+    }
+
+    /// The credit for the estimate: 1 when correct, 0.5 for a fifth, 0.3 for
+    /// the relative and 0.2 for the parallel mode, and 0 otherwise
+    #[must_use]
+    pub fn score(self) -> f64 {
+        match self {
+            KeyRelation::Correct => 1.0,
+            KeyRelation::Fifth => 0.5,
+            KeyRelation::Relative => 0.3,
+            KeyRelation::Parallel => 0.2,
+            KeyRelation::Other => 0.0,
+        }
+    }
+}
+
+/// The number of estimates in each relation to their labeled keys
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Evaluation {
+    /// The number of correct estimates
+    pub correct: usize,
+    /// The number of estimates a perfect fifth above the labeled key
+    pub fifth: usize,
+    /// The number of estimates in the relative mode of the labeled key
+    pub relative: usize,
+    /// The number of estimates in the parallel mode of the labeled key
+    pub parallel: usize,
+    /// The number of other estimates
+    pub other: usize,
+}
+
+impl Evaluation {
+    /// The evaluation of pairs of an estimated and a labeled key
+    #[must_use]
+    pub fn from_pairs<I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = ((Key, Mode), (Key, Mode))>,
+    {
+        let mut evaluation = Self::default();
+        for (estimate, label) in pairs {
+            evaluation.add(KeyRelation::between(estimate, label));
+        }
+        evaluation
+    }
+
+    /// Count one more estimate
+    pub fn add(&mut self, relation: KeyRelation) {
+        let count = match relation {
+            KeyRelation::Correct => &mut self.correct,
+            KeyRelation::Fifth => &mut self.fifth,
+            KeyRelation::Relative => &mut self.relative,
+            KeyRelation::Parallel => &mut self.parallel,
+            KeyRelation::Other => &mut self.other,
+        };
+        *count += 1;
+    }
+
+    /// The number of estimates
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.correct + self.fifth + self.relative + self.parallel + self.other
+    }
+
+    /// The share of correct estimates, from 0 to 1, or None if there are no
+    /// estimates
+    #[must_use]
+    pub fn accuracy(&self) -> Option<f64> {
+        // Counts of estimates are far below the precision of f64
+        #[allow(clippy::cast_precision_loss)]
+        let correct = self.correct as f64;
+        self.share(correct)
+    }
+
+    /// The mean `KeyRelation::score` of the estimates, from 0 to 1, or None
+    /// if there are no estimates
+    #[must_use]
+    pub fn weighted_score(&self) -> Option<f64> {
+        let credit = [
+            (self.correct, KeyRelation::Correct),
+            (self.fifth, KeyRelation::Fifth),
+            (self.relative, KeyRelation::Relative),
+            (self.parallel, KeyRelation::Parallel),
+        ]
+        .iter()
+        .map(|&(count, relation)| {
+            // Counts of estimates are far below the precision of f64
+            #[allow(clippy::cast_precision_loss)]
+            let count = count as f64;
+            count * relation.score()
+        })
+        .sum();
+        self.share(credit)
+    }
+
+    /// A number of estimates divided by the total
+    fn share(&self, count: f64) -> Option<f64> {
+        if self.total() == 0 {
+            return None;
+        }
+        // Counts of estimates are far below the precision of f64
+        #[allow(clippy::cast_precision_loss)]
+        let total = self.total() as f64;
+        Some(count / total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enharmonic_labels() {
+        let relation = KeyRelation::between((Key::Gb, Mode::Major), (Key::Fs, Mode::Major));
+        assert_eq!(KeyRelation::Correct, relation);
+        let relation = KeyRelation::between((Key::Cs, Mode::Major), (Key::Gb, Mode::Major));
+        assert_eq!(KeyRelation::Fifth, relation);
+        let relation = KeyRelation::between((Key::B, Mode::Minor), (Key::Cb, Mode::Major));
+        assert_eq!(KeyRelation::Relative, relation);
+    }
+
+    #[test]
+    fn test_evaluation_counts() {
+        let c_major = (Key::C, Mode::Major);
+        let evaluation = Evaluation::from_pairs(vec![
+            (c_major, c_major),
+            ((Key::G, Mode::Major), c_major),
+            ((Key::C, Mode::Minor), c_major),
+            ((Key::Eb, Mode::Minor), c_major),
+            ((Key::D, Mode::Major), c_major),
+        ]);
+        assert_eq!(5, evaluation.total());
+        assert_eq!(1, evaluation.other);
+        assert_eq!(Some(0.2), evaluation.accuracy());
+        assert!((evaluation.weighted_score().unwrap() - 2.0 / 5.0).abs() < 1e-9);
+        assert_eq!(None, Evaluation::default().weighted_score());
+    }
+}
@@ -0,0 +1,184 @@
+//! Theoretical keys beyond seven sharps or flats
+//!
+//! Parts for transposing instruments push music into keys that are rarely
+//! written otherwise. Music in F sharp major for B flat clarinet is written a
+//! major second up, in G sharp major, with eight sharps: one of them F double
+//! sharp. An `ExtendedKey` spells notes in such keys the same way `Key` does
+//! within seven sharps or flats.
+//! ```
+//! # use tonality::{Interval, Key, Step, Tpc};
+//! # use tonality::extended_key::ExtendedKey;
+//! let written = ExtendedKey::from(Key::Fs).transpose(Interval::Maj2).unwrap();
+//! assert_eq!(Tpc::Gs, written.root());
+//! assert_eq!(None, written.key());
+//! assert_eq!(Tpc::Fss, written.spell(Step::F));
+//! // The enharmonic key that a copyist would rather write
+//! assert_eq!(Key::Ab, written.enharmonic());
+//! ```
+use std::fmt;
+
+use crate::repr::Contiguous;
+use crate::{Alteration, Interval, Key, LineOfFifths, Step, Tpc};
+
+/// A major key with any number of sharps or flats whose scale can be spelled
+/// with at most double sharps or flats, from C double flat major with fourteen
+/// flats to C double sharp major with fourteen sharps.
+///
+/// Displayed as the root of the major key, like `Key`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[must_use]
+pub struct ExtendedKey(i8);
+
+impl ExtendedKey {
+    /// The flattest key is C double flat with fourteen flats
+    pub const MIN: ExtendedKey = ExtendedKey(-14);
+
+    /// The sharpest key is C double sharp with fourteen sharps
+    pub const MAX: ExtendedKey = ExtendedKey(14);
+
+    /// The key with a number of sharps, or flats if negative, or None if its
+    /// scale would need triple sharps or flats
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::extended_key::ExtendedKey;
+    /// assert_eq!(Tpc::Gs, ExtendedKey::new(8).unwrap().root());
+    /// assert_eq!(None, ExtendedKey::new(15));
+    /// ```
+    #[must_use]
+    pub fn new(sharps: i8) -> Option<ExtendedKey> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&sharps) {
+            Some(ExtendedKey(sharps))
+        } else {
+            None
+        }
+    }
+
+    /// The root of the key's major scale
+    pub fn root(self) -> Tpc {
+        Tpc::in_range(self.0)
+    }
+
+    /// Zero-indexed scale degrees: 0 is root, 4 is fifth
+    pub fn scale_degree(self, degree: isize) -> Tpc {
+        // Each degree is as far from the root as in C major
+        Tpc::in_range(self.0 + Key::C.scale_degree(degree).fifths())
+    }
+
+    /// The tonal pitch class of the step in the key, as `Step::with_key`
+    /// ```
+    /// # use tonality::{Step, Tpc};
+    /// # use tonality::extended_key::ExtendedKey;
+    /// let g_sharp = ExtendedKey::new(8).unwrap();
+    /// assert_eq!(Tpc::Fss, g_sharp.spell(Step::F));
+    /// assert_eq!(Tpc::Cs, g_sharp.spell(Step::C));
+    /// ```
+    pub fn spell(self, step: Step) -> Tpc {
+        // The scale spans the seven fifths from the fourth up to the seventh
+        let fourth = self.0 - 1;
+        let natural = step.with_key(Key::C).fifths();
+        Tpc::in_range(fourth + (natural - fourth).rem_euclid(7))
+    }
+
+    /// The alteration of the tpc in the key, as `Tpc::alteration`
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::extended_key::ExtendedKey;
+    /// let g_sharp = ExtendedKey::new(8).unwrap();
+    /// assert_eq!(0, g_sharp.alteration(Tpc::Fss));
+    /// assert_eq!(-1, g_sharp.alteration(Tpc::Fs));
+    /// ```
+    #[must_use]
+    pub fn alteration(self, tpc: Tpc) -> Alteration {
+        (tpc.fifths() - self.spell(tpc.step()).fifths()) / Tpc::DELTA_SEMITONE
+    }
+
+    /// The key transposed up by an interval, or None if it would have more
+    /// than fourteen sharps or flats
+    #[must_use]
+    pub fn transpose(self, interval: Interval) -> Option<ExtendedKey> {
+        Self::new(self.0.checked_add(interval.fifths())?)
+    }
+
+    /// The key as a `Key`, or None if it has more than seven sharps or flats
+    #[must_use]
+    pub fn key(self) -> Option<Key> {
+        Key::from_fifths(self.0)
+    }
+
+    /// The key as a `Key` if it has at most seven sharps or flats, or else the
+    /// enharmonically equivalent `Key`
+    /// ```
+    /// # use tonality::Key;
+    /// # use tonality::extended_key::ExtendedKey;
+    /// assert_eq!(Key::Ab, ExtendedKey::new(8).unwrap().enharmonic());
+    /// assert_eq!(Key::Cs, ExtendedKey::from(Key::Cs).enharmonic());
+    /// ```
+    pub fn enharmonic(self) -> Key {
+        let fifths = if self.0 > Key::MAX.fifths() {
+            self.0 - Tpc::DELTA_ENHARMONIC
+        } else if self.0 < Key::MIN.fifths() {
+            self.0 + Tpc::DELTA_ENHARMONIC
+        } else {
+            self.0
+        };
+        Key::in_range(fifths)
+    }
+}
+
+impl LineOfFifths for ExtendedKey {
+    fn fifths(self) -> i8 {
+        self.0
+    }
+
+    fn from_fifths(fifths: i8) -> Option<Self> {
+        Self::new(fifths)
+    }
+}
+
+impl From<Key> for ExtendedKey {
+    fn from(key: Key) -> Self {
+        ExtendedKey(key.fifths())
+    }
+}
+
+impl fmt::Display for ExtendedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.root(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> impl Iterator<Item = ExtendedKey> {
+        (ExtendedKey::MIN.0..=ExtendedKey::MAX.0).map(ExtendedKey)
+    }
+
+    #[test]
+    fn test_consistent_with_key() {
+        for key in Key::all() {
+            let extended = ExtendedKey::from(key);
+            assert_eq!(Some(key), extended.key());
+            for degree in 0..7 {
+                assert_eq!(key.scale_degree(degree), extended.scale_degree(degree));
+            }
+            for tpc in Tpc::all() {
+                assert_eq!(tpc.alteration(key), extended.alteration(tpc));
+                assert_eq!(tpc.step().with_key(key), extended.spell(tpc.step()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_scales_are_diatonic() {
+        for key in all() {
+            for degree in 0..7 {
+                let step = key.scale_degree(degree).step() as isize;
+                assert_eq!((key.root().step() as isize + degree) % 7, step);
+            }
+            let enharmonic = key.enharmonic();
+            assert_eq!(key.root().pitch_class(), enharmonic.root().pitch_class());
+        }
+    }
+}
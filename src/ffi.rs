@@ -0,0 +1,198 @@
+//! A C API with a stable ABI
+//!
+//! The functions take and return the `i8` representations of the types,
+//! documented in the `repr` module: a tpc, key or interval is its position on
+//! the line of fifths, and a step is 0 for C up to 6 for B. Invalid input or
+//! results outside the domain of the library give `TONALITY_INVALID`.
+//!
+//! The header `include/tonality.h` declares the functions, and can be
+//! regenerated with
+//! `cbindgen --config cbindgen.toml --output include/tonality.h src/ffi.rs`.
+//! The CI workflow fails if the header differs from the output of `cbindgen`.
+//! The `tonality-ffi` crate builds `libtonality_ffi.a` and
+//! `libtonality_ffi.so` to link against.
+//! ```
+//! # use tonality::ffi::*;
+//! # use tonality::Tpc;
+//! // MIDI note 70 in F major is B flat 4
+//! let pitch = tonality_spell_midi(70, -1);
+//! assert_eq!((Tpc::Bb as i8, 4), (pitch.tpc, pitch.octave));
+//! // C sharp up a major third is E sharp
+//! assert_eq!(Tpc::Es as i8, tonality_transpose(Tpc::Cs as i8, 4));
+//! assert_eq!(TONALITY_INVALID, tonality_transpose(Tpc::Bss as i8, 4));
+//! ```
+use std::convert::TryFrom;
+
+use crate::spelling::RespellPolicy;
+use crate::{Accidental, Interval, Key, Pitch, Step, Tpc};
+
+/// The value returned for invalid input, or results outside the domain of the
+/// library
+pub const TONALITY_INVALID: i8 = i8::MIN;
+
+/// A tpc with the octave of its step, in scientific pitch notation
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TonalityPitch {
+    /// The tpc, or `TONALITY_INVALID`
+    pub tpc: i8,
+    /// The octave of the written step, where middle C is in octave 4, as in
+    /// the `octave` module
+    pub octave: i8,
+}
+
+/// The accidentals of a key signature in the order they are written
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TonalityKeySignature {
+    /// The number of accidentals, or `TONALITY_INVALID`
+    pub count: i8,
+    /// The tpcs of the accidentals. Only the first `count` are used, and the
+    /// rest are `TONALITY_INVALID`.
+    pub tpcs: [i8; 7],
+}
+
+/// Spell a MIDI note number with its octave, as it would be written in a key
+#[no_mangle]
+pub extern "C" fn tonality_spell_midi(note: u8, key: i8) -> TonalityPitch {
+    match Key::try_from(key) {
+        Ok(key) => {
+            let pitch = Pitch::from_midi(note, RespellPolicy::KeyRelative(key));
+            TonalityPitch {
+                tpc: pitch.tpc as i8,
+                octave: pitch.octave,
+            }
+        }
+        Err(_) => TonalityPitch {
+            tpc: TONALITY_INVALID,
+            octave: 0,
+        },
+    }
+}
+
+/// Transpose a tpc up by an interval
+#[no_mangle]
+pub extern "C" fn tonality_transpose(tpc: i8, interval: i8) -> i8 {
+    let (Ok(tpc), Ok(interval)) = (Tpc::try_from(tpc), Interval::try_from(interval)) else {
+        return TONALITY_INVALID;
+    };
+    tpc.try_add(interval).map_or(TONALITY_INVALID, i8::from)
+}
+
+/// The interval from one tpc up to another
+#[no_mangle]
+pub extern "C" fn tonality_interval(from: i8, to: i8) -> i8 {
+    let (Ok(from), Ok(to)) = (Tpc::try_from(from), Tpc::try_from(to)) else {
+        return TONALITY_INVALID;
+    };
+    from.try_interval_to(to).map_or(TONALITY_INVALID, i8::from)
+}
+
+/// The step of a tpc
+#[no_mangle]
+pub extern "C" fn tonality_step(tpc: i8) -> i8 {
+    Tpc::try_from(tpc).map_or(TONALITY_INVALID, |tpc| i8::from(tpc.step()))
+}
+
+/// The tpc of a step in a key
+#[no_mangle]
+pub extern "C" fn tonality_spell_step(step: i8, key: i8) -> i8 {
+    let (Ok(step), Ok(key)) = (Step::try_from(step), Key::try_from(key)) else {
+        return TONALITY_INVALID;
+    };
+    i8::from(step.with_key(key))
+}
+
+/// The alteration of a tpc in a key, from -3 to 3
+#[no_mangle]
+pub extern "C" fn tonality_alteration(tpc: i8, key: i8) -> i8 {
+    let (Ok(tpc), Ok(key)) = (Tpc::try_from(tpc), Key::try_from(key)) else {
+        return TONALITY_INVALID;
+    };
+    tpc.alteration(key)
+}
+
+/// The accidentals of a key signature, sharps from F sharp and flats from B
+/// flat
+#[no_mangle]
+pub extern "C" fn tonality_key_signature(key: i8) -> TonalityKeySignature {
+    let mut signature = TonalityKeySignature {
+        count: TONALITY_INVALID,
+        tpcs: [TONALITY_INVALID; 7],
+    };
+    let Ok(key) = Key::try_from(key) else {
+        return signature;
+    };
+    // Sharps are written from F to B along the line of fifths, flats backwards
+    let mut steps = [
+        Step::F,
+        Step::C,
+        Step::G,
+        Step::D,
+        Step::A,
+        Step::E,
+        Step::B,
+    ];
+    if (key as i8) < 0 {
+        steps.reverse();
+    }
+    let accidentals = steps
+        .iter()
+        .map(|step| step.with_key(key))
+        .filter(|tpc| tpc.accidental() != Accidental::Natural);
+    let mut count = 0;
+    for (slot, tpc) in signature.tpcs.iter_mut().zip(accidentals) {
+        *slot = i8::from(tpc);
+        count += 1;
+    }
+    signature.count = count;
+    signature
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(TONALITY_INVALID, tonality_spell_midi(60, 8).tpc);
+        assert_eq!(TONALITY_INVALID, tonality_transpose(20, 0));
+        assert_eq!(
+            TONALITY_INVALID,
+            tonality_interval(Tpc::Fbb as i8, Tpc::Bss as i8)
+        );
+        assert_eq!(TONALITY_INVALID, tonality_step(TONALITY_INVALID));
+        assert_eq!(TONALITY_INVALID, tonality_spell_step(7, 0));
+        assert_eq!(TONALITY_INVALID, tonality_alteration(0, -8));
+        assert_eq!(TONALITY_INVALID, tonality_key_signature(8).count);
+    }
+
+    #[test]
+    fn test_key_signature() {
+        let signature = tonality_key_signature(Key::Eb as i8);
+        assert_eq!(3, signature.count);
+        assert_eq!(
+            [Tpc::Bb as i8, Tpc::Eb as i8, Tpc::Ab as i8],
+            signature.tpcs[..3]
+        );
+        assert!(signature.tpcs[3..]
+            .iter()
+            .all(|&tpc| tpc == TONALITY_INVALID));
+        assert_eq!(0, tonality_key_signature(Key::C as i8).count);
+        assert_eq!(7, tonality_key_signature(Key::Cs as i8).count);
+    }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(Step::F as i8, tonality_step(Tpc::Fs as i8));
+        assert_eq!(
+            Tpc::Fs as i8,
+            tonality_spell_step(Step::F as i8, Key::D as i8)
+        );
+        assert_eq!(-1, tonality_alteration(Tpc::F as i8, Key::D as i8));
+        assert_eq!(
+            Interval::Aug2 as i8,
+            tonality_interval(Tpc::Bb as i8, Tpc::Cs as i8)
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Conversions to and from types in other music crates, behind feature flags
+//! named after the crates
+#[cfg(feature = "rust-music-theory")]
+mod music_theory;
+#[cfg(feature = "pitch_calc")]
+mod pitch_calc;
@@ -0,0 +1,125 @@
+//! Conversions to and from `rust-music-theory`
+//!
+//! Its notes are spelled with any number of sharps or flats, and their octave
+//! follows the sounding pitch, so that C♭4, which sounds as B3, is written as
+//! C♭ in octave 3. Conversions keep both the spelling and the sounding pitch.
+//! ```
+//! # use std::convert::TryFrom;
+//! # use rust_music_theory::note::Note;
+//! # use tonality::{Pitch, Tpc};
+//! let c_flat_4 = Pitch::new(Tpc::Cb, 4);
+//! let note = Note::from(c_flat_4);
+//! assert_eq!(3, note.octave);
+//! assert_eq!(Ok(c_flat_4), Pitch::try_from(note));
+//! ```
+use std::convert::TryFrom;
+
+use rust_music_theory::note::{Note, NoteLetter};
+
+use crate::error::{from_fifths, Domain};
+use crate::{Error, Pitch, Step, Tpc};
+
+impl From<NoteLetter> for Step {
+    fn from(letter: NoteLetter) -> Self {
+        match letter {
+            NoteLetter::C => Step::C,
+            NoteLetter::D => Step::D,
+            NoteLetter::E => Step::E,
+            NoteLetter::F => Step::F,
+            NoteLetter::G => Step::G,
+            NoteLetter::A => Step::A,
+            NoteLetter::B => Step::B,
+        }
+    }
+}
+
+impl From<Step> for NoteLetter {
+    fn from(step: Step) -> Self {
+        match step {
+            Step::C => NoteLetter::C,
+            Step::D => NoteLetter::D,
+            Step::E => NoteLetter::E,
+            Step::F => NoteLetter::F,
+            Step::G => NoteLetter::G,
+            Step::A => NoteLetter::A,
+            Step::B => NoteLetter::B,
+        }
+    }
+}
+
+impl From<Tpc> for rust_music_theory::note::Pitch {
+    fn from(tpc: Tpc) -> Self {
+        let accidental = tpc.accidental() as i8;
+        Self::new(tpc.step().into(), accidental)
+    }
+}
+
+impl TryFrom<rust_music_theory::note::Pitch> for Tpc {
+    type Error = Error;
+
+    fn try_from(pitch: rust_music_theory::note::Pitch) -> Result<Self, Self::Error> {
+        let natural = Step::from(pitch.letter).with_key(crate::Key::C) as i8;
+        let value = pitch
+            .accidental
+            .saturating_mul(Tpc::DELTA_SEMITONE)
+            .saturating_add(natural);
+        from_fifths(value, Domain::Tpc)
+    }
+}
+
+impl From<Pitch> for Note {
+    fn from(pitch: Pitch) -> Self {
+        let octave = (pitch.midi() - i16::from(pitch.tpc.pitch_class())) / 12 - 1;
+        Note::new(pitch.tpc.into(), octave)
+    }
+}
+
+impl TryFrom<Note> for Pitch {
+    type Error = Error;
+
+    fn try_from(note: Note) -> Result<Self, Self::Error> {
+        let tpc = Tpc::try_from(note.pitch)?;
+        let midi = (i32::from(note.octave) + 1) * 12 + i32::from(tpc.pitch_class());
+        let lowest = i32::from(Pitch::new(tpc, -1).midi());
+        let octave = (midi - lowest) / 12 - 1;
+        let octave = i8::try_from(octave).map_err(|_| Error::OctaveOutOfRange { octave })?;
+        Ok(Pitch::new(tpc, octave))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pitch_roundtrip() {
+        for &pitch in &[
+            Pitch::new(Tpc::Cb, 4),
+            Pitch::new(Tpc::Bs, 3),
+            Pitch::new(Tpc::Fss, 2),
+            Pitch::new(Tpc::A, 4),
+        ] {
+            let note = Note::from(pitch);
+            assert_eq!(pitch.midi(), i16::from(note.midi_pitch()));
+            assert_eq!(Ok(pitch), Pitch::try_from(note));
+        }
+    }
+
+    #[test]
+    fn test_octave_out_of_range() {
+        let c = rust_music_theory::note::Pitch::new(NoteLetter::C, 0);
+        let err = Pitch::try_from(Note::new(c, i16::MAX)).unwrap_err();
+        assert_eq!(Error::OctaveOutOfRange { octave: 32767 }, err);
+        assert!(Pitch::try_from(Note::new(c, i16::MIN)).is_err());
+        assert_eq!(
+            Ok(Pitch::new(Tpc::C, 127)),
+            Pitch::try_from(Note::new(c, 127))
+        );
+    }
+
+    #[test]
+    fn test_triple_sharp() {
+        let pitch = rust_music_theory::note::Pitch::new(NoteLetter::C, 3);
+        assert!(Tpc::try_from(pitch).unwrap_err().is_sharpwards());
+    }
+}
@@ -0,0 +1,132 @@
+//! Conversions to and from `pitch_calc`
+//!
+//! `pitch_calc` letters have at most one sharp or flat, and lack E♯, B♯, F♭
+//! and C♭. Such spellings are converted to an enharmonic letter with as few
+//! accidentals as possible, keeping the sounding pitch.
+use std::convert::TryFrom;
+
+use ::pitch_calc::{Letter, LetterOctave};
+
+use crate::spelling::RespellPolicy;
+use crate::{Error, Pitch, Tpc};
+
+impl From<Letter> for Tpc {
+    fn from(letter: Letter) -> Self {
+        match letter {
+            Letter::C => Tpc::C,
+            Letter::Csh => Tpc::Cs,
+            Letter::Db => Tpc::Db,
+            Letter::D => Tpc::D,
+            Letter::Dsh => Tpc::Ds,
+            Letter::Eb => Tpc::Eb,
+            Letter::E => Tpc::E,
+            Letter::F => Tpc::F,
+            Letter::Fsh => Tpc::Fs,
+            Letter::Gb => Tpc::Gb,
+            Letter::G => Tpc::G,
+            Letter::Gsh => Tpc::Gs,
+            Letter::Ab => Tpc::Ab,
+            Letter::A => Tpc::A,
+            Letter::Ash => Tpc::As,
+            Letter::Bb => Tpc::Bb,
+            Letter::B => Tpc::B,
+        }
+    }
+}
+
+impl From<Tpc> for Letter {
+    fn from(tpc: Tpc) -> Self {
+        match tpc {
+            Tpc::C => Letter::C,
+            Tpc::Cs => Letter::Csh,
+            Tpc::Db => Letter::Db,
+            Tpc::D => Letter::D,
+            Tpc::Ds => Letter::Dsh,
+            Tpc::Eb => Letter::Eb,
+            Tpc::E => Letter::E,
+            Tpc::F => Letter::F,
+            Tpc::Fs => Letter::Fsh,
+            Tpc::Gb => Letter::Gb,
+            Tpc::G => Letter::G,
+            Tpc::Gs => Letter::Gsh,
+            Tpc::Ab => Letter::Ab,
+            Tpc::A => Letter::A,
+            Tpc::As => Letter::Ash,
+            Tpc::Bb => Letter::Bb,
+            Tpc::B => Letter::B,
+            // Every spelling under the policy has a letter
+            _ => tpc.respell(RespellPolicy::MinimizeAccidentals).into(),
+        }
+    }
+}
+
+/// Fails with `Error::OctaveOutOfRange` if the `i32` octave does not fit the
+/// `i8` octave of a `Pitch`
+impl TryFrom<LetterOctave> for Pitch {
+    type Error = Error;
+
+    fn try_from(letter_octave: LetterOctave) -> Result<Self, Self::Error> {
+        let LetterOctave(letter, octave) = letter_octave;
+        let octave = i8::try_from(octave).map_err(|_| Error::OctaveOutOfRange { octave })?;
+        Ok(Pitch::new(letter.into(), octave))
+    }
+}
+
+impl From<Pitch> for LetterOctave {
+    fn from(pitch: Pitch) -> Self {
+        let letter = Letter::from(pitch.tpc);
+        let tpc = Tpc::from(letter);
+        // The letter may be spelled differently, but sounds the same
+        let lowest = Pitch::new(tpc, -1).midi();
+        let octave = (pitch.midi() - lowest) / 12 - 1;
+        LetterOctave(letter, i32::from(octave))
+    }
+}
+
+impl From<Pitch> for ::pitch_calc::Step {
+    fn from(pitch: Pitch) -> Self {
+        ::pitch_calc::Step(f32::from(pitch.midi()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letter_roundtrip() {
+        for &letter in &[Letter::Csh, Letter::Db, Letter::E, Letter::Bb] {
+            assert_eq!(letter, Letter::from(Tpc::from(letter)));
+        }
+    }
+
+    #[test]
+    fn test_unrepresentable_spellings() {
+        assert_eq!(Letter::F, Letter::from(Tpc::Es));
+        assert_eq!(
+            Letter::Ab,
+            Letter::from(Tpc::Gs.respell(RespellPolicy::PreferFlats))
+        );
+        let cb4 = Pitch::new(Tpc::Cb, 4);
+        assert_eq!(LetterOctave(Letter::B, 3), LetterOctave::from(cb4));
+        let bs3 = Pitch::new(Tpc::Bs, 3);
+        assert_eq!(LetterOctave(Letter::C, 4), LetterOctave::from(bs3));
+    }
+
+    #[test]
+    fn test_letter_octave_out_of_range() {
+        let a4 = LetterOctave(Letter::A, 4);
+        assert_eq!(Ok(Pitch::new(Tpc::A, 4)), Pitch::try_from(a4));
+        let high = LetterOctave(Letter::C, 128);
+        let err = Error::OctaveOutOfRange { octave: 128 };
+        assert_eq!(Err(err), Pitch::try_from(high));
+        let low = LetterOctave(Letter::C, i32::MIN);
+        assert!(Pitch::try_from(low).is_err());
+    }
+
+    #[test]
+    fn test_step() {
+        let a4 = Pitch::new(Tpc::A, 4);
+        assert!((::pitch_calc::Step::from(a4).0 - 69.0).abs() < f32::EPSILON);
+    }
+}
@@ -1,15 +1,26 @@
 //! Intervals with enharmonic distinction
+use std::cmp::Ordering;
+
 use num_derive::FromPrimitive;
 
+use crate::consonance::ConsonanceTable;
+use crate::error::{from_fifths, Domain};
+use crate::line_of_fifths;
+use crate::repr::Contiguous;
+use crate::{Alteration, Error};
+
 /// An interval relates two tonal pitch classes to each other.
 /// Note: Intervals are ordered by distance on the line of fifth, not by
-/// the number of semitones.
+/// the number of semitones. Wrap them in `BySize` to order them by size.
 /// ```
 /// # use tonality::Interval;
+/// # use tonality::interval::BySize;
 /// assert!(Interval::P5 < Interval::Aug4);
+/// assert!(BySize(Interval::Aug4) < BySize(Interval::P5));
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, FromPrimitive, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, FromPrimitive, Eq, PartialOrd, Ord)]
 #[allow(missing_docs)]
+#[repr(i8)]
 pub enum Interval {
     Dim2 = -12,
     Dim6,
@@ -23,6 +34,7 @@ pub enum Interval {
     Min3,
     Min7,
     P4,
+    #[default]
     Unison,
     P5,
     Maj2,
@@ -38,6 +50,154 @@ pub enum Interval {
     Aug7,
 }
 
+/// Which spelling to choose for an interval given only its size in semitones
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SpellingPreference {
+    /// Perfect, major and minor intervals, and the augmented fourth
+    #[default]
+    Standard,
+    /// Augmented intervals instead of minor ones, so three semitones is an
+    /// augmented second rather than a minor third
+    Augmented,
+    /// Diminished intervals instead of major ones, so four semitones is a
+    /// diminished fourth rather than a major third
+    Diminished,
+}
+
+/// The generic size of an interval, without its quality
+/// ```
+/// # use tonality::interval::{Interval, IntervalClass};
+/// assert_eq!(IntervalClass::Third, Interval::Dim3.class());
+/// assert_eq!(IntervalClass::Third, Interval::Aug3.class());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, FromPrimitive)]
+#[must_use]
+#[allow(missing_docs)]
+#[repr(i8)]
+pub enum IntervalClass {
+    Unison = 0,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+}
+
+/// The quality of an interval
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(missing_docs)]
+pub enum IntervalQuality {
+    Diminished,
+    Minor,
+    Perfect,
+    Major,
+    Augmented,
+}
+
+impl IntervalClass {
+    /// Every interval class, from the unison to the seventh
+    /// ```
+    /// # use tonality::interval::IntervalClass;
+    /// assert_eq!(7, IntervalClass::all().count());
+    /// assert_eq!(Some(IntervalClass::Seventh), IntervalClass::all().last());
+    /// ```
+    #[must_use]
+    pub fn all() -> impl DoubleEndedIterator<Item = IntervalClass> {
+        Self::VARIANTS.iter().copied()
+    }
+
+    /// Whether intervals of this size are perfect rather than major or minor
+    fn is_perfect(self) -> bool {
+        matches!(
+            self,
+            IntervalClass::Unison | IntervalClass::Fourth | IntervalClass::Fifth
+        )
+    }
+}
+
+/// The major or perfect interval of each size, in fifths
+const FIFTHS: [i8; 7] = [0, 2, 4, -1, 1, 3, 5];
+/// The number of semitones in each major or perfect interval
+const SEMITONES: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// The number of intervals in the tables below, counted from `Interval::MIN`
+// There are 25, so the sign is never lost
+#[allow(clippy::cast_sign_loss)]
+const INTERVALS: usize = Interval::NUM_OF as usize;
+
+/// The number of semitones spanned by each interval, counted from
+/// `Interval::MIN`
+const SEMITONE_SPANS: [i8; INTERVALS] = {
+    let mut table = [0; INTERVALS];
+    let mut index = 0;
+    while index < INTERVALS {
+        // Between -12 and 12, as index is less than 25
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let fifths = Interval::MIN as i8 + index as i8;
+        // Within 0 and 6
+        #[allow(clippy::cast_sign_loss)]
+        let size = (fifths * 4).rem_euclid(7) as usize;
+        table[index] = SEMITONES[size] + (fifths - FIFTHS[size]) / 7;
+        index += 1;
+    }
+    table
+};
+
+/// The generic size of each interval, counted from `Interval::MIN`
+const CLASSES: [IntervalClass; INTERVALS] = {
+    let sizes = [
+        IntervalClass::Unison,
+        IntervalClass::Second,
+        IntervalClass::Third,
+        IntervalClass::Fourth,
+        IntervalClass::Fifth,
+        IntervalClass::Sixth,
+        IntervalClass::Seventh,
+    ];
+    let mut table = [IntervalClass::Unison; INTERVALS];
+    let mut index = 0;
+    while index < INTERVALS {
+        // Between -12 and 12, as index is less than 25
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let fifths = Interval::MIN as i8 + index as i8;
+        // A fifth spans four steps, so the size is within 0 and 6
+        #[allow(clippy::cast_sign_loss)]
+        let size = (fifths * 4).rem_euclid(7) as usize;
+        table[index] = sizes[size];
+        index += 1;
+    }
+    table
+};
+
+/// Orders intervals by size: by the number of semitones, and then by the
+/// number of steps, so that an augmented fourth is smaller than a diminished
+/// fifth.
+/// ```
+/// # use tonality::Interval;
+/// # use tonality::interval::BySize;
+/// let mut intervals = vec![Interval::P5, Interval::Dim5, Interval::Aug4, Interval::Min2];
+/// intervals.sort_by_key(|&interval| BySize(interval));
+/// assert_eq!(
+///     vec![Interval::Min2, Interval::Aug4, Interval::Dim5, Interval::P5],
+///     intervals
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BySize(pub Interval);
+
+impl Ord for BySize {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp_by_semitones(other.0)
+    }
+}
+
+impl PartialOrd for BySize {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Interval {
     /// The biggest interval is an augmented seventh
     pub const MAX: Interval = Self::Aug7;
@@ -45,9 +205,24 @@ impl Interval {
     /// The smallest interval is a diminished second
     pub const MIN: Interval = Self::Dim2;
 
+    /// The number of different intervals
+    pub const NUM_OF: isize = Self::MAX as isize - Self::MIN as isize + 1;
+
     /// The number of steps along the line of fifths to an enharmonic variant
     pub const DELTA_ENHARMONIC: i8 = 12;
 
+    /// Every interval, in order along the line of fifths from the diminished
+    /// second to the augmented seventh
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(Some(Interval::Dim2), Interval::all().next());
+    /// assert!(Interval::all().any(|interval| interval == Interval::P5));
+    /// ```
+    #[must_use]
+    pub fn all() -> impl DoubleEndedIterator<Item = Interval> {
+        line_of_fifths::between(Self::MIN, Self::MAX)
+    }
+
     /// Whether the two intervals are enharmonic, i.e. represent the same distance
     /// in semitones in twelve tone equal temperament.
     ///
@@ -61,11 +236,440 @@ impl Interval {
     pub fn enharmonic(self, other: Interval) -> bool {
         (self as i8 - other as i8) % Self::DELTA_ENHARMONIC == 0
     }
+
+    /// Find the interval spanning a number of steps and semitones, such as the
+    /// distance between two notes given both their positions on the staff and
+    /// their MIDI note numbers.
+    ///
+    /// Steps are zero-indexed: 0 is a unison and 2 is a third. Compound
+    /// intervals are reduced to simple ones, so both numbers may exceed an
+    /// octave. Returns None if no interval has that combination, such as a
+    /// third spanning six semitones.
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(Some(Interval::Min3), Interval::from_step_and_semitones(2, 3));
+    /// assert_eq!(Some(Interval::Aug2), Interval::from_step_and_semitones(1, 3));
+    /// assert_eq!(Some(Interval::Dim2), Interval::from_step_and_semitones(1, 0));
+    /// // A major tenth
+    /// assert_eq!(Some(Interval::Maj3), Interval::from_step_and_semitones(9, 16));
+    /// assert_eq!(None, Interval::from_step_and_semitones(2, 6));
+    /// ```
+    #[must_use]
+    pub fn from_step_and_semitones(steps: u8, semitones: i8) -> Option<Interval> {
+        let size = usize::from(steps % 7);
+        // The deviation from the major or perfect interval, between -6 and 5
+        let deviation = (i16::from(semitones) - i16::from(SEMITONES[size]) + 6).rem_euclid(12) - 6;
+        num_traits::FromPrimitive::from_i16(i16::from(FIFTHS[size]) + 7 * deviation)
+    }
+
+    /// The number of steps the interval spans on the staff, from 0 for a
+    /// unison to 6 for a seventh, and its alteration from the major or
+    /// perfect interval of that size, as the alteration of the upper note in
+    /// the major key of the lower note
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!((2, -1), Interval::Min3.to_step_alteration());
+    /// assert_eq!((4, -1), Interval::Dim5.to_step_alteration());
+    /// assert_eq!((1, -2), Interval::Dim2.to_step_alteration());
+    /// assert_eq!((3, 1), Interval::Aug4.to_step_alteration());
+    /// ```
+    #[must_use]
+    pub fn to_step_alteration(self) -> (i8, Alteration) {
+        let alteration = (self as i8 + 1).div_euclid(7);
+        (self.class() as i8, alteration)
+    }
+
+    /// The interval spanning a number of steps with an alteration from the
+    /// major or perfect interval, the inverse of `to_step_alteration`.
+    ///
+    /// Steps are zero-indexed, and compound intervals and negative numbers of
+    /// steps are reduced to simple ones, so -1 and 13 are both sevenths.
+    /// Returns None if the interval would be more than augmented or
+    /// diminished.
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(Some(Interval::Min3), Interval::from_step_alteration(2, -1));
+    /// assert_eq!(Some(Interval::Aug4), Interval::from_step_alteration(3, 1));
+    /// assert_eq!(Some(Interval::Min7), Interval::from_step_alteration(-1, -1));
+    /// // A doubly augmented fourth
+    /// assert_eq!(None, Interval::from_step_alteration(3, 2));
+    /// ```
+    #[must_use]
+    pub fn from_step_alteration(steps: i8, alteration: Alteration) -> Option<Interval> {
+        // Within 0 and 6
+        #[allow(clippy::cast_sign_loss)]
+        let size = steps.rem_euclid(7) as usize;
+        let fifths = i16::from(FIFTHS[size]) + 7 * i16::from(alteration);
+        num_traits::FromPrimitive::from_i16(fifths)
+    }
+
+    /// The sum of two intervals
+    ///
+    /// # Errors
+    ///
+    /// Returns an error telling the direction in which the result would be out
+    /// of range, if it would be more than augmented or diminished.
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(Ok(Interval::Maj3), Interval::Maj2.try_add(Interval::Maj2));
+    /// assert!(Interval::Aug7.try_add(Interval::P5).unwrap_err().is_sharpwards());
+    /// ```
+    pub fn try_add(self, other: Interval) -> Result<Interval, Error> {
+        from_fifths(self as i8 + other as i8, Domain::Interval)
+    }
+
+    /// The difference between two intervals
+    ///
+    /// # Errors
+    ///
+    /// Returns an error telling the direction in which the result would be out
+    /// of range, if it would be more than augmented or diminished.
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(Ok(Interval::Min3), Interval::P5.try_sub(Interval::Maj3));
+    /// assert!(Interval::Dim2.try_sub(Interval::P5).unwrap_err().is_flatwards());
+    /// ```
+    pub fn try_sub(self, other: Interval) -> Result<Interval, Error> {
+        from_fifths(self as i8 - other as i8, Domain::Interval)
+    }
+
+    /// Spell an interval of a number of semitones. Compound intervals are
+    /// reduced to simple ones, and negative numbers of semitones are inverted.
+    /// ```
+    /// # use tonality::interval::{Interval, SpellingPreference};
+    /// let standard = SpellingPreference::Standard;
+    /// assert_eq!(Interval::Min3, Interval::from_semitones(3, standard));
+    /// assert_eq!(Interval::Aug4, Interval::from_semitones(6, standard));
+    /// assert_eq!(Interval::Maj6, Interval::from_semitones(-3, standard));
+    /// let augmented = SpellingPreference::Augmented;
+    /// assert_eq!(Interval::Aug2, Interval::from_semitones(3, augmented));
+    /// let diminished = SpellingPreference::Diminished;
+    /// assert_eq!(Interval::Dim5, Interval::from_semitones(6, diminished));
+    /// ```
+    #[must_use]
+    pub fn from_semitones(semitones: i8, prefer: SpellingPreference) -> Interval {
+        // The flattest of twelve consecutive intervals on the line of fifths
+        let flattest = match prefer {
+            SpellingPreference::Standard => Interval::Min2 as i8,
+            SpellingPreference::Augmented => Interval::P4 as i8,
+            SpellingPreference::Diminished => Interval::Dim3 as i8,
+        };
+        // A semitone is seven fifths up, modulo enharmonic equivalence
+        let fifths = i16::from(semitones) * 7 - i16::from(flattest);
+        let delta = i16::from(Self::DELTA_ENHARMONIC);
+        #[allow(clippy::cast_possible_truncation)]
+        let value = flattest + fifths.rem_euclid(delta) as i8;
+        Self::in_range(value)
+    }
+
+    /// The generic size of the interval, disregarding its quality
+    /// ```
+    /// # use tonality::interval::{Interval, IntervalClass};
+    /// assert_eq!(IntervalClass::Fourth, Interval::Aug4.class());
+    /// assert_eq!(IntervalClass::Fifth, Interval::Dim5.class());
+    /// ```
+    #[inline]
+    pub const fn class(self) -> IntervalClass {
+        CLASSES[self.index()]
+    }
+
+    /// The quality of the interval
+    /// ```
+    /// # use tonality::Interval;
+    /// # use tonality::interval::IntervalQuality;
+    /// assert_eq!(IntervalQuality::Minor, Interval::Min6.quality());
+    /// assert_eq!(IntervalQuality::Diminished, Interval::Dim1.quality());
+    /// ```
+    #[must_use]
+    pub fn quality(self) -> IntervalQuality {
+        let class = self.class();
+        // Each quality is seven fifths from the next
+        let deviation = (self as i8 - FIFTHS[class as usize]) / 7;
+        match (deviation, class.is_perfect()) {
+            (1, _) => IntervalQuality::Augmented,
+            (0, true) => IntervalQuality::Perfect,
+            (0, false) => IntervalQuality::Major,
+            (-1, false) => IntervalQuality::Minor,
+            _ => IntervalQuality::Diminished,
+        }
+    }
+
+    /// The interval of a generic size and a quality. Returns None if the
+    /// quality doesn't apply to the size, such as a perfect third or a major
+    /// fifth.
+    /// ```
+    /// # use tonality::Interval;
+    /// # use tonality::interval::{IntervalClass, IntervalQuality};
+    /// let dim3 = Interval::with_quality(IntervalClass::Third, IntervalQuality::Diminished);
+    /// assert_eq!(Some(Interval::Dim3), dim3);
+    /// let maj5 = Interval::with_quality(IntervalClass::Fifth, IntervalQuality::Major);
+    /// assert_eq!(None, maj5);
+    /// ```
+    #[must_use]
+    pub fn with_quality(class: IntervalClass, quality: IntervalQuality) -> Option<Interval> {
+        let deviation = match (quality, class.is_perfect()) {
+            (IntervalQuality::Augmented, _) => 1,
+            (IntervalQuality::Perfect, true) | (IntervalQuality::Major, false) => 0,
+            (IntervalQuality::Minor, false) | (IntervalQuality::Diminished, true) => -1,
+            (IntervalQuality::Diminished, false) => -2,
+            _ => return None,
+        };
+        num_traits::FromPrimitive::from_i8(FIFTHS[class as usize] + 7 * deviation)
+    }
+
+    /// The interval of the same size a chromatic semitone larger, such as a
+    /// major third from a minor third. Returns None for augmented intervals.
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(Some(Interval::Aug4), Interval::P4.augmented());
+    /// assert_eq!(Some(Interval::Maj3), Interval::Min3.augmented());
+    /// assert_eq!(None, Interval::Aug2.augmented());
+    /// ```
+    #[must_use]
+    pub fn augmented(self) -> Option<Interval> {
+        num_traits::FromPrimitive::from_i8(self as i8 + 7)
+    }
+
+    /// The interval of the same size a chromatic semitone smaller, such as a
+    /// minor sixth from a major sixth. Returns None for diminished intervals.
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(Some(Interval::Dim5), Interval::P5.diminished());
+    /// assert_eq!(Some(Interval::Min6), Interval::Maj6.diminished());
+    /// assert_eq!(None, Interval::Dim7.diminished());
+    /// ```
+    #[must_use]
+    pub fn diminished(self) -> Option<Interval> {
+        num_traits::FromPrimitive::from_i8(self as i8 - 7)
+    }
+
+    /// The interval going up, extended by a number of octaves
+    pub fn compound_with_octaves(self, octaves: u8) -> CompoundInterval {
+        CompoundInterval {
+            interval: self,
+            octaves,
+            descending: false,
+        }
+    }
+
+    /// The interval going up
+    pub fn up(self) -> DirectedInterval {
+        DirectedInterval {
+            interval: self,
+            direction: Direction::Up,
+        }
+    }
+
+    /// The interval going down
+    pub fn down(self) -> DirectedInterval {
+        DirectedInterval {
+            interval: self,
+            direction: Direction::Down,
+        }
+    }
+
+    /// The number of semitones spanned by the interval, between -1 for a
+    /// diminished unison and 12 for an augmented seventh
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(6, Interval::Aug4.semitones());
+    /// assert_eq!(0, Interval::Dim2.semitones());
+    /// assert_eq!(-1, Interval::Dim1.semitones());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn semitones(self) -> i8 {
+        SEMITONE_SPANS[self.index()]
+    }
+
+    /// The number of semitones spanned by the interval, the same as
+    /// `semitones`
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(6, Interval::Dim5.semitone_span());
+    /// assert_eq!(12, Interval::Aug7.semitone_span());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn semitone_span(self) -> i8 {
+        self.semitones()
+    }
+
+    /// The number of steps spanned by the interval, from 0 for a unison to 6
+    /// for a seventh, the same as `class` as a number
+    /// ```
+    /// # use tonality::Interval;
+    /// assert_eq!(4, Interval::Dim5.step_span());
+    /// assert_eq!(3, Interval::Aug4.step_span());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn step_span(self) -> i8 {
+        self.class() as i8
+    }
+
+    /// The entry of the interval in the tables of semitones and classes
+    #[inline]
+    const fn index(self) -> usize {
+        // Never below Interval::MIN
+        #[allow(clippy::cast_sign_loss)]
+        let index = (self as i8 - Self::MIN as i8) as usize;
+        index
+    }
+
+    /// Compare intervals by size rather than by position on the line of
+    /// fifths. See `BySize`.
+    #[must_use]
+    pub fn cmp_by_semitones(self, other: Interval) -> Ordering {
+        (self.semitones(), self.class()).cmp(&(other.semitones(), other.class()))
+    }
+
+    /// Whether the interval is dissonant when measured from the bass, in
+    /// common practice harmony. Seconds, sevenths, perfect fourths and all
+    /// augmented and diminished intervals are dissonant.
+    ///
+    /// See `ConsonanceTable` for other definitions of consonance.
+    /// ```
+    /// # use tonality::Interval;
+    /// assert!(Interval::P4.is_dissonant_against_bass());
+    /// assert!(Interval::Aug2.is_dissonant_against_bass());
+    /// assert!(!Interval::Min3.is_dissonant_against_bass());
+    /// ```
+    #[must_use]
+    pub fn is_dissonant_against_bass(self) -> bool {
+        ConsonanceTable::against_bass().is_dissonant(self)
+    }
 }
 
-impl Default for Interval {
-    fn default() -> Self {
-        Self::Unison
+/// A signed interval between pitches: a simple interval plus a number of
+/// octaves, going up or down.
+///
+/// The simple interval is always measured from the lower to the higher note,
+/// so a descending fifth is a perfect fifth with the `descending` flag, not a
+/// perfect fourth.
+/// ```
+/// # use tonality::Interval;
+/// let tenth = Interval::Maj3.compound_with_octaves(1);
+/// assert_eq!(16, tenth.semitones());
+/// assert_eq!(9, tenth.steps());
+/// assert_eq!(-16, tenth.reversed().semitones());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct CompoundInterval {
+    /// The simple interval, from the lower to the higher note
+    pub interval: Interval,
+    /// The number of whole octaves added to the simple interval
+    pub octaves: u8,
+    /// Whether the interval goes down
+    pub descending: bool,
+}
+
+impl CompoundInterval {
+    /// The same interval in the opposite direction
+    pub fn reversed(self) -> Self {
+        CompoundInterval {
+            descending: !self.descending,
+            ..self
+        }
+    }
+
+    /// The number of semitones spanned by the interval, negative if it goes
+    /// down
+    #[must_use]
+    pub fn semitones(self) -> i16 {
+        let semitones = i16::from(self.interval.semitones()) + 12 * i16::from(self.octaves);
+        if self.descending {
+            -semitones
+        } else {
+            semitones
+        }
+    }
+
+    /// The number of steps spanned by the interval, negative if it goes down.
+    /// A tenth spans nine steps.
+    #[must_use]
+    pub fn steps(self) -> i16 {
+        let steps = self.interval.class() as i16 + 7 * i16::from(self.octaves);
+        if self.descending {
+            -steps
+        } else {
+            steps
+        }
+    }
+}
+
+/// The direction of a melodic interval
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub enum Direction {
+    /// Towards higher pitches
+    Up,
+    /// Towards lower pitches
+    Down,
+}
+
+impl std::ops::Neg for Direction {
+    type Output = Direction;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
+/// A simple interval going up or down, like a descending minor third in a
+/// melody. Negating it gives the same interval in the opposite direction.
+/// ```
+/// # use tonality::{Interval, Pitch, Tpc};
+/// # use tonality::interval::Direction;
+/// let down = Interval::Min3.down();
+/// assert_eq!(Direction::Down, down.direction);
+/// assert_eq!(-3, down.semitones());
+/// assert_eq!(Interval::Min3.up(), -down);
+/// assert_eq!(Some(Pitch::new(Tpc::A, 3)), Pitch::new(Tpc::C, 4) + down);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct DirectedInterval {
+    /// The interval, from the lower to the higher note
+    pub interval: Interval,
+    /// Whether the interval goes up or down
+    pub direction: Direction,
+}
+
+impl DirectedInterval {
+    /// The number of semitones spanned by the interval, negative if it goes
+    /// down
+    #[must_use]
+    pub fn semitones(self) -> i8 {
+        match self.direction {
+            Direction::Up => self.interval.semitones(),
+            Direction::Down => -self.interval.semitones(),
+        }
+    }
+}
+
+impl std::ops::Neg for DirectedInterval {
+    type Output = DirectedInterval;
+
+    fn neg(self) -> Self::Output {
+        DirectedInterval {
+            direction: -self.direction,
+            ..self
+        }
+    }
+}
+
+impl From<DirectedInterval> for CompoundInterval {
+    fn from(directed: DirectedInterval) -> Self {
+        CompoundInterval {
+            interval: directed.interval,
+            octaves: 0,
+            descending: directed.direction == Direction::Down,
+        }
     }
 }
 
@@ -73,7 +677,24 @@ impl std::ops::Add<Interval> for Interval {
     type Output = Option<Self>;
 
     fn add(self, rhs: Interval) -> Self::Output {
-        num_traits::FromPrimitive::from_i8(self as i8 + rhs as i8)
+        self.try_add(rhs).ok()
+    }
+}
+
+/// Negating an interval gives its inversion, so that an interval and its
+/// negation add up to a unison.
+/// ```
+/// # use tonality::Interval;
+/// assert_eq!(Interval::Min6, -Interval::Maj3);
+/// assert_eq!(Interval::Aug4, -Interval::Dim5);
+/// assert_eq!(Some(Interval::Unison), Interval::P5 + -Interval::P5);
+/// ```
+impl std::ops::Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Self::Output {
+        // The domain is symmetric around the unison
+        Interval::in_range(-(self as i8))
     }
 }
 
@@ -81,6 +702,134 @@ impl std::ops::Sub<Interval> for Interval {
     type Output = Option<Self>;
 
     fn sub(self, rhs: Interval) -> Self::Output {
-        num_traits::FromPrimitive::from_i8(self as i8 - rhs as i8)
+        self.try_sub(rhs).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::{Pitch, Tpc};
+
+    #[test]
+    fn test_quality_roundtrip() {
+        for interval in Interval::all() {
+            let rebuilt = Interval::with_quality(interval.class(), interval.quality());
+            assert_eq!(Some(interval), rebuilt);
+            if let Some(augmented) = interval.augmented() {
+                assert_eq!(interval.class(), augmented.class());
+                assert_eq!(interval.semitones() + 1, augmented.semitones());
+                assert_eq!(Some(interval), augmented.diminished());
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_step_and_semitones_roundtrip() {
+        let c = Pitch::new(Tpc::C, 4);
+        for interval in Interval::all() {
+            let above = (c + interval).unwrap();
+            let steps = above.step() as u8;
+            let semitones = i8::try_from(above.midi() - c.midi()).unwrap();
+            assert_eq!(
+                Some(interval),
+                Interval::from_step_and_semitones(steps, semitones)
+            );
+        }
+    }
+
+    #[test]
+    fn test_step_alteration_roundtrip() {
+        for interval in Interval::all() {
+            let (steps, alteration) = interval.to_step_alteration();
+            assert_eq!(steps, interval.class() as i8);
+            assert_eq!(
+                alteration,
+                (Tpc::C + interval).unwrap().alteration(crate::Key::C)
+            );
+            assert_eq!(
+                Some(interval),
+                Interval::from_step_alteration(steps + 7, alteration)
+            );
+        }
+        assert_eq!(None, Interval::from_step_alteration(0, i8::MIN));
+    }
+
+    #[test]
+    fn test_class_matches_steps() {
+        let c = Pitch::new(Tpc::C, 4);
+        for interval in Interval::all() {
+            let above = (c + interval).unwrap();
+            assert_eq!(above.step() as i8, interval.class() as i8);
+        }
+    }
+
+    #[test]
+    fn test_semitones_match_pitches() {
+        let c = Pitch::new(Tpc::C, 4);
+        for interval in Interval::all() {
+            let above = (c + interval).unwrap();
+            assert_eq!(above.midi() - c.midi(), i16::from(interval.semitones()));
+        }
+    }
+
+    #[test]
+    fn test_spans_match_fifths() {
+        for interval in Interval::all() {
+            // Each fifth spans seven semitones and four steps, less the
+            // octaves of twelve semitones and seven steps
+            let fifths = interval as i8;
+            let octaves = (fifths * 4).div_euclid(7);
+            assert_eq!(fifths * 7 - 12 * octaves, interval.semitones());
+            assert_eq!(fifths * 4 - 7 * octaves, interval.class() as i8);
+        }
+    }
+
+    #[test]
+    fn test_from_semitones_roundtrip() {
+        let c = Pitch::new(Tpc::C, 4);
+        let preferences = [
+            SpellingPreference::Standard,
+            SpellingPreference::Augmented,
+            SpellingPreference::Diminished,
+        ];
+        for &prefer in &preferences {
+            for semitones in 0..12 {
+                let interval = Interval::from_semitones(semitones, prefer);
+                let above = (c + interval).unwrap();
+                let span = i16::from(semitones);
+                assert_eq!(span, (above.midi() - c.midi()).rem_euclid(12));
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_step_and_semitones_invalid() {
+        assert_eq!(None, Interval::from_step_and_semitones(0, 2));
+        assert_eq!(None, Interval::from_step_and_semitones(4, 9));
+        assert_eq!(None, Interval::from_step_and_semitones(3, 3));
+    }
+
+    #[test]
+    fn test_from_step_and_semitones_extremes() {
+        // Ten octaves and a fifth, and ten octaves and a major third down
+        assert_eq!(
+            Some(Interval::P5),
+            Interval::from_step_and_semitones(4, i8::MAX)
+        );
+        assert_eq!(
+            Some(Interval::Dim4),
+            Interval::from_step_and_semitones(3, i8::MIN)
+        );
+        for &steps in &[0, 6, u8::MAX] {
+            for &semitones in &[i8::MIN, i8::MIN + 1, i8::MAX - 1, i8::MAX] {
+                assert_eq!(
+                    Interval::from_step_and_semitones(steps % 7, semitones.rem_euclid(12)),
+                    Interval::from_step_and_semitones(steps, semitones)
+                );
+            }
+        }
     }
 }
@@ -1,14 +1,34 @@
 //! Key signatures
+use std::fmt;
+use std::iter::FusedIterator;
+
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
+use crate::chord::ChordQuality;
+use crate::error::{from_fifths, Domain};
+use crate::line_of_fifths;
+use crate::repr::Contiguous;
+use crate::Accidental;
+use crate::Error;
+use crate::Interval;
+use crate::Mode;
 use crate::Step;
 use crate::Tpc;
 
 /// Keys represent a number of fixed sharps or flats.
-#[derive(Clone, Copy, Debug, PartialEq, FromPrimitive)]
+///
+/// Displayed as the root of the major key, with Unicode accidental glyphs or
+/// ASCII characters when using the alternate flag.
+/// ```
+/// # use tonality::Key;
+/// assert_eq!("E♭", Key::Eb.to_string());
+/// assert_eq!("Eb", format!("{:#}", Key::Eb));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, FromPrimitive)]
 #[must_use]
 #[allow(missing_docs)]
+#[repr(i8)]
 pub enum Key {
     Cb = -7,
     Gb,
@@ -17,6 +37,7 @@ pub enum Key {
     Eb,
     Bb,
     F,
+    #[default]
     C, // == 0
     G,
     D,
@@ -27,12 +48,6 @@ pub enum Key {
     Cs,
 }
 
-impl Default for Key {
-    fn default() -> Self {
-        Self::C
-    }
-}
-
 impl Key {
     /// The flattest key is C flat with seven flats
     pub const MIN: Key = Key::Cb;
@@ -46,6 +61,17 @@ impl Key {
     /// Steps along the line of fifths to end up at an enharmonic key.
     pub const DELTA_ENHARMONIC: isize = 12;
 
+    /// Every key, from C flat to C sharp
+    /// ```
+    /// # use tonality::Key;
+    /// assert_eq!(15, Key::all().count());
+    /// assert_eq!(Some(Key::Cs), Key::all().last());
+    /// ```
+    #[must_use]
+    pub fn all() -> impl DoubleEndedIterator<Item = Key> {
+        line_of_fifths::between(Self::MIN, Self::MAX)
+    }
+
     /// The root of the key's major scale
     pub fn root_step(self) -> Step {
         match (self as i8).rem_euclid(7) {
@@ -65,15 +91,529 @@ impl Key {
     /// assert_eq!(Tpc::G, Key::G.root());
     /// ```
     pub fn root(self) -> Tpc {
-        FromPrimitive::from_i8(self as i8).unwrap()
+        self.root_step().with_key(self)
     }
 
     /// Zero-indexed scale degrees: 0 is root, 4 is fifth
     pub fn scale_degree(self, degree: isize) -> Tpc {
-        /// Each scale degree's distance from the root, in fifths
-        const OFFSETS: [i8; 7] = [0, 2, 4, -1, 1, 3, 5];
-        let value = self as i8 + OFFSETS[degree.rem_euclid(7) as usize];
-        FromPrimitive::from_i8(value).unwrap()
+        (self.root_step() + degree).with_key(self)
+    }
+
+    /// The notes of the key's major scale, from the root up
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// let scale: Vec<Tpc> = Key::D.scale().collect();
+    /// assert_eq!(vec![Tpc::D, Tpc::E, Tpc::Fs, Tpc::G, Tpc::A, Tpc::B, Tpc::Cs], scale);
+    /// // Keys iterate over their scales
+    /// let descending: Vec<Tpc> = Key::D.into_iter().rev().take(2).collect();
+    /// assert_eq!(vec![Tpc::Cs, Tpc::B], descending);
+    /// ```
+    pub fn scale(self) -> Scale {
+        self.modal_scale(Mode::Major)
+    }
+
+    /// The notes of the scale of a mode with this key signature, from the
+    /// tonic up
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// let scale: Vec<Tpc> = Key::C.modal_scale(Mode::Dorian).collect();
+    /// assert_eq!(vec![Tpc::D, Tpc::E, Tpc::F, Tpc::G, Tpc::A, Tpc::B, Tpc::C], scale);
+    /// ```
+    pub fn modal_scale(self, mode: Mode) -> Scale {
+        let tonic = mode as isize;
+        Scale {
+            key: self,
+            front: tonic,
+            back: tonic + 7,
+        }
+    }
+
+    /// The traditional name of the scale degree of a tpc in a mode with this
+    /// key signature, or None if the tpc is not in the scale
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// assert_eq!(Some("leading tone"), Key::Eb.degree_name(Tpc::D, Mode::Major));
+    /// assert_eq!(Some("submediant"), Key::Eb.degree_name(Tpc::Ab, Mode::Minor));
+    /// assert_eq!(None, Key::Eb.degree_name(Tpc::B, Mode::Minor));
+    /// ```
+    #[must_use]
+    pub fn degree_name(self, tpc: Tpc, mode: Mode) -> Option<&'static str> {
+        if !tpc.is_diatonic_in(self) {
+            return None;
+        }
+        let degree = self.tonic(mode).step().diatonic_distance(tpc.step());
+        Some(mode.degree_name(isize::from(degree)))
+    }
+
+    /// The movable do solfège syllable of a tpc in a mode with this key
+    /// signature, where the tonic is do. Chromatic notes have their raised or
+    /// lowered syllables, such as fi for a raised fourth. Returns None for
+    /// notes further altered than chromatic solfège goes.
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// assert_eq!(Some("sol"), Key::G.solfege(Tpc::D, Mode::Major));
+    /// assert_eq!(Some("fi"), Key::G.solfege(Tpc::Cs, Mode::Major));
+    /// assert_eq!(Some("me"), Key::G.solfege(Tpc::G, Mode::Minor));
+    /// ```
+    #[must_use]
+    pub fn solfege(self, tpc: Tpc, mode: Mode) -> Option<&'static str> {
+        crate::mode::solfege(self.tonic(mode).try_interval_to(tpc).ok()?)
+    }
+
+    /// The diatonic triad on a zero-indexed scale degree, from the root up
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert_eq!([Tpc::Cs, Tpc::E, Tpc::G], Key::D.triad(6));
+    /// ```
+    pub fn triad(self, degree: isize) -> [Tpc; 3] {
+        let degree = degree.rem_euclid(7);
+        [
+            self.scale_degree(degree),
+            self.scale_degree(degree + 2),
+            self.scale_degree(degree + 4),
+        ]
+    }
+
+    /// The diatonic seventh chord on a zero-indexed scale degree, from the
+    /// root up
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert_eq!([Tpc::A, Tpc::Cs, Tpc::E, Tpc::G], Key::D.seventh_chord(4));
+    /// ```
+    pub fn seventh_chord(self, degree: isize) -> [Tpc; 4] {
+        let [root, third, fifth] = self.triad(degree);
+        [
+            root,
+            third,
+            fifth,
+            self.scale_degree(degree.rem_euclid(7) + 6),
+        ]
+    }
+
+    /// The quality of the diatonic triad on a zero-indexed scale degree. It
+    /// is the same in every key.
+    /// ```
+    /// # use tonality::Key;
+    /// # use tonality::chord::ChordQuality;
+    /// assert_eq!(ChordQuality::Diminished, Key::D.triad_quality(6));
+    /// assert_eq!(ChordQuality::Minor, Key::D.triad_quality(1));
+    /// ```
+    #[must_use]
+    pub fn triad_quality(self, degree: isize) -> ChordQuality {
+        use ChordQuality::{Diminished, Major, Minor};
+
+        const QUALITIES: [ChordQuality; 7] = [Major, Minor, Minor, Major, Major, Minor, Diminished];
+        // Between 0 and 6
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let degree = degree.rem_euclid(7) as usize;
+        QUALITIES[degree]
+    }
+
+    /// The quality of the diatonic seventh chord on a zero-indexed scale
+    /// degree. It is the same in every key.
+    /// ```
+    /// # use tonality::Key;
+    /// # use tonality::chord::ChordQuality;
+    /// assert_eq!(ChordQuality::Dominant7, Key::D.seventh_quality(4));
+    /// assert_eq!(ChordQuality::HalfDiminished7, Key::D.seventh_quality(6));
+    /// ```
+    #[must_use]
+    pub fn seventh_quality(self, degree: isize) -> ChordQuality {
+        use ChordQuality::{Dominant7, HalfDiminished7, Major7, Minor7};
+
+        const QUALITIES: [ChordQuality; 7] = [
+            Major7,
+            Minor7,
+            Minor7,
+            Major7,
+            Dominant7,
+            Minor7,
+            HalfDiminished7,
+        ];
+        // Between 0 and 6
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let degree = degree.rem_euclid(7) as usize;
+        QUALITIES[degree]
+    }
+
+    /// The tonic of the mode with this key signature
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// assert_eq!(Tpc::Gs, Key::B.tonic(Mode::Minor));
+    /// ```
+    pub fn tonic(self, mode: Mode) -> Tpc {
+        self.scale_degree(mode as isize)
+    }
+
+    /// The key signature of a mode with the given tonic, or None if it would
+    /// have more than seven sharps or flats
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// assert_eq!(Some(Key::Eb), Key::from_tonic(Tpc::C, Mode::Minor));
+    /// assert_eq!(None, Key::from_tonic(Tpc::Es, Mode::Minor));
+    /// ```
+    #[must_use]
+    pub fn from_tonic(tonic: Tpc, mode: Mode) -> Option<Key> {
+        FromPrimitive::from_i8(tonic as i8 - Key::C.tonic(mode) as i8)
+    }
+
+    /// The name of the key in a mode, such as "E♭ major" or "cis-Moll"
+    /// ```
+    /// # use tonality::{Key, Mode};
+    /// # use tonality::key::NameStyle;
+    /// assert_eq!("E♭ major", Key::Eb.name(Mode::Major, NameStyle::English));
+    /// assert_eq!("C♯ minor", Key::E.name(Mode::Minor, NameStyle::English));
+    /// assert_eq!("c♯ minor", Key::E.name(Mode::Minor, NameStyle::EnglishLowercaseMinor));
+    /// assert_eq!("Es-Dur", Key::Eb.name(Mode::Major, NameStyle::German));
+    /// assert_eq!("b-Moll", Key::Db.name(Mode::Minor, NameStyle::German));
+    /// ```
+    #[must_use]
+    pub fn name(self, mode: Mode, style: NameStyle) -> String {
+        let tonic = self.tonic(mode);
+        let lowercase = mode.has_minor_third() && style != NameStyle::English;
+        let (tonic, mode, separator) = match style {
+            NameStyle::English | NameStyle::EnglishLowercaseMinor => {
+                (tonic.to_string(), english_mode(mode), ' ')
+            }
+            NameStyle::German => (german_tpc(tonic), german_mode(mode), '-'),
+        };
+        if lowercase {
+            format!("{}{separator}{mode}", tonic.to_lowercase())
+        } else {
+            format!("{tonic}{separator}{mode}")
+        }
+    }
+
+    /// Parse a key name in any of the styles of `Key::name`, ignoring case and
+    /// accepting ASCII accidentals. Returns the key signature and the mode, or
+    /// None if the name is not recognized or the key signature would have
+    /// more than seven sharps or flats.
+    /// ```
+    /// # use tonality::{Key, Mode};
+    /// assert_eq!(Some((Key::B, Mode::Minor)), Key::from_name("g# minor"));
+    /// assert_eq!(Some((Key::Bb, Mode::Major)), Key::from_name("B-Dur"));
+    /// assert_eq!(Some((Key::C, Mode::Dorian)), Key::from_name("D Dorian"));
+    /// assert_eq!(None, Key::from_name("H major"));
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<(Key, Mode)> {
+        let name = name.trim().to_lowercase();
+        let mut all_tpcs = Tpc::all();
+        let (tonic, mode) = if let Some((tonic, mode)) = name.rsplit_once('-') {
+            let mode = Mode::ALL
+                .iter()
+                .find(|&&m| german_mode(m).to_lowercase() == mode)?;
+            let tonic = all_tpcs.find(|&tpc| german_tpc(tpc).to_lowercase() == tonic)?;
+            (tonic, *mode)
+        } else {
+            let (tonic, mode) = name.split_once(' ')?;
+            let mode = Mode::ALL
+                .iter()
+                .find(|&&m| english_mode(m).to_lowercase() == mode.trim())?;
+            let tonic = all_tpcs.find(|&tpc| {
+                tpc.to_string().to_lowercase() == tonic
+                    || format!("{tpc:#}").to_lowercase() == tonic
+            })?;
+            (tonic, *mode)
+        };
+        Some((Key::from_tonic(tonic, mode)?, mode))
+    }
+
+    /// All twelve keys around the circle of fifths, starting with this key.
+    ///
+    /// Where the circle passes beyond seven sharps or flats, it continues with
+    /// the enharmonic key. The twelve keys are enharmonically distinct, so
+    /// three of the fifteen key signatures are left out: one of each of B and
+    /// C♭, F♯ and G♭, and C♯ and D♭.
+    /// ```
+    /// # use tonality::key::{Key, Rotation};
+    /// let keys: Vec<Key> = Key::Fs.circle_of_fifths(Rotation::Clockwise).take(4).collect();
+    /// assert_eq!(vec![Key::Fs, Key::Cs, Key::Ab, Key::Eb], keys);
+    /// // C sharp major is reached instead of D flat major
+    /// assert!(!Key::C.circle_of_fifths(Rotation::Clockwise).any(|key| key == Key::Db));
+    /// let keys: Vec<Key> = Key::F.circle_of_fifths(Rotation::Counterclockwise).take(3).collect();
+    /// assert_eq!(vec![Key::F, Key::Bb, Key::Eb], keys);
+    /// ```
+    pub fn circle_of_fifths(self, rotation: Rotation) -> impl Iterator<Item = Key> {
+        let step = match rotation {
+            Rotation::Clockwise => 1,
+            Rotation::Counterclockwise => -1,
+        };
+        let start = self as i8;
+        (0..12).map(move |i| {
+            let mut value = start + step * i;
+            if value > Key::MAX as i8 {
+                value -= Tpc::DELTA_ENHARMONIC;
+            } else if value < Key::MIN as i8 {
+                value += Tpc::DELTA_ENHARMONIC;
+            }
+            Key::in_range(value)
+        })
+    }
+
+    /// The key signature transposed up by an interval, so that every note
+    /// keeps its alteration relative to the key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error telling the direction, if the key would have more than
+    /// seven sharps or flats.
+    /// ```
+    /// # use tonality::{Interval, Key};
+    /// assert_eq!(Ok(Key::A), Key::Eb.transposed(Interval::Aug4));
+    /// // G sharp major would have eight sharps
+    /// assert!(Key::Fs.transposed(Interval::Maj2).unwrap_err().is_sharpwards());
+    /// ```
+    pub fn transposed(self, interval: Interval) -> Result<Key, Error> {
+        from_fifths(self as i8 + interval as i8, Domain::Key)
+    }
+
+    /// The key signature transposed up by an interval, or the enharmonic key
+    /// signature if the key would have more than seven sharps or flats
+    /// ```
+    /// # use tonality::{Interval, Key};
+    /// // C sharp major would be D sharp major, with nine sharps
+    /// assert_eq!(Key::Eb, Key::Cs.transposed_enharmonic(Interval::Maj2));
+    /// assert_eq!(Key::D, Key::C.transposed_enharmonic(Interval::Maj2));
+    /// ```
+    pub fn transposed_enharmonic(self, interval: Interval) -> Key {
+        let mut value = self as i8 + interval as i8;
+        if value > Key::MAX as i8 {
+            value -= Tpc::DELTA_ENHARMONIC;
+        } else if value < Key::MIN as i8 {
+            value += Tpc::DELTA_ENHARMONIC;
+        }
+        Key::in_range(value)
+    }
+
+    /// The shortest distance from this key to the other around the circle of
+    /// fifths, between -5 and 6. Positive distances are clockwise, towards the
+    /// sharp keys. Enharmonic keys have distance zero, and keys a tritone
+    /// apart have distance 6.
+    /// ```
+    /// # use tonality::Key;
+    /// assert_eq!(2, Key::C.fifths_distance(Key::D));
+    /// assert_eq!(-3, Key::C.fifths_distance(Key::Eb));
+    /// assert_eq!(0, Key::Cs.fifths_distance(Key::Db));
+    /// assert_eq!(3, Key::E.fifths_distance(Key::Db));
+    /// ```
+    #[must_use]
+    pub fn fifths_distance(self, other: Key) -> i8 {
+        let delta = Tpc::DELTA_ENHARMONIC;
+        (other as i8 - self as i8 + delta / 2 - 1).rem_euclid(delta) - delta / 2 + 1
+    }
+
+    /// How closely related two keys are, from 0 for keys a tritone apart to 6
+    /// for the same or enharmonic key signatures.
+    ///
+    /// A key signature is shared by a major key and its relative minor, so
+    /// relative keys are as close as possible. Keys a fifth apart, such as
+    /// the dominant and subdominant, and their relatives, have closeness 5.
+    /// ```
+    /// # use tonality::Key;
+    /// assert_eq!(6, Key::Gb.closeness(Key::Fs));
+    /// assert_eq!(5, Key::C.closeness(Key::F));
+    /// assert_eq!(0, Key::C.closeness(Key::Fs));
+    /// ```
+    #[must_use]
+    pub fn closeness(self, other: Key) -> u8 {
+        6 - self.fifths_distance(other).unsigned_abs()
+    }
+
+    /// Whether two keys are closely related, i.e. a fifth apart at most
+    /// ```
+    /// # use tonality::Key;
+    /// assert!(Key::A.is_closely_related(Key::E));
+    /// assert!(!Key::A.is_closely_related(Key::B));
+    /// ```
+    #[must_use]
+    pub fn is_closely_related(self, other: Key) -> bool {
+        self.closeness(other) >= 5
+    }
+
+    /// The steps whose accidental in the key signature changes from this key
+    /// to the other, with the old and the new accidental.
+    ///
+    /// The steps are in the order they change when moving around the circle
+    /// of fifths from this key to the other, so that cancelled accidentals of
+    /// the old key come before new accidentals. A step that changes twice,
+    /// such as from flat to sharp, is listed once.
+    /// ```
+    /// # use tonality::{Accidental, Key, Step};
+    /// let changes = Key::Bb.alterations_to(Key::D);
+    /// let expected = vec![
+    ///     (Step::E, Accidental::Flat, Accidental::Natural),
+    ///     (Step::B, Accidental::Flat, Accidental::Natural),
+    ///     (Step::F, Accidental::Natural, Accidental::Sharp),
+    ///     (Step::C, Accidental::Natural, Accidental::Sharp),
+    /// ];
+    /// assert_eq!(expected, changes);
+    /// assert!(Key::A.alterations_to(Key::A).is_empty());
+    /// ```
+    #[must_use]
+    pub fn alterations_to(self, other: Key) -> Vec<(Step, Accidental, Accidental)> {
+        let (from, to) = (self as i8, other as i8);
+        // Moving a key up a fifth raises the step a fifth below its root, and
+        // moving it down lowers the step two fifths below its root
+        let changed = if from < to {
+            (from..to).map(|key| key - 1).collect::<Vec<_>>()
+        } else {
+            (to + 1..=from).rev().map(|key| key - 2).collect()
+        };
+        let mut steps: Vec<Step> = Vec::with_capacity(7);
+        for fifths in changed {
+            let step = Tpc::in_range(fifths).step();
+            if !steps.contains(&step) {
+                steps.push(step);
+            }
+        }
+        steps
+            .into_iter()
+            .map(|step| {
+                let old = step.with_key(self).accidental();
+                let new = step.with_key(other).accidental();
+                (step, old, new)
+            })
+            .collect()
+    }
+}
+
+/// An iterator over the seven notes of a scale, from the tonic up, as
+/// returned by `Key::scale` and `Key::modal_scale`.
+///
+/// It yields each note of the scale exactly once, and can be reversed to go
+/// from the seventh degree down. The notes have no octave, so the scale can
+/// be repeated forever with `Iterator::cycle`, in either direction.
+/// ```
+/// # use tonality::{Key, Tpc};
+/// let notes: Vec<Tpc> = Key::F.scale().rev().cycle().skip(6).take(3).collect();
+/// assert_eq!(vec![Tpc::F, Tpc::E, Tpc::D], notes);
+/// assert_eq!(7, Key::F.scale().len());
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Scale {
+    key: Key,
+    /// The next degree from the front
+    front: isize,
+    /// One past the next degree from the back
+    back: isize,
+}
+
+impl Iterator for Scale {
+    type Item = Tpc;
+
+    fn next(&mut self) -> Option<Tpc> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.key.scale_degree(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Scale {
+    fn next_back(&mut self) -> Option<Tpc> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.key.scale_degree(self.back))
+    }
+}
+
+impl ExactSizeIterator for Scale {
+    fn len(&self) -> usize {
+        // The back is never before the front
+        #[allow(clippy::cast_sign_loss)]
+        let len = (self.back - self.front) as usize;
+        len
+    }
+}
+
+impl FusedIterator for Scale {}
+
+impl IntoIterator for Key {
+    type Item = Tpc;
+    type IntoIter = Scale;
+
+    fn into_iter(self) -> Scale {
+        self.scale()
+    }
+}
+
+/// The direction to go around the circle of fifths
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// Towards the sharp keys: C, G, D, ...
+    Clockwise,
+    /// Towards the flat keys: C, F, B♭, ...
+    Counterclockwise,
+}
+
+/// Conventions for naming a key in a mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameStyle {
+    /// English names with capitalized tonics: "E♭ major", "C♯ minor"
+    English,
+    /// English names with lowercase tonics for modes with a minor third:
+    /// "E♭ major", "c♯ minor"
+    EnglishLowercaseMinor,
+    /// German names, with H for B and B for B flat, and lowercase tonics for
+    /// modes with a minor third: "Es-Dur", "cis-Moll"
+    German,
+}
+
+fn english_mode(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Major => "major",
+        Mode::Dorian => "Dorian",
+        Mode::Phrygian => "Phrygian",
+        Mode::Lydian => "Lydian",
+        Mode::Mixolydian => "Mixolydian",
+        Mode::Minor => "minor",
+        Mode::Locrian => "Locrian",
+    }
+}
+
+fn german_mode(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Major => "Dur",
+        Mode::Dorian => "Dorisch",
+        Mode::Phrygian => "Phrygisch",
+        Mode::Lydian => "Lydisch",
+        Mode::Mixolydian => "Mixolydisch",
+        Mode::Minor => "Moll",
+        Mode::Locrian => "Lokrisch",
+    }
+}
+
+/// The German name of a tonal pitch class, such as Fis, Es or H
+fn german_tpc(tpc: Tpc) -> String {
+    let suffix = match tpc.accidental() {
+        Accidental::DblFlat => "eses",
+        Accidental::Flat => "es",
+        Accidental::Natural => "",
+        Accidental::Sharp => "is",
+        Accidental::DblSharp => "isis",
+    };
+    match tpc.step() {
+        Step::B if tpc.accidental() == Accidental::Flat => "B".into(),
+        Step::B => format!("H{suffix}"),
+        // E and A take "s" rather than "es": Es, As, Eses, Ases
+        step @ (Step::E | Step::A) if suffix.starts_with('e') => format!("{step}{}", &suffix[1..]),
+        step => format!("{step}{suffix}"),
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.root(), f)
     }
 }
 
@@ -81,6 +621,102 @@ impl Key {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_transposed_enharmonic_stays_in_range() {
+        for key in Key::all() {
+            for interval in Interval::all() {
+                let transposed = key.transposed_enharmonic(interval);
+                let semitones = i16::from(interval.semitones());
+                let pitch_class = i16::from(key.root().pitch_class()) + semitones;
+                assert_eq!(
+                    pitch_class.rem_euclid(12),
+                    i16::from(transposed.root().pitch_class())
+                );
+                if let Ok(exact) = key.transposed(interval) {
+                    assert_eq!(exact, transposed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_alterations_across_all_steps() {
+        let changes = Key::Cb.alterations_to(Key::Cs);
+        let steps: Vec<Step> = changes.iter().map(|&(step, _, _)| step).collect();
+        let sharps = [
+            Step::F,
+            Step::C,
+            Step::G,
+            Step::D,
+            Step::A,
+            Step::E,
+            Step::B,
+        ];
+        assert_eq!(sharps.to_vec(), steps);
+        for &(_, old, new) in &changes {
+            assert_eq!((Accidental::Flat, Accidental::Sharp), (old, new));
+        }
+        let steps: Vec<Step> = Key::E
+            .alterations_to(Key::Ab)
+            .iter()
+            .map(|&(step, _, _)| step)
+            .collect();
+        // D sharp becomes D natural and then D flat
+        let expected = [
+            Step::D,
+            Step::G,
+            Step::C,
+            Step::F,
+            Step::B,
+            Step::E,
+            Step::A,
+        ];
+        assert_eq!(expected.to_vec(), steps);
+    }
+
+    #[test]
+    fn test_diatonic_chords_in_every_key() {
+        use ChordQuality::{Diminished, Dominant7, HalfDiminished7, Major, Major7, Minor, Minor7};
+
+        let triads = [Major, Minor, Minor, Major, Major, Minor, Diminished];
+        let sevenths = [
+            Major7,
+            Minor7,
+            Minor7,
+            Major7,
+            Dominant7,
+            Minor7,
+            HalfDiminished7,
+        ];
+        for key in Key::all() {
+            for (degree, (&triad, &seventh)) in (0..).zip(triads.iter().zip(sevenths.iter())) {
+                assert_eq!(triad, key.triad_quality(degree));
+                assert_eq!(seventh, key.seventh_quality(degree));
+                assert_eq!(key.triad(degree)[..], key.seventh_chord(degree)[..3]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scale_iterators() {
+        for key in Key::all() {
+            for &mode in &Mode::ALL {
+                let scale: Vec<Tpc> = key.modal_scale(mode).collect();
+                let mut reversed: Vec<Tpc> = key.modal_scale(mode).rev().collect();
+                reversed.reverse();
+                assert_eq!(scale, reversed);
+                assert_eq!(key.tonic(mode), scale[0]);
+            }
+            let mut scale = key.scale();
+            assert_eq!(Some(key.root()), scale.next());
+            assert_eq!(Some(key.scale_degree(6)), scale.next_back());
+            assert_eq!(5, scale.len());
+            assert_eq!(5, scale.by_ref().count());
+            assert_eq!(None, scale.next());
+            assert_eq!(None, scale.next_back());
+        }
+    }
+
     #[test]
     fn test_root() {
         assert_eq!(Tpc::Cs, Key::Cs.root());
@@ -92,4 +728,55 @@ mod tests {
         assert_eq!(Tpc::Bb, Key::Bb.scale_degree(0));
         assert_eq!(Tpc::Es, Key::Cs.scale_degree(2));
     }
+
+    #[test]
+    fn test_name_roundtrip() {
+        let styles = [
+            NameStyle::English,
+            NameStyle::EnglishLowercaseMinor,
+            NameStyle::German,
+        ];
+        for key in Key::C.circle_of_fifths(Rotation::Clockwise) {
+            for &mode in &Mode::ALL {
+                for &style in &styles {
+                    let name = key.name(mode, style);
+                    assert_eq!(Some((key, mode)), Key::from_name(&name), "{name}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_german_names() {
+        assert_eq!("H-Dur", Key::B.name(Mode::Major, NameStyle::German));
+        assert_eq!("as-Moll", Key::Cb.name(Mode::Minor, NameStyle::German));
+        assert_eq!(
+            "eis-Phrygisch",
+            Key::Cs.name(Mode::Phrygian, NameStyle::German)
+        );
+        assert_eq!("Fes-Lydisch", Key::Cb.name(Mode::Lydian, NameStyle::German));
+    }
+
+    #[test]
+    fn test_full_circle() {
+        for &rotation in &[Rotation::Clockwise, Rotation::Counterclockwise] {
+            let keys: Vec<Key> = Key::B.circle_of_fifths(rotation).collect();
+            assert_eq!(12, keys.len());
+            for (i, &a) in keys.iter().enumerate() {
+                for &b in &keys[i + 1..] {
+                    assert_ne!(0, a.fifths_distance(b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fifths_distance_is_antisymmetric() {
+        assert_eq!(6, Key::C.fifths_distance(Key::Fs));
+        assert_eq!(6, Key::Fs.fifths_distance(Key::C));
+        assert_eq!(1, Key::Fs.fifths_distance(Key::Cs));
+        assert_eq!(-1, Key::Cs.fifths_distance(Key::Fs));
+        assert_eq!(2, Key::Cb.fifths_distance(Key::Cs));
+        assert_eq!(-2, Key::Cs.fifths_distance(Key::Cb));
+    }
 }
@@ -1,31 +1,47 @@
 #![warn(clippy::pedantic, missing_docs, missing_doc_code_examples)]
+#![cfg_attr(
+    all(feature = "panic-free", not(test)),
+    deny(
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::todo,
+        clippy::unimplemented
+    )
+)]
 
 //! A library for handling tonal pitch classes, keys, intervals, accidentals and
 //! alterations. A tonal pitch class (`Tpc`) does not distinguish pitches in
 //! different octaves, but it does distinguish different enharmonic spellings of
 //! notes, intervals, and keys. This is done based on the "line of fifths"
 //! concept.
-//! 
+//!
 //! Distinguishing enharmonic spellings is desirable in several applications:
-//! 
+//!
 //! - In musical notation, where using an incorrect enharmonic spelling harms
 //!   legibility
 //! - When using other tunings than twelve tone equal temperament (12TET), in
 //!   which case notes normally considered enharmonic should actually be played at
 //!   different pitches.
-//! 
+//!
 //! Another important type is the `Step`, which represents the fact that G sharp
 //! and G flat are written on the same line of the staff. A `Step` combined with
 //! a `Key` or `Accidental` gives a `Tpc`.
-//! 
+//!
 //! Using the `Step` type also helps you handle octaves. If you want the F above
 //! A flat, for instance, you would compare their `Step`s, see that F has a lower
-//! step than A flat, and therefore should be raised an octave.
-//! 
+//! step than A flat, and therefore should be raised an octave. The `Pitch`
+//! type, a `Tpc` in a specific octave, does this for you.
+//!
 //! Arithmetic operations with `Tpc`s and `Interval`s return optional values,
 //! because they may result in alterations beyond the domain of the library.
 //! Triple sharps/flats or double diminished/augmented intervals are not
-//! supported.
+//! supported. Each of these operations also has a `try_` variant returning a
+//! `Result`, whose `Error` tells in which direction the result was out of range.
+//!
+//! The enums are `#[repr(i8)]` with stable discriminants, documented in the
+//! `repr` module, so that they can be passed across an FFI boundary as `i8`s.
 //!
 //! ## Alteration versus accidental
 //!
@@ -34,6 +50,65 @@
 //! An accidental is an absolute change that can only apply to a
 //! Step - turning it into a Tpc.
 //!
+//! ## Performance
+//!
+//! Spelling a note never allocates or fails. The functions on the hot path of
+//! spelling MIDI notes, such as `Pitch::from_midi`, `Tpc::altered_step`,
+//! `Tpc::step` and `Step::with_key`, are table lookups marked `#[inline]`, and
+//! are benchmarked in `benches/spelling.rs`. To spell many notes in the same
+//! key, the `batch` module looks them up in a table for the key. To count or
+//! collect tpcs, the `TpcMap` and `TpcSet` of the `collections` module are
+//! arrays indexed by the line of fifths, which neither allocate nor hash.
+//!
+//! ## Optional features
+//!
+//! - `rand`: sample random `Tpc`s, `Key`s, `Interval`s, `Step`s and
+//!   `Accidental`s from the `Standard` distribution
+//! - `proptest`: `Arbitrary` implementations of the same types, and
+//!   strategies for property tests in the `testing` module
+//! - `arbitrary`: implementations of `arbitrary::Arbitrary` for fuzzing
+//! - `pitch_calc` and `rust-music-theory`: conversions to and from the pitch
+//!   types of these crates
+//! - `midly`: spelling the notes of a MIDI file track with `midi::spell_track`
+//! - `extended-keys`: keys with up to fourteen sharps or flats, in the
+//!   `extended_key` module
+//! - `microtonal`: quarter-tone accidentals and pitch classes, in the
+//!   `microtonal` module
+//! - `wasm`: a `wasm-bindgen` facade taking and returning strings, in the
+//!   `wasm` module
+//! - `python`: Python bindings through `pyo3`, in the `python` module. The
+//!   extension module is built from the `tonality-py` crate with `maturin`.
+//! - `ffi`: `extern "C"` functions with a C header, in the `ffi` module. The
+//!   static and dynamic libraries are built from the `tonality-ffi` crate.
+//! - `serde`: serialization of `Tpc`s, `Key`s, `Interval`s and `Pitch`es in
+//!   the format of the `wire` module, of `corpus::CorpusReport` and of the
+//!   analysis results in the `results` module
+//! - `audio`: rendering pitches in a tuning to sine waves, in the `audio`
+//!   module
+//! - `tracing`: `tracing` spans and events in the spelling, key estimation
+//!   and chord identification algorithms
+//! - `panic-free`: the tests in `tests/panic_free.rs`, which call the
+//!   functions of the core types with every value of their arguments, and
+//!   lints denying `unwrap`, `expect` and `panic!` in the crate. No public
+//!   function panics for any argument, except the documented length checks
+//!   in the `batch` module.
+//!
+//! # Stability of formatted output
+//!
+//! The text produced by the formatters is part of the API, so that snapshot
+//! tests of applications using them don't break on patch releases. The output
+//! of these only changes, byte for byte, in releases that change the minor
+//! version while the crate is below 1.0:
+//!
+//! - `Display`, in both the default and the alternate form, of `Tpc`, `Key`,
+//!   `Accidental`, `Step`, `Octave`, `chord::Chord` and `chord::ChordSymbol`
+//! - `Key::name` in every `key::NameStyle`
+//! - `wire::WireFormat::to_wire`
+//! - `abc::format_pitch`
+//!
+//! The golden files in `tests/golden` hold the output for every value, and
+//! the tests in `tests/golden.rs` compare them byte for byte.
+//!
 //! # Example
 //!
 //! It can be used for finding the tonal pitch classes in a chord:
@@ -54,20 +129,65 @@
 //! assert_eq!(expected, tpcs);
 //! ```
 
-#[doc(inline)]
+pub mod abc;
 pub mod accidental;
-#[doc(inline)]
 pub mod alteration;
-#[doc(inline)]
+pub mod analysis;
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod batch;
+pub mod chord;
+pub mod clef;
+pub mod collections;
+pub mod consonance;
+pub mod corpus;
+pub mod cycle;
+pub mod dictionary;
+pub mod element;
+pub mod error;
+pub mod evaluation;
+#[cfg(feature = "extended-keys")]
+pub mod extended_key;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(feature = "pitch_calc", feature = "rust-music-theory"))]
+mod interop;
 pub mod interval;
-#[doc(inline)]
 pub mod key;
-#[doc(inline)]
+pub mod line_of_fifths;
+pub mod measure;
+pub mod melody;
+#[cfg(feature = "microtonal")]
+pub mod microtonal;
+pub mod midi;
+pub mod mode;
+pub mod musicxml;
+pub mod neo_riemannian;
+pub mod octave;
+pub mod parse;
+pub mod pitch;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(any(feature = "rand", feature = "proptest", feature = "arbitrary"))]
+mod random;
+pub mod repr;
+pub mod results;
+pub mod roman;
+pub mod scale;
+pub mod spelling;
 pub mod step;
-#[doc(inline)]
+pub mod tables;
+#[cfg(feature = "proptest")]
+pub mod testing;
 pub mod tpc;
+pub mod transform;
+pub mod tuning;
+pub mod voicing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod wire;
 
 pub use {
-    accidental::Accidental, alteration::Alteration, interval::Interval, key::Key, step::Step,
-    tpc::Tpc,
+    accidental::Accidental, alteration::Alteration, error::Error, interval::Interval, key::Key,
+    line_of_fifths::LineOfFifths, mode::Mode, octave::Octave, pitch::Pitch, step::Step, tpc::Tpc,
 };
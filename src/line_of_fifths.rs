@@ -0,0 +1,117 @@
+//! Generic operations on types whose values are positions on the line of
+//! fifths
+//!
+//! ```
+//! # use tonality::{Interval, Key, Tpc};
+//! # use tonality::LineOfFifths;
+//! /// Move any value a fifth up
+//! fn fifth_up<T: LineOfFifths>(value: T) -> Option<T> {
+//!     value.shift(1)
+//! }
+//! assert_eq!(Some(Tpc::G), fifth_up(Tpc::C));
+//! assert_eq!(Some(Key::D), fifth_up(Key::G));
+//! assert_eq!(Some(Interval::Maj2), fifth_up(Interval::P5));
+//! assert_eq!(None, fifth_up(Tpc::Bss));
+//! ```
+use crate::{Interval, Key, Tpc};
+
+/// A type whose values are consecutive positions on the line of fifths, where
+/// C, C major and the unison are at 0
+pub trait LineOfFifths: Copy {
+    /// The position on the line of fifths
+    fn fifths(self) -> i8;
+
+    /// The value at a position on the line of fifths, or None if the position
+    /// is outside the domain of the type
+    fn from_fifths(fifths: i8) -> Option<Self>;
+
+    /// The value a number of fifths up, or down if negative
+    #[must_use]
+    fn shift(self, fifths: i8) -> Option<Self> {
+        Self::from_fifths(self.fifths().checked_add(fifths)?)
+    }
+
+    /// The number of fifths from this value up to another
+    /// ```
+    /// # use tonality::{Key, LineOfFifths};
+    /// assert_eq!(-3, Key::A.fifths_to(Key::C));
+    /// ```
+    #[must_use]
+    fn fifths_to(self, other: Self) -> i8 {
+        other.fifths() - self.fifths()
+    }
+}
+
+macro_rules! impl_line_of_fifths {
+    ($($type:ty),*) => {
+        $(
+            impl LineOfFifths for $type {
+                fn fifths(self) -> i8 {
+                    self as i8
+                }
+
+                fn from_fifths(fifths: i8) -> Option<Self> {
+                    num_traits::FromPrimitive::from_i8(fifths)
+                }
+            }
+        )*
+    };
+}
+
+impl_line_of_fifths!(Tpc, Key, Interval);
+
+/// Every value from the lowest to the highest, in order along the line of
+/// fifths
+pub(crate) fn between<T: LineOfFifths>(min: T, max: T) -> impl DoubleEndedIterator<Item = T> {
+    (min.fifths()..=max.fifths()).filter_map(T::from_fifths)
+}
+
+/// The mean position of the values on the line of fifths, or None if there
+/// are no values.
+///
+/// The centroid of the notes of a passage is close to its key, which makes it
+/// useful for choosing between spellings.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::line_of_fifths::centroid;
+/// assert_eq!(Some(2.0), centroid(vec![Tpc::G, Tpc::D, Tpc::A]));
+/// assert_eq!(None, centroid(Vec::<Tpc>::new()));
+/// ```
+pub fn centroid<T, I>(values: I) -> Option<f64>
+where
+    T: LineOfFifths,
+    I: IntoIterator<Item = T>,
+{
+    let (sum, count) = values
+        .into_iter()
+        .fold((0_i32, 0_i32), |(sum, count), value| {
+            (sum + i32::from(value.fifths()), count + 1)
+        });
+    if count == 0 {
+        None
+    } else {
+        Some(f64::from(sum) / f64::from(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<T: LineOfFifths + PartialEq + std::fmt::Debug>(min: T, max: T) {
+        for fifths in min.fifths()..=max.fifths() {
+            let value = T::from_fifths(fifths).unwrap();
+            assert_eq!(fifths, value.fifths());
+        }
+        assert_eq!(None, min.shift(-1));
+        assert_eq!(None, max.shift(1));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        roundtrip(Tpc::MIN, Tpc::MAX);
+        roundtrip(Key::MIN, Key::MAX);
+        roundtrip(Interval::MIN, Interval::MAX);
+        assert_eq!(None, Tpc::C.shift(i8::MAX));
+    }
+}
@@ -0,0 +1,317 @@
+//! Accidentals in force within a measure, and courtesy accidentals across
+//! barlines and octaves
+use crate::element::Element;
+use crate::{Accidental, Key, Pitch, Step, Tpc};
+
+/// Tracks which accidentals are in force, as in notation where an accidental
+/// applies to the rest of the measure.
+///
+/// Accidentals are tracked per step, regardless of octave.
+/// ```
+/// # use tonality::{Accidental, Key, Step, Tpc};
+/// # use tonality::measure::AccidentalState;
+/// let mut state = AccidentalState::new(Key::G);
+/// assert_eq!(Tpc::Fs, state.resolve(Step::F));
+/// assert_eq!(Tpc::F, state.read(Step::F, Some(Accidental::Natural)));
+/// // The natural is still in force
+/// assert_eq!(Tpc::F, state.read(Step::F, None));
+/// assert_eq!(Some(Accidental::Sharp), state.note_needs_accidental(Tpc::Fs));
+/// state.barline();
+/// assert_eq!(None, state.note_needs_accidental(Tpc::Fs));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccidentalState {
+    key: Key,
+    in_force: [Option<Accidental>; 7],
+}
+
+impl AccidentalState {
+    /// The state at the start of a measure in a key
+    #[must_use]
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            in_force: [None; 7],
+        }
+    }
+
+    /// The key signature of the measure
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// The tonal pitch class of a step written without an accidental
+    pub fn resolve(&self, step: Step) -> Tpc {
+        step.resolve(self.in_force[step as usize], self.key)
+    }
+
+    /// The accidental to write before a note so that it reads as the given
+    /// tonal pitch class, or None if the key and earlier accidentals imply it
+    #[must_use]
+    pub fn note_needs_accidental(&self, tpc: Tpc) -> Option<Accidental> {
+        if self.resolve(tpc.step()) == tpc {
+            None
+        } else {
+            Some(tpc.accidental())
+        }
+    }
+
+    /// Read a note as written, and keep its accidental in force for the rest
+    /// of the measure
+    pub fn read(&mut self, step: Step, accidental: Option<Accidental>) -> Tpc {
+        if let Some(accidental) = accidental {
+            self.in_force[step as usize] = Some(accidental);
+        }
+        self.resolve(step)
+    }
+
+    /// Write a note, returning its step and the accidental it needs, and keep
+    /// that accidental in force for the rest of the measure
+    pub fn write(&mut self, tpc: Tpc) -> (Step, Option<Accidental>) {
+        let step = tpc.step();
+        let accidental = self.note_needs_accidental(tpc);
+        if let Some(accidental) = accidental {
+            self.in_force[step as usize] = Some(accidental);
+        }
+        (step, accidental)
+    }
+
+    /// Start a new measure, so that only the key signature is in force
+    pub fn barline(&mut self) {
+        self.in_force = [None; 7];
+    }
+
+    /// Change the key signature, which also starts a new measure
+    pub fn set_key(&mut self, key: Key) {
+        self.key = key;
+        self.barline();
+    }
+}
+
+/// Engraving rules for when to add courtesy accidentals, which are not
+/// required but remind the reader of an accidental that is no longer in force
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CourtesyRules {
+    /// Remind the reader of the key signature after a barline, the first time
+    /// a step altered in the previous measure appears
+    pub after_barline: bool,
+    /// Add an accidental if the same step in another octave was altered
+    /// earlier in the measure
+    pub other_octaves: bool,
+}
+
+impl Default for CourtesyRules {
+    fn default() -> Self {
+        Self {
+            after_barline: true,
+            other_octaves: true,
+        }
+    }
+}
+
+/// A note as written on the staff
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrittenNote {
+    /// The position on the staff
+    pub step: Step,
+    /// The octave of the step
+    pub octave: i8,
+    /// The accidental written before the note, if any
+    pub accidental: Option<Accidental>,
+    /// Whether the accidental is a courtesy accidental, which is often
+    /// printed in parentheses
+    pub courtesy: bool,
+}
+
+/// Chooses the accidentals to write before each note in a sequence of
+/// measures, including courtesy accidentals according to a set of rules.
+///
+/// Unlike `AccidentalState`, accidentals are only required in the octave
+/// where they were written, as is the convention in modern notation.
+/// ```
+/// # use tonality::{Accidental, Key, Pitch, Tpc};
+/// # use tonality::measure::{AccidentalEngraver, CourtesyRules};
+/// let mut engraver = AccidentalEngraver::new(Key::C, CourtesyRules::default());
+/// let note = engraver.write(Pitch::new(Tpc::Fs, 4));
+/// assert_eq!((Some(Accidental::Sharp), false), (note.accidental, note.courtesy));
+/// engraver.barline();
+/// let note = engraver.write(Pitch::new(Tpc::F, 4));
+/// assert_eq!((Some(Accidental::Natural), true), (note.accidental, note.courtesy));
+/// let note = engraver.write(Pitch::new(Tpc::F, 4));
+/// assert_eq!((None, false), (note.accidental, note.courtesy));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccidentalEngraver {
+    key: Key,
+    rules: CourtesyRules,
+    /// Accidentals written in this measure, by step and octave
+    current: Vec<(Step, i8, Accidental)>,
+    /// Accidentals written in the previous measure, by step and octave
+    previous: Vec<(Step, i8, Accidental)>,
+}
+
+impl AccidentalEngraver {
+    /// An engraver at the start of a piece in a key
+    #[must_use]
+    pub fn new(key: Key, rules: CourtesyRules) -> Self {
+        Self {
+            key,
+            rules,
+            current: Vec::new(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Choose the accidental for a note, and keep it in force for the rest of
+    /// the measure
+    pub fn write(&mut self, pitch: Pitch) -> WrittenNote {
+        let step = pitch.step();
+        let octave = pitch.octave;
+        let written = pitch.tpc.accidental();
+        let required = self.resolve(step, octave) != pitch.tpc;
+        let courtesy = !required && self.needs_courtesy(pitch);
+        let accidental = if required || courtesy {
+            self.current.retain(|&(s, o, _)| (s, o) != (step, octave));
+            self.current.push((step, octave, written));
+            Some(written)
+        } else {
+            None
+        };
+        WrittenNote {
+            step,
+            octave,
+            accidental,
+            courtesy,
+        }
+    }
+
+    /// Choose the accidental for a pitched note, as `write`. Rests and
+    /// unpitched notes have no accidental, and leave the accidentals in force
+    /// as they are.
+    /// ```
+    /// # use tonality::{Key, Pitch, Tpc};
+    /// # use tonality::element::Element;
+    /// # use tonality::measure::{AccidentalEngraver, CourtesyRules};
+    /// let mut engraver = AccidentalEngraver::new(Key::C, CourtesyRules::default());
+    /// assert_eq!(None, engraver.write_element(Element::Rest));
+    /// let note = engraver.write_element(Pitch::new(Tpc::Fs, 4).into()).unwrap();
+    /// assert!(note.accidental.is_some());
+    /// ```
+    pub fn write_element(&mut self, element: Element) -> Option<WrittenNote> {
+        element.pitch().map(|pitch| self.write(pitch))
+    }
+
+    /// Start a new measure
+    pub fn barline(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Change the key signature at a barline. Courtesy accidentals after the
+    /// barline are relative to the new key.
+    pub fn set_key(&mut self, key: Key) {
+        self.barline();
+        self.key = key;
+    }
+
+    /// The tonal pitch class of a note written without an accidental
+    fn resolve(&self, step: Step, octave: i8) -> Tpc {
+        let accidental = self
+            .current
+            .iter()
+            .find(|&&(s, o, _)| (s, o) == (step, octave))
+            .map(|&(_, _, accidental)| accidental);
+        step.resolve(accidental, self.key)
+    }
+
+    fn needs_courtesy(&self, pitch: Pitch) -> bool {
+        let step = pitch.step();
+        // Whether an accidental on the same step reads as another pitch class
+        let differs = |&(s, _, accidental): &(Step, i8, Accidental)| {
+            s == step && step.with_accidental(accidental) != pitch.tpc
+        };
+        let other_octave = self.rules.other_octaves
+            && self
+                .current
+                .iter()
+                .filter(|&&(_, o, _)| o != pitch.octave)
+                .any(differs);
+        let seen_this_measure = self.current.iter().any(|&(s, _, _)| s == step);
+        let after_barline =
+            self.rules.after_barline && !seen_this_measure && self.previous.iter().any(differs);
+        other_octave || after_barline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_chromatic_line() {
+        let mut state = AccidentalState::new(Key::C);
+        let written: Vec<_> = [Tpc::C, Tpc::Cs, Tpc::D, Tpc::Cs, Tpc::C]
+            .iter()
+            .map(|&tpc| state.write(tpc))
+            .collect();
+        let expected = vec![
+            (Step::C, None),
+            (Step::C, Some(Accidental::Sharp)),
+            (Step::D, None),
+            (Step::C, None),
+            (Step::C, Some(Accidental::Natural)),
+        ];
+        assert_eq!(expected, written);
+    }
+
+    #[test]
+    fn test_read_matches_write() {
+        let tpcs = [Tpc::Bb, Tpc::B, Tpc::Bbb, Tpc::Bb, Tpc::E, Tpc::Es];
+        let mut writer = AccidentalState::new(Key::F);
+        let mut reader = AccidentalState::new(Key::F);
+        for &tpc in &tpcs {
+            let (step, accidental) = writer.write(tpc);
+            assert_eq!(tpc, reader.read(step, accidental));
+        }
+    }
+
+    #[test]
+    fn test_other_octaves() {
+        let pitches = [Pitch::new(Tpc::Cs, 5), Pitch::new(Tpc::C, 4)];
+        let mut engraver = AccidentalEngraver::new(Key::C, CourtesyRules::default());
+        let written: Vec<_> = pitches.iter().map(|&p| engraver.write(p)).collect();
+        assert_eq!(Some(Accidental::Natural), written[1].accidental);
+        assert!(written[1].courtesy);
+
+        let rules = CourtesyRules {
+            other_octaves: false,
+            ..CourtesyRules::default()
+        };
+        let mut engraver = AccidentalEngraver::new(Key::C, rules);
+        let written: Vec<_> = pitches.iter().map(|&p| engraver.write(p)).collect();
+        assert_eq!(None, written[1].accidental);
+    }
+
+    #[test]
+    fn test_required_in_same_octave_only() {
+        let mut engraver = AccidentalEngraver::new(Key::D, CourtesyRules::default());
+        engraver.write(Pitch::new(Tpc::F, 4));
+        let note = engraver.write(Pitch::new(Tpc::F, 4));
+        assert_eq!(None, note.accidental);
+        let note = engraver.write(Pitch::new(Tpc::Fs, 5));
+        assert_eq!(
+            (Some(Accidental::Sharp), true),
+            (note.accidental, note.courtesy)
+        );
+    }
+
+    #[test]
+    fn test_only_first_note_after_barline() {
+        let mut engraver = AccidentalEngraver::new(Key::Bb, CourtesyRules::default());
+        engraver.write(Pitch::new(Tpc::E, 4));
+        engraver.barline();
+        assert!(engraver.write(Pitch::new(Tpc::Eb, 4)).courtesy);
+        assert_eq!(None, engraver.write(Pitch::new(Tpc::Eb, 5)).accidental);
+        engraver.barline();
+        assert_eq!(None, engraver.write(Pitch::new(Tpc::Eb, 4)).accidental);
+    }
+}
@@ -0,0 +1,278 @@
+//! Operations on sequences of notes
+use std::convert::TryFrom;
+
+use crate::element::{self, Element};
+use crate::interval::DirectedInterval;
+use crate::{Error, Interval, Mode, Tpc};
+
+/// Transpose every note of a melody by an interval.
+///
+/// If some notes would end up beyond double sharps or flats, the whole
+/// passage is respelled enharmonically, so that the notes keep their
+/// relationships to each other. Returns None only if the melody is too wide
+/// on the line of fifths to fit in the domain of `Tpc` at all.
+/// ```
+/// # use tonality::{Interval, Tpc};
+/// # use tonality::melody;
+/// let melody = [Tpc::Ds, Tpc::Fss, Tpc::As];
+/// // Up an augmented second, F double sharp would become G triple sharp, so
+/// // the whole melody is respelled, starting on F sharp instead of E double
+/// // sharp
+/// let transposed = melody::transpose(melody.iter().copied(), Interval::Aug2).unwrap();
+/// assert_eq!(vec![Tpc::Fs, Tpc::As, Tpc::Cs], transposed);
+///
+/// let transposed = melody::transpose(melody.iter().copied(), Interval::Min2).unwrap();
+/// assert_eq!(vec![Tpc::E, Tpc::Gs, Tpc::B], transposed);
+/// ```
+#[must_use]
+pub fn transpose<I>(melody: I, interval: Interval) -> Option<Vec<Tpc>>
+where
+    I: IntoIterator<Item = Tpc>,
+{
+    let fifths: Vec<i8> = melody
+        .into_iter()
+        .map(|tpc| tpc as i8 + interval as i8)
+        .collect();
+    let (Some(&lowest), Some(&highest)) = (fifths.iter().min(), fifths.iter().max()) else {
+        return Some(Vec::new());
+    };
+    // Move by the fewest enharmonic shifts that bring the passage in range
+    let delta = Tpc::DELTA_ENHARMONIC;
+    let shift = if highest > Tpc::MAX as i8 {
+        -((highest - Tpc::MAX as i8 + delta - 1) / delta) * delta
+    } else if lowest < Tpc::MIN as i8 {
+        ((Tpc::MIN as i8 - lowest + delta - 1) / delta) * delta
+    } else {
+        0
+    };
+    fifths
+        .iter()
+        .map(|&value| num_traits::FromPrimitive::from_i8(value + shift))
+        .collect()
+}
+
+/// The error from transposing a passage note by note, telling which note
+/// would have been out of range
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransposeError {
+    /// The index of the first note that could not be transposed
+    pub index: usize,
+    /// Why the note could not be transposed
+    pub error: Error,
+}
+
+/// Transpose every note by an interval, keeping each note's spelling.
+///
+/// Unlike `transpose`, the passage is never respelled, and unlike
+/// transposing each note with `filter_map`, no note is silently dropped.
+///
+/// # Errors
+///
+/// Returns the index of the first note that would be beyond double sharp or
+/// double flat.
+/// ```
+/// # use tonality::{Interval, Tpc};
+/// # use tonality::melody::transpose_all;
+/// let notes = [Tpc::C, Tpc::Fs, Tpc::Bss];
+/// assert_eq!(Ok(vec![Tpc::D, Tpc::Gs]), transpose_all(&notes[..2], Interval::Maj2));
+/// let err = transpose_all(&notes, Interval::Maj2).unwrap_err();
+/// assert_eq!(2, err.index);
+/// assert!(err.error.is_sharpwards());
+/// ```
+pub fn transpose_all(notes: &[Tpc], interval: Interval) -> Result<Vec<Tpc>, TransposeError> {
+    notes
+        .iter()
+        .enumerate()
+        .map(|(index, tpc)| {
+            tpc.try_add(interval)
+                .map_err(|error| TransposeError { index, error })
+        })
+        .collect()
+}
+
+/// Transpose every note by an interval in place, keeping each note's
+/// spelling
+///
+/// # Errors
+///
+/// Leaves every note unchanged and returns the index of the first note that
+/// would be beyond double sharp or double flat.
+/// ```
+/// # use tonality::{Interval, Tpc};
+/// # use tonality::melody::transpose_in_place;
+/// let mut notes = [Tpc::C, Tpc::Fss];
+/// transpose_in_place(&mut notes, Interval::Min3).unwrap();
+/// assert_eq!([Tpc::Eb, Tpc::As], notes);
+/// // A sharp up an augmented third would be C triple sharp
+/// assert!(transpose_in_place(&mut notes, Interval::Aug3).is_err());
+/// assert_eq!([Tpc::Eb, Tpc::As], notes);
+/// ```
+pub fn transpose_in_place(notes: &mut [Tpc], interval: Interval) -> Result<(), TransposeError> {
+    for (index, tpc) in notes.iter().enumerate() {
+        if let Err(error) = tpc.try_add(interval) {
+            return Err(TransposeError { index, error });
+        }
+    }
+    for tpc in notes.iter_mut() {
+        if let Ok(transposed) = tpc.try_add(interval) {
+            *tpc = transposed;
+        }
+    }
+    Ok(())
+}
+
+/// Respell a melody from one mode to its parallel mode on the same tonic,
+/// altering each note by the difference between the modes at its degree, as
+/// `Mode::map_degree`. Chromatic notes keep their alteration relative to the
+/// degree.
+///
+/// # Errors
+///
+/// Returns the index of the first note that would be beyond double sharp or
+/// double flat.
+/// ```
+/// # use tonality::{Mode, Tpc};
+/// # use tonality::melody::change_mode;
+/// // "Twinkle, twinkle" in D major, and then in D minor
+/// let melody = [Tpc::D, Tpc::A, Tpc::B, Tpc::A, Tpc::G, Tpc::Fs, Tpc::E, Tpc::D];
+/// let minor = change_mode(&melody, Tpc::D, Mode::Major, Mode::Minor).unwrap();
+/// assert_eq!(
+///     vec![Tpc::D, Tpc::A, Tpc::Bb, Tpc::A, Tpc::G, Tpc::F, Tpc::E, Tpc::D],
+///     minor
+/// );
+/// // A raised fourth stays raised in Dorian, while the leading tone becomes
+/// // the subtonic
+/// let dorian = change_mode(&[Tpc::Gs, Tpc::Cs], Tpc::D, Mode::Major, Mode::Dorian);
+/// assert_eq!(Ok(vec![Tpc::Gs, Tpc::C]), dorian);
+/// ```
+pub fn change_mode(
+    notes: &[Tpc],
+    tonic: Tpc,
+    from: Mode,
+    to: Mode,
+) -> Result<Vec<Tpc>, TransposeError> {
+    notes
+        .iter()
+        .enumerate()
+        .map(|(index, &tpc)| {
+            let degree = tpc.step() as isize - tonic.step() as isize;
+            let alteration = Mode::map_degree(from, to, degree);
+            Tpc::try_from(tpc as i8 + alteration * Tpc::DELTA_SEMITONE)
+                .map_err(|error| TransposeError { index, error })
+        })
+        .collect()
+}
+
+/// The directed interval from each pitch of a melody to the next. Yields
+/// one item less than the pitches.
+///
+/// An item is None where the notes are an octave or more apart, or the
+/// interval is more than augmented or diminished; `Pitch::interval_to`
+/// measures those as compound intervals. The melody may also be a stream of
+/// `Element`s, where an item is None after a rest and unpitched notes are
+/// skipped, as described in the `element` module.
+/// ```
+/// # use tonality::{Interval, Pitch, Tpc};
+/// # use tonality::melody::directed_intervals;
+/// let melody = [Pitch::new(Tpc::E, 4), Pitch::new(Tpc::Cs, 4), Pitch::new(Tpc::Cs, 5)];
+/// let intervals: Vec<_> = directed_intervals(melody.iter().copied()).collect();
+/// assert_eq!(vec![Some(Interval::Min3.down()), None], intervals);
+/// ```
+pub fn directed_intervals<I>(melody: I) -> impl Iterator<Item = Option<DirectedInterval>>
+where
+    I: IntoIterator,
+    I::Item: Into<Element>,
+{
+    let elements = melody.into_iter().map(Into::into);
+    element::pairs(elements, Element::pitch).map(|pair| {
+        pair.and_then(|(previous, pitch)| previous.interval_to(pitch))
+            .filter(|interval| interval.octaves == 0)
+            .map(|interval| {
+                if interval.descending {
+                    interval.interval.down()
+                } else {
+                    interval.interval.up()
+                }
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pitch;
+
+    #[test]
+    fn test_in_range_is_note_by_note() {
+        let melody = [Tpc::C, Tpc::E, Tpc::G];
+        let transposed = transpose(melody.iter().copied(), Interval::Maj3).unwrap();
+        assert_eq!(vec![Tpc::E, Tpc::Gs, Tpc::B], transposed);
+    }
+
+    #[test]
+    fn test_flatwards_respelling() {
+        let melody = [Tpc::Fbb, Tpc::Bb];
+        let transposed = transpose(melody.iter().copied(), Interval::P4).unwrap();
+        assert_eq!(vec![Tpc::Ab, Tpc::Ds], transposed);
+    }
+
+    #[test]
+    fn test_too_wide() {
+        let melody = [Tpc::Fbb, Tpc::Bss];
+        assert_eq!(None, transpose(melody.iter().copied(), Interval::P5));
+        assert_eq!(Some(Vec::new()), transpose(Vec::new(), Interval::P5));
+    }
+
+    #[test]
+    fn test_transpose_all_reports_first_failure() {
+        let notes = [Tpc::Fbb, Tpc::C, Tpc::Cbb];
+        let err = transpose_all(&notes, Interval::P4).unwrap_err();
+        assert_eq!(0, err.index);
+        assert!(err.error.is_flatwards());
+        assert_eq!(Ok(Vec::new()), transpose_all(&[], Interval::P4));
+    }
+
+    #[test]
+    fn test_change_mode_and_back() {
+        let scale: Vec<Tpc> = crate::Key::Bb.scale().collect();
+        let minor = change_mode(&scale, Tpc::Bb, Mode::Major, Mode::Minor).unwrap();
+        let expected: Vec<Tpc> = crate::Key::Db.modal_scale(Mode::Minor).collect();
+        assert_eq!(expected, minor);
+        let major = change_mode(&minor, Tpc::Bb, Mode::Minor, Mode::Major).unwrap();
+        assert_eq!(scale, major);
+        let err = change_mode(&[Tpc::C, Tpc::Dbb], Tpc::Bb, Mode::Major, Mode::Minor);
+        assert_eq!(1, err.unwrap_err().index);
+    }
+
+    #[test]
+    fn test_directed_intervals_retrace_melody() {
+        let melody = [
+            Pitch::new(Tpc::G, 4),
+            Pitch::new(Tpc::Fs, 4),
+            Pitch::new(Tpc::B, 3),
+            Pitch::new(Tpc::Bs, 3),
+            Pitch::new(Tpc::C, 4),
+        ];
+        let mut pitch = melody[0];
+        for (interval, &next) in directed_intervals(melody.iter().copied()).zip(&melody[1..]) {
+            let interval = interval.unwrap();
+            assert_eq!(next.midi() - pitch.midi(), i16::from(interval.semitones()));
+            pitch = (pitch + interval).unwrap();
+            assert_eq!(next, pitch);
+        }
+    }
+
+    #[test]
+    fn test_directed_intervals_of_elements() {
+        let (g, a) = (Pitch::new(Tpc::G, 4), Pitch::new(Tpc::A, 4));
+        let melody = [
+            Element::from(g),
+            Element::Unpitched,
+            a.into(),
+            Element::Rest,
+            g.into(),
+        ];
+        let intervals: Vec<_> = directed_intervals(melody.iter().copied()).collect();
+        assert_eq!(vec![Some(Interval::Maj2.up()), None], intervals);
+    }
+}
@@ -0,0 +1,275 @@
+//! Quarter-tone accidentals and pitch classes
+//!
+//! A `MicroTpc` is a `Tpc` inflected a quarter tone up or down, which covers
+//! the half and three-quarter sharps and flats used in maqam and contemporary
+//! music. Intervals still move along the line of fifths, so transposing keeps
+//! the inflection.
+//!
+//! ```
+//! # use tonality::{Interval, Tpc};
+//! # use tonality::microtonal::{Inflection, MicroAccidental, MicroTpc};
+//! let e_half_flat = MicroTpc::new(Tpc::E, Inflection::Down).unwrap();
+//! assert_eq!(MicroAccidental::HalfFlat, e_half_flat.accidental());
+//! let b_half_flat = (e_half_flat + Interval::P5).unwrap();
+//! assert_eq!("B𝄳", b_half_flat.to_string());
+//! assert_eq!("Bd", format!("{:#}", b_half_flat));
+//! ```
+use std::fmt;
+
+use num_derive::FromPrimitive;
+
+use crate::repr::Contiguous;
+use crate::{Accidental, Alteration, Interval, Key, Step, Tpc};
+
+/// An accidental in quarter tones, from double flat to double sharp
+///
+/// Unicode has no glyphs for three-quarter accidentals, so they are displayed
+/// as a sharp or flat followed by a quarter-tone glyph. In ASCII, the half
+/// flat is `d`, after the reversed flat it resembles, and the half sharp is
+/// `+`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, FromPrimitive)]
+#[allow(missing_docs)]
+pub enum MicroAccidental {
+    DblFlat = -4,
+    ThreeQuarterFlat,
+    Flat,
+    HalfFlat,
+    Natural,
+    HalfSharp,
+    Sharp,
+    ThreeQuarterSharp,
+    DblSharp,
+}
+
+/// A quarter-tone deviation from a tonal pitch class
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[allow(missing_docs)]
+pub enum Inflection {
+    Down = -1,
+    #[default]
+    None = 0,
+    Up = 1,
+}
+
+/// A tonal pitch class with a quarter-tone inflection
+///
+/// Spellings are unique: C half sharp is C inflected up, never C sharp
+/// inflected down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[must_use]
+pub struct MicroTpc {
+    tpc: Tpc,
+    inflection: Inflection,
+}
+
+impl MicroAccidental {
+    /// The accidental in quarter tones, between -4 and 4
+    #[must_use]
+    pub fn quarter_tones(self) -> i8 {
+        self as i8
+    }
+
+    /// The equivalent semitone accidental, or None for half and
+    /// three-quarter accidentals
+    /// ```
+    /// # use tonality::Accidental;
+    /// # use tonality::microtonal::MicroAccidental;
+    /// assert_eq!(Some(Accidental::Flat), MicroAccidental::Flat.to_accidental());
+    /// assert_eq!(None, MicroAccidental::HalfFlat.to_accidental());
+    /// ```
+    #[must_use]
+    pub fn to_accidental(self) -> Option<Accidental> {
+        match self {
+            MicroAccidental::DblFlat => Some(Accidental::DblFlat),
+            MicroAccidental::Flat => Some(Accidental::Flat),
+            MicroAccidental::Natural => Some(Accidental::Natural),
+            MicroAccidental::Sharp => Some(Accidental::Sharp),
+            MicroAccidental::DblSharp => Some(Accidental::DblSharp),
+            _ => None,
+        }
+    }
+
+    fn symbol(self, ascii: bool) -> &'static str {
+        match (self, ascii) {
+            (MicroAccidental::ThreeQuarterFlat, false) => "♭𝄳",
+            (MicroAccidental::ThreeQuarterFlat, true) => "db",
+            (MicroAccidental::HalfFlat, false) => "𝄳",
+            (MicroAccidental::HalfFlat, true) => "d",
+            (MicroAccidental::HalfSharp, false) => "𝄲",
+            (MicroAccidental::HalfSharp, true) => "+",
+            (MicroAccidental::ThreeQuarterSharp, false) => "♯𝄲",
+            (MicroAccidental::ThreeQuarterSharp, true) => "#+",
+            (accidental, ascii) => accidental
+                .to_accidental()
+                .map_or("", |accidental| accidental.symbol(ascii)),
+        }
+    }
+}
+
+impl From<Accidental> for MicroAccidental {
+    fn from(accidental: Accidental) -> Self {
+        // Every accidental is twice as many quarter tones
+        num_traits::FromPrimitive::from_i8(2 * accidental as i8).unwrap_or(MicroAccidental::Natural)
+    }
+}
+
+impl fmt::Display for MicroAccidental {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol(f.alternate()))
+    }
+}
+
+impl MicroTpc {
+    /// Inflect a tonal pitch class. Returns None if the accidental would be
+    /// beyond a double sharp or flat.
+    #[must_use]
+    pub fn new(tpc: Tpc, inflection: Inflection) -> Option<Self> {
+        let quarter_tones = 2 * tpc.accidental() as i8 + inflection as i8;
+        let accidental = num_traits::FromPrimitive::from_i8(quarter_tones)?;
+        Some(Self::with_accidental(tpc.step(), accidental))
+    }
+
+    /// The pitch class resulting from applying an accidental to a step
+    /// ```
+    /// # use tonality::{Step, Tpc};
+    /// # use tonality::microtonal::{Inflection, MicroAccidental, MicroTpc};
+    /// let f = MicroTpc::with_accidental(Step::F, MicroAccidental::ThreeQuarterSharp);
+    /// assert_eq!(Tpc::Fs, f.tpc());
+    /// assert_eq!(Inflection::Up, f.inflection());
+    /// ```
+    pub fn with_accidental(step: Step, accidental: MicroAccidental) -> Self {
+        let quarter_tones = accidental.quarter_tones();
+        // Half accidentals are inflections of the accidental nearer natural
+        let semitones = Accidental::in_range(quarter_tones / 2);
+        let inflection = match quarter_tones % 2 {
+            -1 => Inflection::Down,
+            1 => Inflection::Up,
+            _ => Inflection::None,
+        };
+        Self {
+            tpc: step.with_accidental(semitones),
+            inflection,
+        }
+    }
+
+    /// The uninflected tonal pitch class
+    pub fn tpc(self) -> Tpc {
+        self.tpc
+    }
+
+    /// The quarter-tone inflection of the tonal pitch class
+    #[must_use]
+    pub fn inflection(self) -> Inflection {
+        self.inflection
+    }
+
+    /// The position on the staff
+    pub fn step(self) -> Step {
+        self.tpc.step()
+    }
+
+    /// The accidental of the pitch class, without the context of a key
+    #[must_use]
+    pub fn accidental(self) -> MicroAccidental {
+        let quarter_tones = 2 * self.tpc.accidental() as i8 + self.inflection as i8;
+        // Inflections never take a tpc beyond the micro accidentals
+        num_traits::FromPrimitive::from_i8(quarter_tones).unwrap_or(MicroAccidental::Natural)
+    }
+
+    /// The number of quarter tones by which the pitch class is altered with
+    /// respect to the key
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// # use tonality::microtonal::{Inflection, MicroTpc};
+    /// let b_half_flat = MicroTpc::new(Tpc::B, Inflection::Down).unwrap();
+    /// assert_eq!(-1, b_half_flat.alteration(Key::C));
+    /// assert_eq!(1, b_half_flat.alteration(Key::F));
+    /// ```
+    #[must_use]
+    pub fn alteration(self, key: Key) -> Alteration {
+        2 * self.tpc.alteration(key) + self.inflection as i8
+    }
+
+    /// The pitch class in 24-tone equal temperament, where C is 0 and C half
+    /// sharp is 1
+    #[must_use]
+    pub fn quarter_tone_class(self) -> u8 {
+        let quarter_tones = 2 * i16::from(self.tpc.pitch_class()) + self.inflection as i16;
+        // Between 0 and 23
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let quarter_tone_class = quarter_tones.rem_euclid(24) as u8;
+        quarter_tone_class
+    }
+}
+
+impl From<Tpc> for MicroTpc {
+    fn from(tpc: Tpc) -> Self {
+        Self {
+            tpc,
+            inflection: Inflection::None,
+        }
+    }
+}
+
+impl fmt::Display for MicroTpc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.step())?;
+        match self.accidental() {
+            MicroAccidental::Natural => Ok(()),
+            accidental => f.write_str(accidental.symbol(f.alternate())),
+        }
+    }
+}
+
+impl std::ops::Add<Interval> for MicroTpc {
+    type Output = Option<MicroTpc>;
+
+    fn add(self, rhs: Interval) -> Self::Output {
+        Self::new((self.tpc + rhs)?, self.inflection)
+    }
+}
+
+impl std::ops::Sub<Interval> for MicroTpc {
+    type Output = Option<MicroTpc>;
+
+    fn sub(self, rhs: Interval) -> Self::Output {
+        Self::new((self.tpc - rhs)?, self.inflection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_spelling() {
+        let c_half_sharp = MicroTpc::new(Tpc::C, Inflection::Up);
+        assert_eq!(c_half_sharp, MicroTpc::new(Tpc::Cs, Inflection::Down));
+        let c_half_flat = MicroTpc::new(Tpc::C, Inflection::Down);
+        assert_eq!(c_half_flat, MicroTpc::new(Tpc::Cb, Inflection::Up));
+    }
+
+    #[test]
+    fn test_accidental_roundtrip() {
+        for quarter_tones in -4..=4 {
+            let accidental = num_traits::FromPrimitive::from_i8(quarter_tones).unwrap();
+            let tpc = MicroTpc::with_accidental(Step::G, accidental);
+            assert_eq!(accidental, tpc.accidental());
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(None, MicroTpc::new(Tpc::Gss, Inflection::Up));
+        let e_three_quarter_sharp = MicroTpc::new(Tpc::Es, Inflection::Up).unwrap();
+        assert_eq!(None, e_three_quarter_sharp + Interval::Aug4);
+    }
+
+    #[test]
+    fn test_quarter_tone_class() {
+        let b_half_sharp = MicroTpc::new(Tpc::B, Inflection::Up).unwrap();
+        assert_eq!(23, b_half_sharp.quarter_tone_class());
+        let c_half_flat = MicroTpc::new(Tpc::C, Inflection::Down).unwrap();
+        assert_eq!(23, c_half_flat.quarter_tone_class());
+    }
+}
@@ -0,0 +1,364 @@
+//! Spelling a stream of MIDI events as notation
+//!
+//! `MidiSpeller` combines spelling relative to the key signature with the
+//! accidentals in force in each measure. For streams without a key
+//! signature, `detect_key` estimates one from the notes. With the `midly`
+//! feature, `spell_track` runs a whole track of a MIDI file through both.
+use crate::analysis;
+use crate::measure::{AccidentalEngraver, CourtesyRules};
+use crate::spelling::{self, RespellPolicy};
+use crate::{Accidental, Key, Mode, Pitch};
+
+/// An event in a MIDI stream, at an absolute time in ticks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MidiEvent {
+    /// A note starting
+    Note {
+        /// The time of the event
+        tick: u64,
+        /// The MIDI note number
+        note: u8,
+    },
+    /// A change of key signature, which takes effect from the next note
+    KeySignature {
+        /// The time of the event
+        tick: u64,
+        /// The key signature
+        key: Key,
+        /// Major or minor, as given in MIDI files
+        mode: Mode,
+    },
+    /// A change of time signature, which starts a new measure
+    TimeSignature {
+        /// The time of the event
+        tick: u64,
+        /// The length of each measure from now on
+        ticks_per_measure: u64,
+    },
+}
+
+/// A note spelled for notation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpelledNote {
+    /// The time of the note
+    pub tick: u64,
+    /// The spelled pitch
+    pub pitch: Pitch,
+    /// The accidental to write before the note, if any
+    pub accidental: Option<Accidental>,
+    /// Whether the accidental is a courtesy accidental
+    pub courtesy: bool,
+}
+
+/// Spells MIDI notes as they arrive, keeping track of the key signature and
+/// measures. Events must arrive in order of time.
+///
+/// In major keys, chromatic notes are spelled as in
+/// `RespellPolicy::KeyRelative`, and in minor keys as in
+/// `RespellPolicy::KeyRelativeMinor`. Other modes are spelled as major keys.
+/// ```
+/// # use tonality::{Accidental, Key, Mode, Pitch, Tpc};
+/// # use tonality::midi::{MidiEvent, MidiSpeller};
+/// let mut speller = MidiSpeller::new(Key::C, Mode::Minor, 1920);
+/// let note = speller.process(MidiEvent::Note { tick: 0, note: 68 }).unwrap();
+/// assert_eq!(Pitch::new(Tpc::Gs, 4), note.pitch);
+/// assert_eq!(Some(Accidental::Sharp), note.accidental);
+/// // The sharp is still in force
+/// let note = speller.process(MidiEvent::Note { tick: 960, note: 68 }).unwrap();
+/// assert_eq!(None, note.accidental);
+/// // But not in the next measure
+/// let note = speller.process(MidiEvent::Note { tick: 1920, note: 68 }).unwrap();
+/// assert_eq!(Some(Accidental::Sharp), note.accidental);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MidiSpeller {
+    policy: RespellPolicy,
+    engraver: AccidentalEngraver,
+    ticks_per_measure: u64,
+    measure_start: u64,
+}
+
+impl MidiSpeller {
+    /// A speller starting at tick 0 in a key, with measures of the given
+    /// length
+    #[must_use]
+    pub fn new(key: Key, mode: Mode, ticks_per_measure: u64) -> Self {
+        Self {
+            policy: policy(key, mode),
+            engraver: AccidentalEngraver::new(key, CourtesyRules::default()),
+            ticks_per_measure: ticks_per_measure.max(1),
+            measure_start: 0,
+        }
+    }
+
+    /// Process an event, returning the spelled note if it is a note
+    pub fn process(&mut self, event: MidiEvent) -> Option<SpelledNote> {
+        match event {
+            MidiEvent::Note { tick, note } => {
+                self.advance_to(tick);
+                let pitch = Pitch::from_midi(note, self.policy);
+                let written = self.engraver.write(pitch);
+                Some(SpelledNote {
+                    tick,
+                    pitch,
+                    accidental: written.accidental,
+                    courtesy: written.courtesy,
+                })
+            }
+            MidiEvent::KeySignature { tick, key, mode } => {
+                self.advance_to(tick);
+                self.policy = policy(key, mode);
+                self.engraver.set_key(key);
+                None
+            }
+            MidiEvent::TimeSignature {
+                tick,
+                ticks_per_measure,
+            } => {
+                self.advance_to(tick);
+                if tick > self.measure_start {
+                    self.engraver.barline();
+                }
+                self.measure_start = tick;
+                self.ticks_per_measure = ticks_per_measure.max(1);
+                None
+            }
+        }
+    }
+
+    /// Pass the barlines before a tick
+    fn advance_to(&mut self, tick: u64) {
+        while tick >= self.measure_start + self.ticks_per_measure {
+            self.measure_start += self.ticks_per_measure;
+            self.engraver.barline();
+        }
+    }
+}
+
+/// The estimated major key of MIDI notes, for streams without a key
+/// signature, or None if there are no notes. The notes are spelled as by
+/// `spelling::spell_atonal`, and weighted equally in
+/// `analysis::weighted_key`.
+/// ```
+/// # use tonality::Key;
+/// # use tonality::midi::detect_key;
+/// // A melody in E flat major
+/// assert_eq!(Some(Key::Eb), detect_key(vec![63, 67, 70, 68, 65, 62, 63]));
+/// assert_eq!(None, detect_key(vec![]));
+/// ```
+#[must_use]
+pub fn detect_key<I>(notes: I) -> Option<Key>
+where
+    I: IntoIterator<Item = u8>,
+{
+    let tpcs = spelling::spell_atonal(notes.into_iter().map(|note| note % 12));
+    analysis::weighted_key(tpcs.into_iter().map(|tpc| (tpc, 1.0)))
+}
+
+fn policy(key: Key, mode: Mode) -> RespellPolicy {
+    match mode {
+        Mode::Minor => RespellPolicy::KeyRelativeMinor(key),
+        _ => RespellPolicy::KeyRelative(key),
+    }
+}
+
+/// Spell the notes of a track in a MIDI file, given the number of ticks per
+/// quarter note from the file header. The track starts in 4/4 time until a
+/// time signature event says otherwise. Until the first key signature event,
+/// the notes are spelled in the major key that `detect_key` estimates from
+/// them, or in C major if there are none. Key signatures with more than
+/// seven sharps or flats are ignored, as are time signatures with a beat
+/// shorter than a tick.
+#[cfg(feature = "midly")]
+#[must_use]
+pub fn spell_track(track: &[midly::TrackEvent<'_>], ticks_per_quarter: u16) -> Vec<SpelledNote> {
+    use midly::{MetaMessage, MidiMessage, TrackEventKind};
+    use num_traits::FromPrimitive;
+
+    let ticks_per_quarter = u64::from(ticks_per_quarter);
+    let is_key_signature = |kind: &TrackEventKind<'_>| match kind {
+        TrackEventKind::Meta(MetaMessage::KeySignature(sharps, _)) => {
+            Key::from_i8(*sharps).is_some()
+        }
+        _ => false,
+    };
+    let unsigned = track
+        .iter()
+        .take_while(|event| !is_key_signature(&event.kind))
+        .filter_map(|event| match event.kind {
+            TrackEventKind::Midi {
+                message: MidiMessage::NoteOn { key, vel },
+                ..
+            } if vel > 0 => Some(key.as_int()),
+            _ => None,
+        });
+    let key = detect_key(unsigned).unwrap_or(Key::C);
+    let mut speller = MidiSpeller::new(key, Mode::Major, 4 * ticks_per_quarter);
+    let mut tick = 0;
+    let mut notes = Vec::new();
+    for event in track {
+        tick += u64::from(event.delta.as_int());
+        let event = match event.kind {
+            TrackEventKind::Midi {
+                message: MidiMessage::NoteOn { key, vel },
+                ..
+            } if vel > 0 => MidiEvent::Note {
+                tick,
+                note: key.as_int(),
+            },
+            TrackEventKind::Meta(MetaMessage::KeySignature(sharps, minor)) => {
+                let Some(key) = Key::from_i8(sharps) else {
+                    continue;
+                };
+                let mode = if minor { Mode::Minor } else { Mode::Major };
+                MidiEvent::KeySignature { tick, key, mode }
+            }
+            TrackEventKind::Meta(MetaMessage::TimeSignature(numerator, denominator, ..)) => {
+                // The denominator is a power of two
+                let beat = match (4 * ticks_per_quarter).checked_shr(u32::from(denominator)) {
+                    Some(beat) if beat > 0 => beat,
+                    _ => continue,
+                };
+                MidiEvent::TimeSignature {
+                    tick,
+                    ticks_per_measure: u64::from(numerator) * beat,
+                }
+            }
+            _ => continue,
+        };
+        notes.extend(speller.process(event));
+    }
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tpc;
+
+    #[test]
+    fn test_key_change() {
+        let mut speller = MidiSpeller::new(Key::C, Mode::Major, 4);
+        let note = speller.process(MidiEvent::Note { tick: 0, note: 70 });
+        assert_eq!(Tpc::Bb, note.unwrap().pitch.tpc);
+        let key = MidiEvent::KeySignature {
+            tick: 4,
+            key: Key::B,
+            mode: Mode::Major,
+        };
+        assert_eq!(None, speller.process(key));
+        let note = speller
+            .process(MidiEvent::Note { tick: 4, note: 70 })
+            .unwrap();
+        assert_eq!(Tpc::As, note.pitch.tpc);
+        assert_eq!(None, note.accidental);
+    }
+
+    #[test]
+    fn test_time_signature() {
+        let mut speller = MidiSpeller::new(Key::C, Mode::Major, 4);
+        speller.process(MidiEvent::Note { tick: 0, note: 66 });
+        let time = MidiEvent::TimeSignature {
+            tick: 2,
+            ticks_per_measure: 3,
+        };
+        speller.process(time);
+        // A new measure started at tick 2, and the next one starts at tick 5
+        let note = speller
+            .process(MidiEvent::Note { tick: 4, note: 66 })
+            .unwrap();
+        assert_eq!(Some(Accidental::Sharp), note.accidental);
+        let note = speller
+            .process(MidiEvent::Note { tick: 4, note: 66 })
+            .unwrap();
+        assert_eq!(None, note.accidental);
+    }
+
+    #[cfg(feature = "midly")]
+    #[test]
+    fn test_spell_track() {
+        use midly::{MetaMessage, MidiMessage, TrackEvent, TrackEventKind};
+
+        let note_on = |delta: u32, key: u8| TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Midi {
+                channel: 0.into(),
+                message: MidiMessage::NoteOn {
+                    key: key.into(),
+                    vel: 64.into(),
+                },
+            },
+        };
+        let track = [
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::KeySignature(-3, true)),
+            },
+            note_on(0, 60),
+            note_on(480, 71),
+            note_on(480, 63),
+        ];
+        let tpcs: Vec<Tpc> = spell_track(&track, 480)
+            .iter()
+            .map(|note| note.pitch.tpc)
+            .collect();
+        assert_eq!(vec![Tpc::C, Tpc::B, Tpc::Eb], tpcs);
+
+        // Without a key signature, the key is estimated from the notes
+        let d_major: Vec<TrackEvent<'_>> = [62, 64, 66, 67, 69, 71, 73, 74]
+            .iter()
+            .map(|&key| note_on(480, key))
+            .collect();
+        let notes = spell_track(&d_major, 480);
+        assert_eq!(Tpc::Fs, notes[2].pitch.tpc);
+        assert_eq!(None, notes[2].accidental);
+        assert_eq!(Tpc::Cs, notes[6].pitch.tpc);
+    }
+
+    #[cfg(feature = "midly")]
+    #[test]
+    fn test_beat_shorter_than_a_tick() {
+        use midly::{MetaMessage, MidiMessage, TrackEvent, TrackEventKind};
+
+        let note_on = |delta: u32, key: u8| TrackEvent {
+            delta: delta.into(),
+            kind: TrackEventKind::Midi {
+                channel: 0.into(),
+                message: MidiMessage::NoteOn {
+                    key: key.into(),
+                    vel: 64.into(),
+                },
+            },
+        };
+        let track = [
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::KeySignature(0, false)),
+            },
+            note_on(0, 66),
+            // A denominator of 2^64 is beyond the width of the tick count
+            TrackEvent {
+                delta: 0.into(),
+                kind: TrackEventKind::Meta(MetaMessage::TimeSignature(3, 64, 24, 8)),
+            },
+            note_on(480, 66),
+        ];
+        let notes = spell_track(&track, 480);
+        // The time signature is ignored, so both notes are in the first
+        // measure of 4/4
+        assert_eq!(Some(Accidental::Sharp), notes[0].accidental);
+        assert_eq!(None, notes[1].accidental);
+    }
+
+    #[test]
+    fn test_detect_key() {
+        assert_eq!(
+            Some(Key::A),
+            detect_key(vec![69, 71, 73, 74, 76, 78, 80, 81])
+        );
+        assert_eq!(
+            Some(Key::Bb),
+            detect_key(vec![70, 72, 74, 75, 77, 79, 81, 82])
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! The diatonic modes
+use num_derive::FromPrimitive;
+
+use crate::repr::Contiguous;
+use crate::{Alteration, Interval, Key, Tpc};
+
+/// One of the seven diatonic modes. A key signature has one tonic in each
+/// mode, such as E♭ major and C minor for three flats.
+///
+/// The numeric value is the scale degree of the major key signature on which
+/// the mode starts, so that Dorian starts on the second degree, 1.
+/// ```
+/// # use tonality::{Key, Mode, Tpc};
+/// assert_eq!(Tpc::C, Key::Eb.tonic(Mode::Minor));
+/// assert_eq!(Tpc::E, Key::D.tonic(Mode::Dorian));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, FromPrimitive)]
+#[must_use]
+#[allow(missing_docs)]
+#[repr(i8)]
+pub enum Mode {
+    #[default]
+    Major = 0,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Minor,
+    Locrian,
+}
+
+impl Mode {
+    /// All modes, in the order of the degrees they start on
+    pub const ALL: [Mode; 7] = [
+        Mode::Major,
+        Mode::Dorian,
+        Mode::Phrygian,
+        Mode::Lydian,
+        Mode::Mixolydian,
+        Mode::Minor,
+        Mode::Locrian,
+    ];
+
+    /// The interval from the tonic of the major key to the tonic of the mode,
+    /// under the same key signature
+    /// ```
+    /// # use tonality::{Interval, Mode};
+    /// assert_eq!(Interval::Maj6, Mode::Minor.offset());
+    /// ```
+    #[must_use]
+    pub fn offset(self) -> Interval {
+        Interval::in_range(Key::C.scale_degree(self as isize) as i8)
+    }
+
+    /// The interval from the tonic up to a zero-indexed degree of the mode's
+    /// scale
+    /// ```
+    /// # use tonality::{Interval, Mode};
+    /// assert_eq!(Interval::Min3, Mode::Dorian.degree_interval(2));
+    /// assert_eq!(Interval::Aug4, Mode::Lydian.degree_interval(3));
+    /// ```
+    #[must_use]
+    pub fn degree_interval(self, degree: isize) -> Interval {
+        let tonic = Key::C.scale_degree(self as isize);
+        let note = Key::C.scale_degree(self as isize + degree.rem_euclid(7));
+        // Within the seven naturals, so never beyond augmented or diminished
+        Interval::in_range(note as i8 - tonic as i8)
+    }
+
+    /// The traditional name of a zero-indexed degree of the mode's scale.
+    ///
+    /// The seventh degree is the leading tone when it is a semitone below the
+    /// tonic, and the subtonic when it is a whole tone below.
+    /// ```
+    /// # use tonality::Mode;
+    /// assert_eq!("mediant", Mode::Minor.degree_name(2));
+    /// assert_eq!("leading tone", Mode::Major.degree_name(6));
+    /// assert_eq!("subtonic", Mode::Minor.degree_name(6));
+    /// ```
+    #[must_use]
+    pub fn degree_name(self, degree: isize) -> &'static str {
+        const NAMES: [&str; 6] = [
+            "tonic",
+            "supertonic",
+            "mediant",
+            "subdominant",
+            "dominant",
+            "submediant",
+        ];
+        // Within 0 and 6
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let degree = degree.rem_euclid(7) as usize;
+        match NAMES.get(degree) {
+            Some(name) => name,
+            None if self.degree_interval(6) == Interval::Maj7 => "leading tone",
+            None => "subtonic",
+        }
+    }
+
+    /// The movable do solfège syllable of a zero-indexed degree of the mode's
+    /// scale, where the tonic of every mode is do
+    /// ```
+    /// # use tonality::Mode;
+    /// let syllables: Vec<&str> = (0..7).map(|degree| Mode::Dorian.solfege(degree)).collect();
+    /// assert_eq!(vec!["do", "re", "me", "fa", "sol", "la", "te"], syllables);
+    /// ```
+    #[must_use]
+    pub fn solfege(self, degree: isize) -> &'static str {
+        // Every degree of a mode has a syllable
+        solfege(self.degree_interval(degree)).unwrap_or_default()
+    }
+
+    /// The alteration that takes a zero-indexed degree of one mode to the
+    /// same degree of another mode on the same tonic
+    /// ```
+    /// # use tonality::Mode;
+    /// // The third, sixth and seventh are lowered from major to minor
+    /// let alterations: Vec<i8> = (0..7)
+    ///     .map(|degree| Mode::map_degree(Mode::Major, Mode::Minor, degree))
+    ///     .collect();
+    /// assert_eq!(vec![0, 0, -1, 0, 0, -1, -1], alterations);
+    /// assert_eq!(1, Mode::map_degree(Mode::Locrian, Mode::Lydian, 4));
+    /// ```
+    #[must_use]
+    pub fn map_degree(from: Mode, to: Mode, degree: isize) -> Alteration {
+        let from = from.degree_interval(degree) as i8;
+        let to = to.degree_interval(degree) as i8;
+        (to - from) / Tpc::DELTA_SEMITONE
+    }
+
+    /// Whether the third above the tonic is a minor third
+    /// ```
+    /// # use tonality::Mode;
+    /// assert!(Mode::Dorian.has_minor_third());
+    /// assert!(!Mode::Lydian.has_minor_third());
+    /// ```
+    #[must_use]
+    pub fn has_minor_third(self) -> bool {
+        match self {
+            Mode::Major | Mode::Lydian | Mode::Mixolydian => false,
+            Mode::Dorian | Mode::Phrygian | Mode::Minor | Mode::Locrian => true,
+        }
+    }
+}
+
+/// The chromatic solfège syllable of a note at an interval above do, or None
+/// if the interval is further altered than chromatic solfège goes
+pub(crate) fn solfege(interval: Interval) -> Option<&'static str> {
+    use Interval::{
+        Aug1, Aug2, Aug4, Aug5, Aug6, Dim5, Maj2, Maj3, Maj6, Maj7, Min2, Min3, Min6, Min7, Unison,
+        P4, P5,
+    };
+
+    let syllable = match interval {
+        Unison => "do",
+        Aug1 => "di",
+        Min2 => "ra",
+        Maj2 => "re",
+        Aug2 => "ri",
+        Min3 => "me",
+        Maj3 => "mi",
+        P4 => "fa",
+        Aug4 => "fi",
+        Dim5 => "se",
+        P5 => "sol",
+        Aug5 => "si",
+        Min6 => "le",
+        Maj6 => "la",
+        Aug6 => "li",
+        Min7 => "te",
+        Maj7 => "ti",
+        _ => return None,
+    };
+    Some(syllable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tonic_and_key_roundtrip() {
+        for &mode in &Mode::ALL {
+            assert_eq!(Some(Key::G), Key::from_tonic(Key::G.tonic(mode), mode));
+        }
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(Interval::Unison, Mode::Major.offset());
+        assert_eq!(Interval::Maj2, Mode::Dorian.offset());
+        assert_eq!(Interval::P4, Mode::Lydian.offset());
+        assert_eq!(Interval::Maj7, Mode::Locrian.offset());
+    }
+
+    #[test]
+    fn test_degree_names() {
+        for &mode in &Mode::ALL {
+            assert_eq!("tonic", mode.degree_name(0));
+            assert_eq!("dominant", mode.degree_name(-3));
+            assert_eq!("do", mode.solfege(7));
+        }
+        let leading: Vec<Mode> = Mode::ALL
+            .iter()
+            .copied()
+            .filter(|mode| mode.degree_name(6) == "leading tone")
+            .collect();
+        assert_eq!(vec![Mode::Major, Mode::Lydian], leading);
+    }
+
+    #[test]
+    fn test_map_degree_roundtrip() {
+        for &from in &Mode::ALL {
+            for &to in &Mode::ALL {
+                for degree in -7..14 {
+                    let alteration = Mode::map_degree(from, to, degree);
+                    assert_eq!(-alteration, Mode::map_degree(to, from, degree));
+                    let interval = from.degree_interval(degree) as i8;
+                    let mapped = interval + alteration * Tpc::DELTA_SEMITONE;
+                    assert_eq!(to.degree_interval(degree) as i8, mapped);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Lenient conversions from the values of `MusicXML` files
+//!
+//! `MusicXML` writes a key signature as a number of `<fifths>` and a note as a
+//! step with an `<alter>` in semitones, which may be fractional for
+//! microtones. Files in the wild have key signatures beyond seven sharps or
+//! flats and alterations that don't fit an accidental. These conversions map
+//! any such value to the nearest one the library supports, and report the
+//! rounding and respelling they applied, so an importer can warn about it.
+//! ```
+//! # use tonality::{Key, Step, Tpc};
+//! # use tonality::musicxml;
+//! // Eight sharps is G sharp major, which is respelled as A flat major
+//! let key = musicxml::key_from_fifths(8);
+//! assert_eq!((Key::Ab, true), (key.value, key.respelled));
+//! // An E raised by 30 cents is rounded down to a natural
+//! let tpc = musicxml::tpc_from_alter(Step::E, 0.3).unwrap();
+//! assert_eq!(Tpc::E, tpc.value);
+//! assert!((tpc.alter_error - 0.3).abs() < 1e-9);
+//! assert!(musicxml::tpc_from_alter(Step::E, -1.0).unwrap().is_exact());
+//! ```
+use std::convert::TryFrom;
+
+use crate::error::Suggestion;
+#[cfg(feature = "microtonal")]
+use crate::microtonal::{Inflection, MicroTpc};
+use crate::repr::Contiguous;
+#[cfg(feature = "microtonal")]
+use crate::spelling::RespellPolicy;
+use crate::{Key, Step, Tpc};
+
+/// A converted value, with how it differs from the value that was read
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct Rounded<T> {
+    /// The converted value
+    pub value: T,
+    /// The alteration read minus the alteration of the value, in semitones
+    pub alter_error: f64,
+    /// Whether the value was respelled enharmonically to be in range
+    pub respelled: bool,
+}
+
+impl<T> Rounded<T> {
+    /// Whether the value is exactly the value that was read
+    #[must_use]
+    pub fn is_exact(&self) -> bool {
+        self.alter_error == 0.0 && !self.respelled
+    }
+}
+
+/// The key signature of a `MusicXML` `<fifths>` element. Beyond seven sharps
+/// or flats, the key is respelled as the enharmonic key in range.
+/// ```
+/// # use tonality::Key;
+/// # use tonality::musicxml::key_from_fifths;
+/// assert_eq!(Key::Eb, key_from_fifths(-3).value);
+/// // F flat major is E major
+/// assert_eq!(Key::E, key_from_fifths(-8).value);
+/// ```
+pub fn key_from_fifths(fifths: i32) -> Rounded<Key> {
+    let (flattest, sharpest) = (i32::from(Key::MIN as i8), i32::from(Key::MAX as i8));
+    let delta = i32::from(Tpc::DELTA_ENHARMONIC);
+    let value = if fifths > sharpest {
+        fifths - (fifths - sharpest + delta - 1) / delta * delta
+    } else if fifths < flattest {
+        fifths + (flattest - fifths + delta - 1) / delta * delta
+    } else {
+        fifths
+    };
+    Rounded {
+        // Reduced to the domain of Key, which fits in an i8
+        #[allow(clippy::cast_possible_truncation)]
+        value: Key::in_range(value as i8),
+        alter_error: 0.0,
+        respelled: value != fifths,
+    }
+}
+
+/// The tpc of a step with a `MusicXML` `<alter>`, rounded to the nearest
+/// semitone. Beyond double sharps or flats, the note is respelled as the
+/// nearest enharmonic tpc in range. Returns None if the alteration is not a
+/// finite number.
+/// ```
+/// # use tonality::{Step, Tpc};
+/// # use tonality::musicxml::tpc_from_alter;
+/// // C triple sharp is D sharp
+/// let tpc = tpc_from_alter(Step::C, 3.0).unwrap();
+/// assert_eq!((Tpc::Ds, true), (tpc.value, tpc.respelled));
+/// assert_eq!(None, tpc_from_alter(Step::C, f64::NAN));
+/// ```
+#[must_use]
+pub fn tpc_from_alter(step: Step, alter: f64) -> Option<Rounded<Tpc>> {
+    if !alter.is_finite() {
+        return None;
+    }
+    let semitones = alter.round();
+    // Pitch classes repeat every twelve semitones, between -6 and 5 here
+    #[allow(clippy::cast_possible_truncation)]
+    let reduced = ((semitones + 6.0).rem_euclid(12.0) - 6.0) as i8;
+    let natural = step.with_key(Key::C) as i8;
+    let fifths = natural + reduced * Tpc::DELTA_SEMITONE;
+    let (value, respelled) = match Tpc::try_from(fifths) {
+        Ok(tpc) => (tpc, (f64::from(reduced) - semitones).abs() > 0.5),
+        Err(error) => match error.suggestion() {
+            Some(Suggestion::Tpc(tpc)) => (tpc, true),
+            // Every position has an enharmonic tpc, so this is not reached
+            _ => return None,
+        },
+    };
+    Some(Rounded {
+        value,
+        alter_error: alter - semitones,
+        respelled,
+    })
+}
+
+/// The quarter-tone pitch class of a step with a `MusicXML` `<alter>`, rounded
+/// to the nearest quarter tone. Beyond double sharps or flats, the note is
+/// respelled with at most a sharp or flat and an inflection. Returns None if
+/// the alteration is not a finite number.
+/// ```
+/// # use tonality::{Step, Tpc};
+/// # use tonality::microtonal::{Inflection, MicroTpc};
+/// # use tonality::musicxml::micro_tpc_from_alter;
+/// let e = micro_tpc_from_alter(Step::E, -0.5).unwrap();
+/// assert_eq!(MicroTpc::new(Tpc::E, Inflection::Down), Some(e.value));
+/// assert!(e.is_exact());
+/// ```
+#[cfg(feature = "microtonal")]
+#[must_use]
+pub fn micro_tpc_from_alter(step: Step, alter: f64) -> Option<Rounded<MicroTpc>> {
+    if !alter.is_finite() {
+        return None;
+    }
+    let quarter_tones = (alter * 2.0).round();
+    let semitones = (quarter_tones / 2.0).trunc();
+    let inflection = match quarter_tones - 2.0 * semitones {
+        offset if offset > 0.0 => Inflection::Up,
+        offset if offset < 0.0 => Inflection::Down,
+        _ => Inflection::None,
+    };
+    let tpc = tpc_from_alter(step, semitones)?;
+    let (value, respelled) = if let Some(value) = MicroTpc::new(tpc.value, inflection) {
+        (value, tpc.respelled)
+    } else {
+        let tpc = tpc.value.respell(RespellPolicy::MinimizeAccidentals);
+        (MicroTpc::new(tpc, inflection)?, true)
+    };
+    Some(Rounded {
+        value,
+        alter_error: alter - quarter_tones / 2.0,
+        respelled,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_in_range_are_exact() {
+        for fifths in -7..=7 {
+            let key = key_from_fifths(fifths);
+            assert!(key.is_exact());
+            assert_eq!(fifths, i32::from(key.value as i8));
+        }
+        for fifths in (-100..-7).chain(8..100) {
+            let key = key_from_fifths(fifths);
+            assert!(key.respelled);
+            assert_eq!(0, (fifths - i32::from(key.value as i8)) % 12);
+        }
+    }
+
+    #[test]
+    fn test_alters_keep_pitch_class() {
+        for &step in &[Step::C, Step::F, Step::B] {
+            for semitones in -30..30 {
+                let alter = f64::from(semitones);
+                let tpc = tpc_from_alter(step, alter).unwrap();
+                let expected =
+                    (i32::from(step.with_key(Key::C).pitch_class()) + semitones).rem_euclid(12);
+                assert_eq!(expected, i32::from(tpc.value.pitch_class()));
+                assert_eq!((-2..=2).contains(&semitones), !tpc.respelled);
+            }
+        }
+        assert!((tpc_from_alter(Step::G, 1.3).unwrap().alter_error - 0.3).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "microtonal")]
+    #[test]
+    fn test_quarter_tones_beyond_double_sharps() {
+        let b = micro_tpc_from_alter(Step::B, 2.5).unwrap();
+        assert!(b.respelled);
+        assert_eq!(Inflection::Up, b.value.inflection());
+        assert_eq!(Tpc::Cs, b.value.tpc());
+    }
+}
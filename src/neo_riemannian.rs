@@ -0,0 +1,164 @@
+//! Neo-Riemannian transformations of major and minor triads
+//!
+//! The transformations move between consonant triads by keeping two of the
+//! three notes and moving the third by a step. Since tonal pitch classes are
+//! spelled, so are the results: the relative of E♭ major is C minor, while the
+//! relative of D♯ major is B♯ minor.
+//!
+//! ```
+//! # use tonality::Tpc;
+//! # use tonality::neo_riemannian::{Quality, Transformation, Triad};
+//! use Transformation::*;
+//! let c_major = Triad::new(Tpc::C, Quality::Major);
+//! let result = c_major.apply_all(&[L, R, P]);
+//! assert_eq!(Some(Triad::new(Tpc::G, Quality::Minor)), result);
+//! ```
+use crate::{Interval, Tpc};
+
+/// The quality of a consonant triad
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Quality {
+    Major,
+    Minor,
+}
+
+/// A major or minor triad
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct Triad {
+    /// The root of the triad
+    pub root: Tpc,
+    /// Whether the triad is major or minor
+    pub quality: Quality,
+}
+
+/// The basic transformations, and some common compound ones
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transformation {
+    /// Parallel: C major and C minor
+    P,
+    /// Leading-tone exchange: C major and E minor
+    L,
+    /// Relative: C major and A minor
+    R,
+    /// Nebenverwandt, RLP: C major and F minor
+    N,
+    /// Slide, LPR: C major and C♯ minor
+    S,
+    /// Hexatonic pole, LPL: C major and G♯ minor
+    H,
+}
+
+impl Triad {
+    /// A triad with the given root and quality
+    pub const fn new(root: Tpc, quality: Quality) -> Self {
+        Self { root, quality }
+    }
+
+    /// The root, third and fifth of the triad, or None if any of them is
+    /// outside the domain of `Tpc`
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::neo_riemannian::{Quality, Triad};
+    /// let triad = Triad::new(Tpc::Fs, Quality::Minor);
+    /// assert_eq!(Some([Tpc::Fs, Tpc::A, Tpc::Cs]), triad.tones());
+    /// ```
+    #[must_use]
+    pub fn tones(self) -> Option<[Tpc; 3]> {
+        let third = match self.quality {
+            Quality::Major => Interval::Maj3,
+            Quality::Minor => Interval::Min3,
+        };
+        Some([self.root, (self.root + third)?, (self.root + Interval::P5)?])
+    }
+
+    /// Apply a transformation to the triad. Returns None if the root of the
+    /// result is outside the domain of `Tpc`.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::neo_riemannian::{Quality, Transformation, Triad};
+    /// let eb_major = Triad::new(Tpc::Eb, Quality::Major);
+    /// let c_minor = Triad::new(Tpc::C, Quality::Minor);
+    /// assert_eq!(Some(c_minor), eb_major.apply(Transformation::R));
+    /// assert_eq!(Some(eb_major), c_minor.apply(Transformation::R));
+    /// ```
+    #[must_use]
+    pub fn apply(self, transformation: Transformation) -> Option<Triad> {
+        use Transformation::{H, L, N, P, R, S};
+
+        let (root, quality) = (self.root, self.quality);
+        match (transformation, quality) {
+            (P, _) => Some(Triad::new(root, quality.flipped())),
+            (L, Quality::Major) => Some(Triad::new((root + Interval::Maj3)?, Quality::Minor)),
+            (L, Quality::Minor) => Some(Triad::new((root - Interval::Maj3)?, Quality::Major)),
+            (R, Quality::Major) => Some(Triad::new((root - Interval::Min3)?, Quality::Minor)),
+            (R, Quality::Minor) => Some(Triad::new((root + Interval::Min3)?, Quality::Major)),
+            (N, _) => self.apply_all(&[R, L, P]),
+            (S, _) => self.apply_all(&[L, P, R]),
+            (H, _) => self.apply_all(&[L, P, L]),
+        }
+    }
+
+    /// Apply a sequence of transformations, from first to last. Returns None
+    /// if any intermediate root is outside the domain of `Tpc`.
+    #[must_use]
+    pub fn apply_all(self, transformations: &[Transformation]) -> Option<Triad> {
+        transformations
+            .iter()
+            .try_fold(self, |triad, &transformation| triad.apply(transformation))
+    }
+}
+
+impl Quality {
+    /// Major for minor, and minor for major
+    fn flipped(self) -> Self {
+        match self {
+            Quality::Major => Quality::Minor,
+            Quality::Minor => Quality::Major,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Transformation::*;
+
+    fn major(root: Tpc) -> Triad {
+        Triad::new(root, Quality::Major)
+    }
+
+    fn minor(root: Tpc) -> Triad {
+        Triad::new(root, Quality::Minor)
+    }
+
+    #[test]
+    fn test_basic_transformations() {
+        assert_eq!(Some(minor(Tpc::C)), major(Tpc::C).apply(P));
+        assert_eq!(Some(minor(Tpc::E)), major(Tpc::C).apply(L));
+        assert_eq!(Some(minor(Tpc::A)), major(Tpc::C).apply(R));
+        assert_eq!(Some(minor(Tpc::Bs)), major(Tpc::Ds).apply(R));
+        assert_eq!(Some(major(Tpc::Cb)), minor(Tpc::Eb).apply(L));
+    }
+
+    #[test]
+    fn test_compound_transformations() {
+        assert_eq!(Some(minor(Tpc::F)), major(Tpc::C).apply(N));
+        assert_eq!(Some(minor(Tpc::Cs)), major(Tpc::C).apply(S));
+        assert_eq!(Some(minor(Tpc::Gs)), major(Tpc::C).apply(H));
+    }
+
+    #[test]
+    fn test_involutions() {
+        for &t in &[P, L, R, N, S, H] {
+            let triad = major(Tpc::Gb);
+            assert_eq!(Some(triad), triad.apply_all(&[t, t]));
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(None, major(Tpc::Bss).apply(L));
+    }
+}
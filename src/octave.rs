@@ -0,0 +1,188 @@
+//! Octave numbers with a checked range
+//!
+//! # The octave convention
+//!
+//! Every octave number in the crate, whether an `Octave` or a raw `i8`, is
+//! the octave of the written step in scientific pitch notation. Middle C is
+//! C4, each octave starts on C, and MIDI note 60 is C4. Since the octave
+//! belongs to the step, B sharp 3 sounds as C4 and C flat 4 sounds as B3.
+//!
+//! `Octave` is used where the range of MIDI notes applies. The raw `i8` is
+//! used where pitches may go beyond it, as by transposing or from other
+//! tunings: the `octave` field of `Pitch`, `Pitch::from_midi`, which gives
+//! B sharp -2 for note 0, `Edo::steps` and `Edo::frequency` in `tuning`,
+//! `Clef::position`, and the octaves of the `ffi` and `wasm` APIs. Check a raw
+//! octave with `Octave::new` or `Pitch::written_octave`.
+use std::fmt;
+use std::ops::{Add, Sub};
+
+/// An octave number in scientific pitch notation, where middle C is in octave
+/// 4 and the octave starts on C.
+///
+/// This is the convention of `Pitch` and of MIDI note numbers, where note 60
+/// is middle C. The range goes from -1 to 9, the octaves of the lowest and
+/// highest MIDI notes.
+/// ```
+/// # use tonality::Octave;
+/// let octave = Octave::from_midi(60).unwrap();
+/// assert_eq!(Octave::MIDDLE, octave);
+/// assert_eq!(Some(Octave::MAX), Octave::MIDDLE + 5);
+/// assert_eq!(None, Octave::MIDDLE + 6);
+/// assert_eq!("4", octave.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[must_use]
+pub struct Octave(i8);
+
+impl Octave {
+    /// The octave of MIDI note 0, a C
+    pub const MIN: Octave = Octave(-1);
+
+    /// The octave of MIDI note 127, a G
+    pub const MAX: Octave = Octave(9);
+
+    /// The octave starting on middle C
+    pub const MIDDLE: Octave = Octave(4);
+
+    /// The octave with the given number, or None if it is outside the range
+    /// from -1 to 9
+    /// ```
+    /// # use tonality::Octave;
+    /// assert_eq!(Some(Octave::MIN), Octave::new(-1));
+    /// assert_eq!(None, Octave::new(10));
+    /// ```
+    #[must_use]
+    pub fn new(number: i8) -> Option<Octave> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&number) {
+            Some(Octave(number))
+        } else {
+            None
+        }
+    }
+
+    /// The octave number in scientific pitch notation
+    #[must_use]
+    pub const fn number(self) -> i8 {
+        self.0
+    }
+
+    /// The octave of a MIDI note, or None if the note is above 127
+    /// ```
+    /// # use tonality::Octave;
+    /// assert_eq!(Octave::new(3), Octave::from_midi(59));
+    /// assert_eq!(Some(Octave::MIN), Octave::from_midi(0));
+    /// assert_eq!(None, Octave::from_midi(128));
+    /// ```
+    #[must_use]
+    pub fn from_midi(note: u8) -> Option<Octave> {
+        if note > 127 {
+            return None;
+        }
+        // At most 10, which fits in an i8
+        #[allow(clippy::cast_possible_wrap)]
+        let octaves_above_min = (note / 12) as i8;
+        Some(Octave(Self::MIN.0 + octaves_above_min))
+    }
+
+    /// The MIDI note number of the C starting the octave
+    /// ```
+    /// # use tonality::Octave;
+    /// assert_eq!(60, Octave::MIDDLE.midi());
+    /// assert_eq!(0, Octave::MIN.midi());
+    /// ```
+    #[must_use]
+    pub fn midi(self) -> u8 {
+        // The range starts at octave -1, so the sum is between 0 and 10
+        #[allow(clippy::cast_sign_loss)]
+        let octaves_above_min = (self.0 - Self::MIN.0) as u8;
+        12 * octaves_above_min
+    }
+}
+
+/// Moving up by a number of octaves gives None outside the range
+impl Add<i8> for Octave {
+    type Output = Option<Octave>;
+
+    fn add(self, rhs: i8) -> Self::Output {
+        Octave::new(self.0.checked_add(rhs)?)
+    }
+}
+
+/// Moving down by a number of octaves gives None outside the range
+impl Sub<i8> for Octave {
+    type Output = Option<Octave>;
+
+    fn sub(self, rhs: i8) -> Self::Output {
+        Octave::new(self.0.checked_sub(rhs)?)
+    }
+}
+
+/// The number of octaves between two octaves
+/// ```
+/// # use tonality::Octave;
+/// assert_eq!(-5, Octave::MIN - Octave::MIDDLE);
+/// ```
+impl Sub<Octave> for Octave {
+    type Output = i8;
+
+    fn sub(self, rhs: Octave) -> Self::Output {
+        self.0 - rhs.0
+    }
+}
+
+impl From<Octave> for i8 {
+    fn from(octave: Octave) -> Self {
+        octave.0
+    }
+}
+
+impl fmt::Display for Octave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_midi_roundtrip() {
+        for note in 0..=127 {
+            let octave = Octave::from_midi(note).unwrap();
+            assert!(octave.midi() <= note && note < octave.midi() + 12);
+        }
+        for number in -1..=9 {
+            let octave = Octave::new(number).unwrap();
+            assert_eq!(Some(octave), Octave::from_midi(octave.midi()));
+        }
+    }
+
+    #[test]
+    fn test_same_convention_as_pitch() {
+        use crate::spelling::RespellPolicy;
+        use crate::tuning::Edo;
+        use crate::{Key, Pitch};
+
+        let policies = [
+            RespellPolicy::PreferSharps,
+            RespellPolicy::PreferFlats,
+            RespellPolicy::KeyRelative(Key::Cs),
+            RespellPolicy::KeyRelative(Key::Cb),
+        ];
+        for note in 0..=127 {
+            for &policy in &policies {
+                let pitch = Pitch::from_midi(note, policy);
+                assert_eq!(Octave::from_midi(note), pitch.sounding_octave());
+                assert_eq!(i32::from(note) - 12, Edo::TWELVE.steps(pitch));
+            }
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_range() {
+        assert_eq!(None, Octave::MIN - 1);
+        assert_eq!(None, Octave::MIN + i8::MAX);
+        assert_eq!(Octave::new(2), Octave::MIDDLE - 2);
+    }
+}
@@ -0,0 +1,358 @@
+//! Parsing note and key names, strictly or leniently
+//!
+//! Strict parsing accepts exactly the names that `Tpc` and `Key` display: an
+//! uppercase step followed by accidentals that are either all ASCII or all
+//! Unicode glyphs. This suits file formats. Lenient parsing also accepts
+//! lowercase steps, mixed glyphs, `x` for double sharp, the natural sign,
+//! spelled out accidentals such as "F sharp", and German names such as "Fis"
+//! and "H". This suits user input.
+//!
+//! Errors tell why and where parsing failed:
+//! ```
+//! # use tonality::Tpc;
+//! # use tonality::parse::{parse_tpc, ParseErrorKind, Strictness};
+//! assert_eq!(Ok(Tpc::Fs), "F#".parse());
+//! assert_eq!(Ok(Tpc::Fs), parse_tpc("f sharp", Strictness::Lenient));
+//! assert_eq!(Ok(Tpc::Fs), parse_tpc("Fis", Strictness::Lenient));
+//!
+//! let err = parse_tpc("F#♯", Strictness::Strict).unwrap_err();
+//! assert_eq!(ParseErrorKind::MixedNotation, err.kind);
+//! assert_eq!(2..5, err.span);
+//! let err = parse_tpc("fb#", Strictness::Lenient).unwrap_err();
+//! assert_eq!(ParseErrorKind::ConflictingAccidentals, err.kind);
+//! assert_eq!("conflicting accidentals at 2..3", err.to_string());
+//! ```
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::{Accidental, Key, LineOfFifths, Step, Tpc};
+
+/// How strictly to parse names
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strictness {
+    /// Accept only the names as displayed, in ASCII or Unicode
+    Strict,
+    /// Accept common spellings of names in user input
+    Lenient,
+}
+
+/// Why parsing a name failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The name was empty
+    Empty,
+    /// The name did not start with a step from A to G
+    InvalidStep,
+    /// A character or word after the step was not an accidental
+    InvalidAccidental,
+    /// ASCII accidentals were mixed with Unicode glyphs
+    MixedNotation,
+    /// Sharps were mixed with flats, or with a natural sign
+    ConflictingAccidentals,
+    /// The accidentals added up to more than a double sharp or flat, or the
+    /// key would have more than seven sharps or flats
+    OutOfRange,
+}
+
+/// The error from parsing a name, with the reason and the byte range of the
+/// name where parsing failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Why parsing failed
+    pub kind: ParseErrorKind,
+    /// The bytes of the input where parsing failed
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            ParseErrorKind::Empty => "empty name",
+            ParseErrorKind::InvalidStep => "expected a step from A to G",
+            ParseErrorKind::InvalidAccidental => "invalid accidental",
+            ParseErrorKind::MixedNotation => "mixed ASCII and Unicode accidentals",
+            ParseErrorKind::ConflictingAccidentals => "conflicting accidentals",
+            ParseErrorKind::OutOfRange => "out of range",
+        };
+        write!(f, "{} at {}..{}", reason, self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// How an accidental was written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Notation {
+    Ascii,
+    Unicode,
+    Word,
+}
+
+/// Accidentals written as words in lenient parsing, longest first among
+/// those sharing a prefix
+const WORDS: [(&str, i8); 11] = [
+    ("double sharp", 2),
+    ("double-sharp", 2),
+    ("double flat", -2),
+    ("double-flat", -2),
+    ("sharp", 1),
+    ("flat", -1),
+    ("natural", 0),
+    ("isis", 2),
+    ("is", 1),
+    ("eses", -2),
+    ("es", -1),
+];
+
+/// Parse a note name, such as `"Eb"` or `"F♯"`
+///
+/// # Errors
+///
+/// Returns the reason and location of the first problem in the name.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::parse::{parse_tpc, ParseErrorKind, Strictness};
+/// assert_eq!(Ok(Tpc::Bbb), parse_tpc("B𝄫", Strictness::Strict));
+/// assert_eq!(Ok(Tpc::Ab), parse_tpc("As", Strictness::Lenient));
+/// assert_eq!(Ok(Tpc::B), parse_tpc("H", Strictness::Lenient));
+/// let err = parse_tpc("C###", Strictness::Lenient).unwrap_err();
+/// assert_eq!(ParseErrorKind::OutOfRange, err.kind);
+/// assert_eq!(1..4, err.span);
+/// ```
+pub fn parse_tpc(name: &str, strictness: Strictness) -> Result<Tpc, ParseError> {
+    let lenient = strictness == Strictness::Lenient;
+    let (offset, name) = if lenient {
+        (name.len() - name.trim_start().len(), name.trim())
+    } else {
+        (0, name)
+    };
+    let mut chars = name.chars();
+    let first = chars.next().ok_or(ParseError {
+        kind: ParseErrorKind::Empty,
+        span: offset..offset,
+    })?;
+    let step_letter = if lenient {
+        first.to_ascii_uppercase()
+    } else {
+        first
+    };
+    let step = match step_letter {
+        'C' => Step::C,
+        'D' => Step::D,
+        'E' => Step::E,
+        'F' => Step::F,
+        'G' => Step::G,
+        'A' => Step::A,
+        'B' => Step::B,
+        'H' if lenient => Step::B,
+        _ => {
+            return Err(ParseError {
+                kind: ParseErrorKind::InvalidStep,
+                span: offset..offset + first.len_utf8(),
+            })
+        }
+    };
+    let start = offset + first.len_utf8();
+    let rest = chars.as_str();
+
+    let mut alteration = 0_i8;
+    let mut sign = 0_i8;
+    let mut natural = false;
+    let mut notation = None;
+    let mut position = 0;
+    while position < rest.len() {
+        let tail = &rest[position..];
+        let Some(next) = tail.chars().next() else {
+            break;
+        };
+        let span = |len: usize| start + position..start + position + len;
+        if lenient && (next.is_whitespace() || next == '-') {
+            position += next.len_utf8();
+            continue;
+        }
+        let Some((by, written, len)) = accidental(step, position, tail, lenient) else {
+            return Err(ParseError {
+                kind: ParseErrorKind::InvalidAccidental,
+                span: span(next.len_utf8()),
+            });
+        };
+        if !lenient && notation.is_some_and(|notation| notation != written) {
+            return Err(ParseError {
+                kind: ParseErrorKind::MixedNotation,
+                span: span(len),
+            });
+        }
+        let conflicting = if by == 0 {
+            natural || notation.is_some()
+        } else {
+            natural || sign == -by.signum()
+        };
+        if conflicting {
+            return Err(ParseError {
+                kind: ParseErrorKind::ConflictingAccidentals,
+                span: span(len),
+            });
+        }
+        natural |= by == 0;
+        sign = by.signum();
+        notation = Some(written);
+        alteration += by;
+        position += len;
+    }
+    let accidental = match alteration {
+        -2 => Accidental::DblFlat,
+        -1 => Accidental::Flat,
+        0 => Accidental::Natural,
+        1 => Accidental::Sharp,
+        2 => Accidental::DblSharp,
+        _ => {
+            return Err(ParseError {
+                kind: ParseErrorKind::OutOfRange,
+                span: start..start + rest.len(),
+            })
+        }
+    };
+    Ok(step.with_accidental(accidental))
+}
+
+/// Parse the name of a major key, written as its root
+///
+/// # Errors
+///
+/// Returns the reason and location of the first problem in the name. A root
+/// whose key would have more than seven sharps or flats is out of range.
+/// ```
+/// # use tonality::Key;
+/// # use tonality::parse::{parse_key, ParseErrorKind, Strictness};
+/// assert_eq!(Ok(Key::Eb), parse_key("e flat", Strictness::Lenient));
+/// let err = parse_key("G#", Strictness::Strict).unwrap_err();
+/// assert_eq!(ParseErrorKind::OutOfRange, err.kind);
+/// ```
+pub fn parse_key(name: &str, strictness: Strictness) -> Result<Key, ParseError> {
+    let root = parse_tpc(name, strictness)?;
+    Key::from_fifths(root.fifths()).ok_or(ParseError {
+        kind: ParseErrorKind::OutOfRange,
+        span: 0..name.len(),
+    })
+}
+
+/// The alteration, notation and length in bytes of the accidental at the
+/// start of the text, or None if it does not start with an accidental
+fn accidental(
+    step: Step,
+    position: usize,
+    text: &str,
+    lenient: bool,
+) -> Option<(i8, Notation, usize)> {
+    if lenient {
+        let word = WORDS
+            .iter()
+            .copied()
+            .find(|&(word, _)| starts_with_word(text, word))
+            .or_else(|| german_contraction(step, position, text));
+        if let Some((word, by)) = word {
+            return Some((by, Notation::Word, word.len()));
+        }
+    }
+    let next = text.chars().next()?;
+    let (by, notation) = match next {
+        '#' => (1, Notation::Ascii),
+        'b' => (-1, Notation::Ascii),
+        'x' if lenient => (2, Notation::Ascii),
+        '♯' => (1, Notation::Unicode),
+        '♭' => (-1, Notation::Unicode),
+        '𝄪' => (2, Notation::Unicode),
+        '𝄫' => (-2, Notation::Unicode),
+        '♮' if lenient => (0, Notation::Unicode),
+        _ => return None,
+    };
+    Some((by, notation, next.len_utf8()))
+}
+
+/// The German flats of E and A, which drop the "e": Es, Eses, As, Ases
+fn german_contraction(step: Step, position: usize, tail: &str) -> Option<(&'static str, i8)> {
+    if position != 0 || !(step == Step::E || step == Step::A) {
+        return None;
+    }
+    [("ses", -2), ("s", -1)]
+        .iter()
+        .copied()
+        .find(|&(word, _)| starts_with_word(tail, word))
+}
+
+/// Whether the text starts with the word, ignoring case
+fn starts_with_word(text: &str, word: &str) -> bool {
+    text.get(..word.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(word))
+}
+
+/// Parses strictly
+impl FromStr for Tpc {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        parse_tpc(name, Strictness::Strict)
+    }
+}
+
+/// Parses strictly
+impl FromStr for Key {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        parse_key(name, Strictness::Strict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_roundtrip() {
+        for tpc in Tpc::all() {
+            for &strictness in &[Strictness::Strict, Strictness::Lenient] {
+                assert_eq!(Ok(tpc), parse_tpc(&tpc.to_string(), strictness));
+                assert_eq!(Ok(tpc), parse_tpc(&format!("{tpc:#}"), strictness));
+            }
+        }
+    }
+
+    #[test]
+    fn test_strict_rejects_lenient_forms() {
+        for &name in &["f#", "F sharp", "Fis", "Fx", "F♮", " F", "H", "F#♯"] {
+            assert!(parse_tpc(name, Strictness::Strict).is_err(), "{}", name);
+            assert!(parse_tpc(name, Strictness::Lenient).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_lenient_forms() {
+        let lenient = |name| parse_tpc(name, Strictness::Lenient);
+        assert_eq!(Ok(Tpc::Gss), lenient("g double sharp"));
+        assert_eq!(Ok(Tpc::Gss), lenient("Gisis"));
+        assert_eq!(Ok(Tpc::Ebb), lenient("Eses"));
+        assert_eq!(Ok(Tpc::Db), lenient("Des"));
+        assert_eq!(Ok(Tpc::Bb), lenient("B-flat"));
+        assert_eq!(Ok(Tpc::C), lenient("  C natural "));
+    }
+
+    #[test]
+    fn test_error_spans() {
+        let err = parse_tpc("", Strictness::Strict).unwrap_err();
+        assert_eq!(ParseErrorKind::Empty, err.kind);
+        let err = parse_tpc("  X#", Strictness::Lenient).unwrap_err();
+        assert_eq!((ParseErrorKind::InvalidStep, 2..3), (err.kind, err.span));
+        let err = parse_tpc("Fq", Strictness::Lenient).unwrap_err();
+        assert_eq!(
+            (ParseErrorKind::InvalidAccidental, 1..2),
+            (err.kind, err.span)
+        );
+        let err = parse_tpc("F♮#", Strictness::Lenient).unwrap_err();
+        assert_eq!(
+            (ParseErrorKind::ConflictingAccidentals, 4..5),
+            (err.kind, err.span)
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Tonal pitch classes placed in a specific octave
+use std::convert::TryFrom;
+use std::ops::{Add, RangeInclusive, Sub};
+
+use crate::interval::{CompoundInterval, DirectedInterval, Direction};
+use crate::spelling::RespellPolicy;
+use crate::{Interval, Key, Octave, Step, Tpc};
+
+/// A `Pitch` is a `Tpc` in a given octave.
+///
+/// Octaves follow scientific pitch notation, where middle C is C4. The octave
+/// number belongs to the step, not to the sounding pitch, so B sharp 3 is
+/// enharmonic to C4, and C flat 4 is enharmonic to B3. The `octave` module
+/// documents this convention, which `Octave` follows with a checked range.
+/// ```
+/// # use tonality::{Pitch, Tpc};
+/// let middle_c = Pitch::new(Tpc::C, 4);
+/// assert_eq!(60, middle_c.midi());
+/// assert_eq!(60, Pitch::new(Tpc::Bs, 3).midi());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct Pitch {
+    /// The tonal pitch class
+    pub tpc: Tpc,
+    /// The octave of the step, in scientific pitch notation, as described in
+    /// the `octave` module
+    pub octave: i8,
+}
+
+impl Pitch {
+    /// Place a tonal pitch class in an octave
+    pub const fn new(tpc: Tpc, octave: i8) -> Self {
+        Self { tpc, octave }
+    }
+
+    /// Place a tonal pitch class in a checked octave
+    /// ```
+    /// # use tonality::{Octave, Pitch, Tpc};
+    /// assert_eq!(Pitch::new(Tpc::C, 4), Pitch::in_octave(Tpc::C, Octave::MIDDLE));
+    /// ```
+    pub const fn in_octave(tpc: Tpc, octave: Octave) -> Self {
+        Self::new(tpc, octave.number())
+    }
+
+    /// The octave of the step as a checked `Octave`, or None if it is outside
+    /// the range of MIDI octaves
+    #[must_use]
+    pub fn written_octave(self) -> Option<Octave> {
+        Octave::new(self.octave)
+    }
+
+    /// The octave of the sounding pitch, which differs from the octave of the
+    /// step for B sharps and C flats. Returns None if the pitch is not a MIDI
+    /// note.
+    /// ```
+    /// # use tonality::{Octave, Pitch, Tpc};
+    /// let b_sharp = Pitch::new(Tpc::Bs, 3);
+    /// assert_eq!(Octave::new(3), b_sharp.written_octave());
+    /// assert_eq!(Some(Octave::MIDDLE), b_sharp.sounding_octave());
+    /// ```
+    #[must_use]
+    pub fn sounding_octave(self) -> Option<Octave> {
+        Octave::from_midi(u8::try_from(self.midi()).ok()?)
+    }
+
+    /// Spell a MIDI note number according to a policy.
+    ///
+    /// The octave is that of the written step, so note 0 spelled as B sharp is
+    /// in octave -2, below the range of `Octave`.
+    /// ```
+    /// # use tonality::{Key, Pitch, Tpc};
+    /// # use tonality::spelling::RespellPolicy;
+    /// let policy = RespellPolicy::KeyRelative(Key::Cs);
+    /// assert_eq!(Pitch::new(Tpc::Bs, 3), Pitch::from_midi(60, policy));
+    /// assert_eq!(Pitch::new(Tpc::Bs, -2), Pitch::from_midi(0, policy));
+    /// let policy = RespellPolicy::PreferFlats;
+    /// assert_eq!(Pitch::new(Tpc::Bb, 4), Pitch::from_midi(70, policy));
+    /// ```
+    #[inline]
+    pub fn from_midi(note: u8, policy: RespellPolicy) -> Self {
+        let tpc = Tpc::from_pitch_class(note, policy);
+        let lowest = Self::new(tpc, -1).midi();
+        // The note is at most 255, and the tpc in octave -1 is between MIDI
+        // notes -2 and 13, so the octave is between -2 and 20
+        #[allow(clippy::cast_possible_truncation)]
+        let octave = ((i16::from(note) - lowest) / 12 - 1) as i8;
+        Self::new(tpc, octave)
+    }
+
+    /// The position on the staff, disregarding the octave
+    #[inline]
+    pub fn step(self) -> Step {
+        self.tpc.step()
+    }
+
+    /// The MIDI note number of the pitch, where middle C is 60.
+    ///
+    /// The number is not limited to the range of valid MIDI notes, which makes
+    /// it useful for comparing pitches by their sounding height.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// assert_eq!(69, Pitch::new(Tpc::A, 4).midi());
+    /// assert_eq!(59, Pitch::new(Tpc::Cb, 4).midi());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn midi(self) -> i16 {
+        let octave = i16::from(self.octave) + 1;
+        let natural = i16::from(self.step().semitones());
+        let alteration = i16::from(self.tpc.alteration(Key::C));
+        12 * octave + natural + alteration
+    }
+
+    /// The lowest pitch with the given tpc whose step is written above this
+    /// pitch's step.
+    ///
+    /// This is how to find the F above A flat: F has a lower step than A, and
+    /// must therefore be raised an octave. Returns None if the pitch would be
+    /// above the highest octave.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// let ab = Pitch::new(Tpc::Ab, 4);
+    /// assert_eq!(Some(Pitch::new(Tpc::F, 5)), ab.next_above(Tpc::F));
+    /// assert_eq!(Some(Pitch::new(Tpc::Bb, 4)), ab.next_above(Tpc::Bb));
+    /// assert_eq!(Some(Pitch::new(Tpc::A, 5)), ab.next_above(Tpc::A));
+    /// assert_eq!(None, Pitch::new(Tpc::Ab, i8::MAX).next_above(Tpc::F));
+    /// ```
+    #[must_use]
+    pub fn next_above(self, tpc: Tpc) -> Option<Self> {
+        if tpc.step().same_octave_above(self.step()) {
+            Some(Self::new(tpc, self.octave))
+        } else {
+            Some(Self::new(tpc, self.octave.checked_add(1)?))
+        }
+    }
+
+    /// The same tpc in the octave starting at a reference pitch, at least as
+    /// high as the reference and less than an octave above it. The spelling
+    /// is kept, and pitches are compared by their sounding height, so a B
+    /// sharp folded into the octave from C4 is B♯3.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// let c4 = Pitch::new(Tpc::C, 4);
+    /// assert_eq!(Pitch::new(Tpc::E, 4), Pitch::new(Tpc::E, 1).fold_into_octave(c4));
+    /// assert_eq!(Pitch::new(Tpc::Bs, 3), Pitch::new(Tpc::Bs, 6).fold_into_octave(c4));
+    /// assert_eq!(Pitch::new(Tpc::Cb, 5), Pitch::new(Tpc::Cb, 4).fold_into_octave(c4));
+    /// ```
+    pub fn fold_into_octave(self, reference: Pitch) -> Self {
+        let octaves = (self.midi() - reference.midi()).div_euclid(12);
+        self.shifted_octaves(-octaves)
+    }
+
+    /// The pitch folded by octaves into a range, keeping the tpc. Pitches in
+    /// the range are kept, lower pitches are raised to the lowest octave at
+    /// or above the bottom of the range, and higher pitches are lowered to
+    /// the highest octave at or below the top. Pitches are compared by their
+    /// sounding height.
+    ///
+    /// If the range is narrower than an octave, it may hold no pitch with the
+    /// tpc, and the result is then just outside the range.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// // The range of a violin
+    /// let range = Pitch::new(Tpc::G, 3)..=Pitch::new(Tpc::A, 7);
+    /// assert_eq!(Pitch::new(Tpc::Fs, 4), Pitch::new(Tpc::Fs, 2).clamp_to_range(range.clone()));
+    /// assert_eq!(Pitch::new(Tpc::Bb, 6), Pitch::new(Tpc::Bb, 7).clamp_to_range(range.clone()));
+    /// assert_eq!(Pitch::new(Tpc::G, 3), Pitch::new(Tpc::G, 3).clamp_to_range(range));
+    /// ```
+    pub fn clamp_to_range(self, range: RangeInclusive<Pitch>) -> Self {
+        let (lowest, highest) = range.into_inner();
+        if self.midi() < lowest.midi() {
+            self.fold_into_octave(lowest)
+        } else if self.midi() > highest.midi() {
+            let folded = self.fold_into_octave(highest);
+            if folded.midi() == highest.midi() {
+                folded
+            } else {
+                folded.shifted_octaves(-1)
+            }
+        } else {
+            self
+        }
+    }
+
+    /// The same tpc a number of octaves higher, saturated at the range of
+    /// octave numbers
+    fn shifted_octaves(self, octaves: i16) -> Self {
+        let octave = (i16::from(self.octave) + octaves).clamp(i8::MIN.into(), i8::MAX.into());
+        // Clamped to the range of i8
+        #[allow(clippy::cast_possible_truncation)]
+        let octave = octave as i8;
+        Self::new(self.tpc, octave)
+    }
+
+    /// The interval from this pitch to another, going up or down by any
+    /// number of octaves.
+    ///
+    /// The direction follows the steps on the staff, so that C4 down to B♯3
+    /// is a descending diminished second. Between notes on the same step, the
+    /// interval goes down if the other pitch is lower. Returns None if the
+    /// simple interval would be more than augmented or diminished.
+    /// ```
+    /// # use tonality::{Interval, Pitch, Tpc};
+    /// let c4 = Pitch::new(Tpc::C, 4);
+    /// let interval = c4.interval_to(Pitch::new(Tpc::F, 2)).unwrap();
+    /// assert_eq!(Interval::P5, interval.interval);
+    /// assert_eq!(1, interval.octaves);
+    /// assert!(interval.descending);
+    /// assert_eq!(Some(Pitch::new(Tpc::F, 2)), c4 + interval);
+    /// ```
+    #[must_use]
+    pub fn interval_to(self, other: Pitch) -> Option<CompoundInterval> {
+        let steps = other.diatonic_index() - self.diatonic_index();
+        let descending = steps < 0 || (steps == 0 && other.midi() < self.midi());
+        let (low, high) = if descending {
+            (other, self)
+        } else {
+            (self, other)
+        };
+        let interval = low.tpc.try_interval_to(high.tpc).ok()?;
+        let octaves = u8::try_from(steps.abs() / 7).ok()?;
+        Some(CompoundInterval {
+            interval,
+            octaves,
+            descending,
+        })
+    }
+
+    /// The steps from C0 up to the pitch's step
+    fn diatonic_index(self) -> i16 {
+        i16::from(self.octave) * 7 + self.step() as i16
+    }
+}
+
+impl Add<Interval> for Pitch {
+    type Output = Option<Pitch>;
+
+    /// Transpose the pitch upwards by a simple interval, giving None beyond
+    /// the range of octaves
+    fn add(self, rhs: Interval) -> Self::Output {
+        let tpc = (self.tpc + rhs)?;
+        let octave = if tpc.step() < self.step() {
+            self.octave.checked_add(1)?
+        } else {
+            self.octave
+        };
+        Some(Self::new(tpc, octave))
+    }
+}
+
+impl Sub<Interval> for Pitch {
+    type Output = Option<Pitch>;
+
+    /// Transpose the pitch downwards by a simple interval, giving None beyond
+    /// the range of octaves
+    fn sub(self, rhs: Interval) -> Self::Output {
+        let tpc = (self.tpc - rhs)?;
+        let octave = if tpc.step() > self.step() {
+            self.octave.checked_sub(1)?
+        } else {
+            self.octave
+        };
+        Some(Self::new(tpc, octave))
+    }
+}
+
+/// Transposing by a directed interval moves up or down by the simple
+/// interval, giving None if the result would be beyond double sharp or
+/// double flat
+impl Add<DirectedInterval> for Pitch {
+    type Output = Option<Pitch>;
+
+    fn add(self, rhs: DirectedInterval) -> Self::Output {
+        match rhs.direction {
+            Direction::Up => self + rhs.interval,
+            Direction::Down => self - rhs.interval,
+        }
+    }
+}
+
+/// Transposing by a compound interval gives None if the result would be
+/// beyond double sharp or double flat, or outside the range of octaves
+impl Add<CompoundInterval> for Pitch {
+    type Output = Option<Pitch>;
+
+    fn add(self, rhs: CompoundInterval) -> Self::Output {
+        let tpc = if rhs.descending {
+            (self.tpc - rhs.interval)?
+        } else {
+            (self.tpc + rhs.interval)?
+        };
+        let index = self.diatonic_index() + rhs.steps();
+        let octave = i8::try_from(index.div_euclid(7)).ok()?;
+        Some(Self::new(tpc, octave))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_midi() {
+        assert_eq!(21, Pitch::new(Tpc::A, 0).midi());
+        assert_eq!(108, Pitch::new(Tpc::C, 8).midi());
+        assert_eq!(62, Pitch::new(Tpc::Css, 4).midi());
+        assert_eq!(70, Pitch::new(Tpc::Cbb, 5).midi());
+    }
+
+    #[test]
+    fn test_add_interval() {
+        let a4 = Pitch::new(Tpc::A, 4);
+        assert_eq!(Some(Pitch::new(Tpc::Cs, 5)), a4 + Interval::Maj3);
+        assert_eq!(Some(Pitch::new(Tpc::Gs, 5)), a4 + Interval::Maj7);
+        assert_eq!(Some(Pitch::new(Tpc::Ab, 4)), a4 + Interval::Dim1);
+        assert_eq!(None, Pitch::new(Tpc::Fbb, 4) + Interval::Dim5);
+    }
+
+    #[test]
+    fn test_sub_interval() {
+        let c4 = Pitch::new(Tpc::C, 4);
+        assert_eq!(Some(Pitch::new(Tpc::A, 3)), c4 - Interval::Min3);
+        assert_eq!(Some(Pitch::new(Tpc::Cs, 4)), c4 - Interval::Dim1);
+        assert_eq!(Some(Pitch::new(Tpc::Bs, 3)), c4 - Interval::Dim2);
+    }
+
+    #[test]
+    fn test_fold_and_clamp() {
+        let c4 = Pitch::new(Tpc::C, 4);
+        for octave in -10..10 {
+            for &tpc in &[Tpc::C, Tpc::Cb, Tpc::Bs, Tpc::Bss, Tpc::Dbb, Tpc::G] {
+                let folded = Pitch::new(tpc, octave).fold_into_octave(c4);
+                assert_eq!(tpc, folded.tpc);
+                assert!((60..72).contains(&folded.midi()), "{:?}", folded);
+            }
+        }
+        // A range narrower than an octave
+        let range = Pitch::new(Tpc::E, 4)..=Pitch::new(Tpc::G, 4);
+        assert_eq!(
+            Pitch::new(Tpc::F, 4),
+            Pitch::new(Tpc::F, 0).clamp_to_range(range.clone())
+        );
+        assert_eq!(
+            Pitch::new(Tpc::C, 5),
+            Pitch::new(Tpc::C, 2).clamp_to_range(range.clone())
+        );
+        assert_eq!(
+            Pitch::new(Tpc::C, 4),
+            Pitch::new(Tpc::C, 8).clamp_to_range(range)
+        );
+        // The top of the range is kept when folding down onto it
+        let range = Pitch::new(Tpc::C, 2)..=Pitch::new(Tpc::Bs, 4);
+        assert_eq!(
+            Pitch::new(Tpc::Bs, 4),
+            Pitch::new(Tpc::Bs, 6).clamp_to_range(range)
+        );
+        // Octaves saturate instead of overflowing
+        let top = Pitch::new(Tpc::C, i8::MAX);
+        assert_eq!(top, Pitch::new(Tpc::C, i8::MIN).fold_into_octave(top));
+    }
+
+    #[test]
+    fn test_interval_roundtrip() {
+        let pitches = [
+            Pitch::new(Tpc::C, 4),
+            Pitch::new(Tpc::Cb, 4),
+            Pitch::new(Tpc::Bs, 3),
+            Pitch::new(Tpc::Fs, 1),
+            Pitch::new(Tpc::Ebb, 6),
+            Pitch::new(Tpc::Cs, 4),
+        ];
+        for &from in &pitches {
+            for &to in &pitches {
+                if let Some(interval) = from.interval_to(to) {
+                    assert_eq!(Some(to), from + interval);
+                    assert_eq!(to.midi() - from.midi(), interval.semitones());
+                    if from != to {
+                        assert_eq!(Some(interval.reversed()), to.interval_to(from));
+                    }
+                }
+            }
+        }
+        let unison = Pitch::new(Tpc::C, 4)
+            .interval_to(Pitch::new(Tpc::Cb, 4))
+            .unwrap();
+        assert_eq!(Interval::Aug1, unison.interval);
+        assert!(unison.descending);
+    }
+}
@@ -0,0 +1,333 @@
+//! Python bindings through `pyo3`
+//!
+//! The `tonality` Python module has the classes `Tpc`, `Key`, `Interval` and
+//! `Step`, which wrap the Rust types of the same names. They are created from
+//! their names, print as they are displayed in Rust, and support the same
+//! arithmetic:
+//! ```python
+//! from tonality import Interval, Key, Tpc
+//!
+//! assert str(Tpc("F#") + Interval("M3")) == "A♯"
+//! assert Tpc("A") - Tpc("C#") == Interval("M3")
+//! assert [str(tpc) for tpc in Key("Eb").scale()][:3] == ["E♭", "F", "G"]
+//! ```
+//! Results beyond double sharps or flats raise `ValueError`. The extension
+//! module is built from the `tonality-py` crate in the repository, which
+//! calls `register`, with `maturin build` in its directory.
+// Python methods take self by reference, and the code generated by pyo3
+// converts errors to themselves
+#![allow(clippy::trivially_copy_pass_by_ref, clippy::useless_conversion)]
+
+use std::convert::TryFrom;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::spelling::RespellPolicy;
+use crate::wire::WireFormat;
+use crate::{Accidental, Error, Interval, Key, Pitch, Step, Tpc};
+
+/// A `ValueError` telling in which direction the result was out of range
+fn out_of_range(error: Error) -> PyErr {
+    let direction = if error.is_sharpwards() {
+        "sharp"
+    } else {
+        "flat"
+    };
+    PyValueError::new_err(format!("result would be too {direction}"))
+}
+
+/// A `ValueError` for a name that could not be parsed
+fn invalid_name(kind: &str, name: &str) -> PyErr {
+    PyValueError::new_err(format!("invalid {kind}: {name:?}"))
+}
+
+/// A tonal pitch class, such as `Tpc("F#")`
+#[pyclass(name = "Tpc", module = "tonality", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyTpc(pub Tpc);
+
+#[pymethods]
+impl PyTpc {
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        name.parse()
+            .map(PyTpc)
+            .map_err(|_| invalid_name("tpc", name))
+    }
+
+    /// The tpc at a position on the line of fifths, where C is 0
+    #[staticmethod]
+    fn from_fifths(fifths: i8) -> PyResult<Self> {
+        Tpc::try_from(fifths).map(PyTpc).map_err(out_of_range)
+    }
+
+    /// The position on the line of fifths
+    #[getter]
+    fn fifths(&self) -> i8 {
+        i8::from(self.0)
+    }
+
+    /// The step of the tpc, without its accidental
+    #[getter]
+    fn step(&self) -> PyStep {
+        PyStep(self.0.step())
+    }
+
+    /// The number of semitones from C up to the tpc, between 0 and 11
+    #[getter]
+    fn pitch_class(&self) -> u8 {
+        self.0.pitch_class()
+    }
+
+    /// The alteration of the tpc relative to the key
+    fn alteration(&self, key: PyKey) -> i8 {
+        self.0.alteration(key.0)
+    }
+
+    /// Whether the two tpcs are the same pitch class
+    fn enharmonic(&self, other: PyTpc) -> bool {
+        self.0.enharmonic(other.0)
+    }
+
+    /// The enharmonic spelling which fits the key best
+    fn respell_in_key(&self, key: PyKey) -> PyTpc {
+        PyTpc(self.0.respell_in_key(key.0))
+    }
+
+    fn __add__(&self, interval: PyInterval) -> PyResult<PyTpc> {
+        self.0.try_add(interval.0).map(PyTpc).map_err(out_of_range)
+    }
+
+    /// Subtracting an interval gives a tpc, and subtracting a tpc gives the
+    /// interval from this tpc to the other, as in Rust
+    fn __sub__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        if let Ok(interval) = other.extract::<PyInterval>() {
+            let tpc = self.0.try_sub(interval.0).map_err(out_of_range)?;
+            return Ok(PyTpc(tpc).into_py(py));
+        }
+        let tpc = other.extract::<PyTpc>()?;
+        let interval = self.0.try_interval_to(tpc.0).map_err(out_of_range)?;
+        Ok(PyInterval(interval).into_py(py))
+    }
+
+    fn __eq__(&self, other: PyTpc) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> isize {
+        isize::from(i8::from(self.0))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Tpc({:?})", self.0.to_wire())
+    }
+}
+
+/// A major key signature, named by its root, such as `Key("Eb")`
+#[pyclass(name = "Key", module = "tonality", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyKey(pub Key);
+
+#[pymethods]
+impl PyKey {
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        name.parse()
+            .map(PyKey)
+            .map_err(|_| invalid_name("key", name))
+    }
+
+    /// The key with a number of sharps, or flats if negative
+    #[staticmethod]
+    fn from_fifths(fifths: i8) -> PyResult<Self> {
+        Key::try_from(fifths).map(PyKey).map_err(out_of_range)
+    }
+
+    /// The number of sharps, or flats if negative
+    #[getter]
+    fn fifths(&self) -> i8 {
+        i8::from(self.0)
+    }
+
+    /// The root of the major scale
+    #[getter]
+    fn root(&self) -> PyTpc {
+        PyTpc(self.0.root())
+    }
+
+    /// The notes of the major scale, from the root up
+    fn scale(&self) -> Vec<PyTpc> {
+        self.0.scale().map(PyTpc).collect()
+    }
+
+    /// The tpc of the step in the key
+    fn spell(&self, step: PyStep) -> PyTpc {
+        PyTpc(step.0.with_key(self.0))
+    }
+
+    /// The key transposed up by an interval
+    fn __add__(&self, interval: PyInterval) -> PyResult<PyKey> {
+        let fifths = i8::from(self.0) + i8::from(interval.0);
+        Key::try_from(fifths).map(PyKey).map_err(out_of_range)
+    }
+
+    fn __eq__(&self, other: PyKey) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> isize {
+        isize::from(i8::from(self.0))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Key({:?})", self.0.to_wire())
+    }
+}
+
+/// A simple interval, named by its quality and number, such as
+/// `Interval("m3")` or `Interval("A4")`
+#[pyclass(name = "Interval", module = "tonality", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyInterval(pub Interval);
+
+#[pymethods]
+impl PyInterval {
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        Interval::from_wire(name)
+            .map(PyInterval)
+            .ok_or_else(|| invalid_name("interval", name))
+    }
+
+    /// The number of semitones spanned by the interval
+    #[getter]
+    fn semitones(&self) -> i8 {
+        self.0.semitones()
+    }
+
+    /// The position on the line of fifths, where the unison is 0
+    #[getter]
+    fn fifths(&self) -> i8 {
+        i8::from(self.0)
+    }
+
+    fn __add__(&self, other: PyInterval) -> PyResult<PyInterval> {
+        self.0
+            .try_add(other.0)
+            .map(PyInterval)
+            .map_err(out_of_range)
+    }
+
+    /// The inversion of the interval
+    fn __neg__(&self) -> PyInterval {
+        PyInterval(-self.0)
+    }
+
+    fn __eq__(&self, other: PyInterval) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> isize {
+        isize::from(i8::from(self.0))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_wire()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Interval({:?})", self.0.to_wire())
+    }
+}
+
+/// A position on the staff, named by its letter, such as `Step("F")`
+#[pyclass(name = "Step", module = "tonality", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyStep(pub Step);
+
+#[pymethods]
+impl PyStep {
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        match name.parse::<Tpc>() {
+            Ok(tpc) if tpc.accidental() == Accidental::Natural => Ok(PyStep(tpc.step())),
+            _ => Err(invalid_name("step", name)),
+        }
+    }
+
+    /// The tpc of the step in the key
+    fn with_key(&self, key: PyKey) -> PyTpc {
+        PyTpc(self.0.with_key(key.0))
+    }
+
+    fn __eq__(&self, other: PyStep) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> isize {
+        isize::from(i8::from(self.0))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Step({:?})", self.0.to_string())
+    }
+}
+
+/// Spell a MIDI note number as it would be written in a key, returning the
+/// tpc and the octave
+#[pyfunction]
+fn spell_midi(note: u8, key: PyKey) -> (PyTpc, i8) {
+    let pitch = Pitch::from_midi(note, RespellPolicy::KeyRelative(key.0));
+    (PyTpc(pitch.tpc), pitch.octave)
+}
+
+/// Add the classes and functions of the `tonality` Python module to a module
+///
+/// # Errors
+///
+/// Returns the Python error if a class or function could not be added.
+pub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyTpc>()?;
+    module.add_class::<PyKey>()?;
+    module.add_class::<PyInterval>()?;
+    module.add_class::<PyStep>()?;
+    module.add_function(wrap_pyfunction!(spell_midi, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(Tpc::Fs, PyTpc::new("F#").unwrap().0);
+        assert_eq!(Key::Eb, PyKey::new("Eb").unwrap().0);
+        assert_eq!(Interval::Min3, PyInterval::new("m3").unwrap().0);
+        assert_eq!(Step::F, PyStep::new("F").unwrap().0);
+        assert!(PyStep::new("F#").is_err());
+        assert!(PyTpc::new("H").is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let interval = PyInterval::new("M3").unwrap();
+        assert_eq!(Tpc::As, PyTpc(Tpc::Fs).__add__(interval).unwrap().0);
+        assert!(PyTpc(Tpc::Bss).__add__(interval).is_err());
+        assert_eq!(Interval::Min6, interval.__neg__().0);
+        assert_eq!("Interval(\"M3\")", interval.__repr__());
+    }
+}
@@ -0,0 +1,41 @@
+//! Random values of the core types, behind the `rand`, `proptest` and
+//! `arbitrary` features
+use crate::repr::Contiguous;
+use crate::{Accidental, Interval, Key, Step, Tpc};
+
+/// Implement the random value traits for enums, choosing uniformly among
+/// their variants
+macro_rules! impl_random {
+    ($($type:ty),*) => {
+        $(
+            #[cfg(feature = "rand")]
+            impl rand::distributions::Distribution<$type> for rand::distributions::Standard {
+                fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> $type {
+                    let variants = <$type as Contiguous>::VARIANTS;
+                    variants[rng.gen_range(0..variants.len())]
+                }
+            }
+
+            #[cfg(feature = "proptest")]
+            impl proptest::arbitrary::Arbitrary for $type {
+                type Parameters = ();
+                type Strategy = proptest::strategy::BoxedStrategy<$type>;
+
+                fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+                    use proptest::strategy::Strategy;
+
+                    proptest::sample::select(<$type as Contiguous>::VARIANTS).boxed()
+                }
+            }
+
+            #[cfg(feature = "arbitrary")]
+            impl<'a> arbitrary::Arbitrary<'a> for $type {
+                fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                    u.choose(<$type as Contiguous>::VARIANTS).copied()
+                }
+            }
+        )*
+    };
+}
+
+impl_random!(Tpc, Key, Interval, Step, Accidental);
@@ -0,0 +1,230 @@
+//! Stable integer representations of the enums
+//!
+//! `Tpc`, `Key`, `Interval`, `Step`, `Accidental`, `Mode` and `IntervalClass`
+//! are `#[repr(i8)]`, and their discriminants are part of the API, so they can
+//! cross an FFI boundary as plain `i8`s:
+//!
+//! - `Tpc`, `Key` and `Interval`: the position on the line of fifths, with C,
+//!   C major and the unison at 0
+//! - `Step`: 0 for C up to 6 for B
+//! - `Accidental`: the alteration, from -2 for double flat to 2 for double
+//!   sharp
+//! - `Mode`: the degree of the major scale the mode starts on, from 0 for
+//!   major to 6 for Locrian
+//! - `IntervalClass`: the number of steps, from 0 for a unison to 6 for a
+//!   seventh
+//!
+//! Each type converts into an `i8`, and back with `TryFrom`.
+//! ```
+//! # use std::convert::TryFrom;
+//! # use tonality::{Accidental, Tpc};
+//! assert_eq!(-2, i8::from(Tpc::Bb));
+//! assert_eq!(Ok(Tpc::Bb), Tpc::try_from(-2));
+//! assert!(Tpc::try_from(20).unwrap_err().is_sharpwards());
+//! assert_eq!(Ok(Accidental::Sharp), Accidental::try_from(1));
+//! ```
+use std::convert::TryFrom;
+use std::fmt;
+
+use num_traits::FromPrimitive;
+
+use crate::error::{from_fifths, Domain};
+use crate::interval::IntervalClass;
+use crate::{Accidental, Error, Interval, Key, Mode, Step, Tpc};
+
+/// The error from converting an integer which is not the discriminant of any
+/// variant
+/// ```
+/// # use std::convert::TryFrom;
+/// # use tonality::Step;
+/// # use tonality::repr::InvalidDiscriminant;
+/// assert_eq!(Err(InvalidDiscriminant { value: 7 }), Step::try_from(7));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidDiscriminant {
+    /// The integer which was converted
+    pub value: i8,
+}
+
+impl fmt::Display for InvalidDiscriminant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a valid discriminant", self.value)
+    }
+}
+
+impl std::error::Error for InvalidDiscriminant {}
+
+macro_rules! impl_into_i8 {
+    ($($type:ty),*) => {
+        $(
+            impl From<$type> for i8 {
+                fn from(value: $type) -> i8 {
+                    value as i8
+                }
+            }
+        )*
+    };
+}
+
+impl_into_i8!(Tpc, Key, Interval, Step, Accidental, Mode, IntervalClass);
+
+macro_rules! impl_try_from_fifths {
+    ($($type:ident),*) => {
+        $(
+            /// Out of range values give an error telling in which direction
+            impl TryFrom<i8> for $type {
+                type Error = Error;
+
+                fn try_from(value: i8) -> Result<Self, Error> {
+                    from_fifths(value, Domain::$type)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_fifths!(Tpc, Key, Interval);
+
+macro_rules! impl_try_from_discriminant {
+    ($($type:ty),*) => {
+        $(
+            impl TryFrom<i8> for $type {
+                type Error = InvalidDiscriminant;
+
+                fn try_from(value: i8) -> Result<Self, InvalidDiscriminant> {
+                    FromPrimitive::from_i8(value).ok_or(InvalidDiscriminant { value })
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_discriminant!(Step, Accidental, Mode, IntervalClass);
+
+/// Enums whose discriminants are a contiguous range of `i8`s
+pub(crate) trait Contiguous: Copy + Into<i8> + 'static {
+    /// Every variant, in order of discriminant
+    const VARIANTS: &'static [Self];
+
+    /// The variant with a discriminant that the caller has brought into
+    /// range, such as by reducing it by whole enharmonic cycles. Panics
+    /// otherwise, since that is a bug in the caller which the exhaustive tests
+    /// should reveal, where rounding to the nearest variant would hide it.
+    fn in_range(value: i8) -> Self {
+        let lowest: i8 = Self::VARIANTS[0].into();
+        // A value below the lowest gives an index far beyond the variants
+        #[allow(clippy::cast_sign_loss)]
+        let index = (i16::from(value) - i16::from(lowest)) as usize;
+        Self::VARIANTS[index]
+    }
+}
+
+macro_rules! impl_contiguous {
+    ($($type:ty: $variants:expr);*) => {
+        $(
+            impl Contiguous for $type {
+                const VARIANTS: &'static [Self] = &$variants;
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+impl_contiguous!(
+    Tpc: Tpc::LINE_OF_FIFTHS;
+    Key: [
+        Key::Cb, Key::Gb, Key::Db, Key::Ab, Key::Eb, Key::Bb, Key::F, Key::C,
+        Key::G, Key::D, Key::A, Key::E, Key::B, Key::Fs, Key::Cs,
+    ];
+    Interval: [
+        Interval::Dim2, Interval::Dim6, Interval::Dim3, Interval::Dim7, Interval::Dim4,
+        Interval::Dim1, Interval::Dim5, Interval::Min2, Interval::Min6, Interval::Min3,
+        Interval::Min7, Interval::P4, Interval::Unison, Interval::P5, Interval::Maj2,
+        Interval::Maj6, Interval::Maj3, Interval::Maj7, Interval::Aug4, Interval::Aug1,
+        Interval::Aug5, Interval::Aug2, Interval::Aug6, Interval::Aug3, Interval::Aug7,
+    ];
+    Step: [Step::C, Step::D, Step::E, Step::F, Step::G, Step::A, Step::B];
+    Accidental: [
+        Accidental::DblFlat, Accidental::Flat, Accidental::Natural, Accidental::Sharp,
+        Accidental::DblSharp,
+    ];
+    Mode: Mode::ALL;
+    IntervalClass: [
+        IntervalClass::Unison, IntervalClass::Second, IntervalClass::Third,
+        IntervalClass::Fourth, IntervalClass::Fifth, IntervalClass::Sixth,
+        IntervalClass::Seventh,
+    ]
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<T>(values: std::ops::RangeInclusive<i8>)
+    where
+        T: TryFrom<i8> + Into<i8>,
+    {
+        for value in i8::MIN..=i8::MAX {
+            match T::try_from(value) {
+                Ok(converted) => {
+                    assert!(values.contains(&value));
+                    assert_eq!(value, converted.into());
+                }
+                Err(_) => assert!(!values.contains(&value)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        roundtrip::<Tpc>(-15..=19);
+        roundtrip::<Key>(-7..=7);
+        roundtrip::<Interval>(-12..=12);
+        roundtrip::<Step>(0..=6);
+        roundtrip::<Accidental>(-2..=2);
+        roundtrip::<Mode>(0..=6);
+        roundtrip::<IntervalClass>(0..=6);
+    }
+
+    fn variants_in_order<T: Contiguous>(values: std::ops::RangeInclusive<i8>) {
+        let discriminants: Vec<i8> = T::VARIANTS.iter().map(|&variant| variant.into()).collect();
+        assert_eq!(values.collect::<Vec<_>>(), discriminants);
+    }
+
+    #[test]
+    fn test_variants() {
+        variants_in_order::<Tpc>(-15..=19);
+        variants_in_order::<Key>(-7..=7);
+        variants_in_order::<Interval>(-12..=12);
+        variants_in_order::<Step>(0..=6);
+        variants_in_order::<Accidental>(-2..=2);
+        variants_in_order::<Mode>(0..=6);
+        variants_in_order::<IntervalClass>(0..=6);
+        assert_eq!(Tpc::Bb, Tpc::in_range(-2));
+        assert_eq!(Key::Cs, Key::in_range(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_out_of_range() {
+        let _ = Key::in_range(8);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_below_range() {
+        let _ = Tpc::in_range(i8::MIN);
+    }
+
+    #[test]
+    fn test_documented_values() {
+        assert_eq!(-15, i8::from(Tpc::Fbb));
+        assert_eq!(19, i8::from(Tpc::Bss));
+        assert_eq!(-7, i8::from(Key::Cb));
+        assert_eq!(1, i8::from(Interval::P5));
+        assert_eq!(6, i8::from(Step::B));
+        assert_eq!(-2, i8::from(Accidental::DblFlat));
+        assert_eq!(5, i8::from(Mode::Minor));
+        assert_eq!(6, i8::from(IntervalClass::Seventh));
+    }
+}
@@ -0,0 +1,345 @@
+//! Analysis results which record how they were computed
+//!
+//! Each result carries a `Provenance`: the name and version of the algorithm,
+//! the version of the crate, and a hash of the input. The hash is stable
+//! across platforms and releases, so results stored next to their input can
+//! be checked with `is_valid_for` and recomputed only when the input or the
+//! algorithm has changed. With the `serde` feature, the results can be
+//! serialized, with tpcs and keys in the format of the `wire` module, and the
+//! fields in a fixed order for diffing.
+//! ```
+//! # use tonality::{Key, Tpc};
+//! # use tonality::results::KeyResult;
+//! let notes = [(Tpc::G, 2.0), (Tpc::B, 1.0), (Tpc::D, 1.0), (Tpc::Fs, 0.5)];
+//! let result = KeyResult::compute(&notes);
+//! assert_eq!(Some(Key::G), result.key);
+//! assert!(result.is_valid_for(&notes));
+//! assert!(!result.is_valid_for(&notes[1..]));
+//! ```
+use crate::analysis;
+use crate::chord::Chord;
+use crate::spelling;
+use crate::{Key, Tpc};
+
+/// How a result was computed
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Provenance {
+    /// The name of the algorithm, such as `"analysis::weighted_key"`
+    pub algorithm: String,
+    /// The version of the algorithm, which is increased whenever its results
+    /// can change for the same input
+    pub algorithm_version: u32,
+    /// The version of the crate which computed the result
+    pub crate_version: String,
+    /// The 64-bit FNV-1a hash of the input, as given by `InputHash`
+    pub input_hash: u64,
+}
+
+impl Provenance {
+    fn new(algorithm: &str, algorithm_version: u32, input_hash: u64) -> Self {
+        Self {
+            algorithm: algorithm.to_string(),
+            algorithm_version,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            input_hash,
+        }
+    }
+
+    /// Whether a result with this provenance is what the algorithm of this
+    /// version of the crate would give for input with the hash. Results from
+    /// another crate version are valid as long as the algorithm version is
+    /// the same.
+    #[must_use]
+    pub fn matches(&self, algorithm: &str, algorithm_version: u32, input_hash: u64) -> bool {
+        self.algorithm == algorithm
+            && self.algorithm_version == algorithm_version
+            && self.input_hash == input_hash
+    }
+}
+
+/// A 64-bit FNV-1a hasher, which unlike the hashers of the standard library
+/// gives the same hash on every platform and in every release
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::results::InputHash;
+/// let mut hash = InputHash::new();
+/// hash.write_tpc(Tpc::C);
+/// assert_eq!(0xaf63_bd4c_8601_b7df, hash.finish());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct InputHash(u64);
+
+impl InputHash {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// The hash of no input
+    pub const fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    /// Add bytes to the hash
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Add a tpc to the hash, by its position on the line of fifths
+    pub fn write_tpc(&mut self, tpc: Tpc) {
+        self.write(&i8::from(tpc).to_le_bytes());
+    }
+
+    /// The hash of the input so far
+    #[must_use]
+    pub const fn finish(self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for InputHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The hash of weighted notes, by the tpcs and the bits of the weights
+fn hash_weighted(notes: &[(Tpc, f32)]) -> u64 {
+    let mut hash = InputHash::new();
+    for &(tpc, weight) in notes {
+        hash.write_tpc(tpc);
+        hash.write(&weight.to_bits().to_le_bytes());
+    }
+    hash.finish()
+}
+
+/// The hash of chords given as their tones, with the number of tones before
+/// each chord so that chords cannot run into each other
+fn hash_chords(chords: &[Vec<Tpc>]) -> u64 {
+    let mut hash = InputHash::new();
+    for tones in chords {
+        hash.write(&(tones.len() as u64).to_le_bytes());
+        for &tpc in tones {
+            hash.write_tpc(tpc);
+        }
+    }
+    hash.finish()
+}
+
+/// The hash of pitch classes
+fn hash_pitch_classes(pitch_classes: &[u8]) -> u64 {
+    let mut hash = InputHash::new();
+    hash.write(pitch_classes);
+    hash.finish()
+}
+
+/// The estimated key of weighted notes, from `analysis::weighted_key`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyResult {
+    /// How the key was estimated
+    pub provenance: Provenance,
+    /// The estimated major key, or None if the notes have no weight
+    pub key: Option<Key>,
+}
+
+impl KeyResult {
+    /// The name of the algorithm
+    pub const ALGORITHM: &'static str = "analysis::weighted_key";
+    /// The version of the algorithm
+    pub const VERSION: u32 = 1;
+
+    /// Estimate the key of the notes with their weights
+    #[must_use]
+    pub fn compute(notes: &[(Tpc, f32)]) -> Self {
+        Self {
+            provenance: Provenance::new(Self::ALGORITHM, Self::VERSION, hash_weighted(notes)),
+            key: analysis::weighted_key(notes.iter().copied()),
+        }
+    }
+
+    /// Whether the result is still the one `compute` would give for the notes
+    #[must_use]
+    pub fn is_valid_for(&self, notes: &[(Tpc, f32)]) -> bool {
+        self.provenance
+            .matches(Self::ALGORITHM, Self::VERSION, hash_weighted(notes))
+    }
+}
+
+/// The chord labels of groups of tones, from `Chord::identify`
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::results::ChordLabelResult;
+/// let chords = vec![vec![Tpc::G, Tpc::B, Tpc::D, Tpc::F], vec![Tpc::C, Tpc::Cs]];
+/// let result = ChordLabelResult::compute(&chords);
+/// assert_eq!(Some("G7".to_string()), result.labels[0].map(|chord| chord.to_string()));
+/// assert_eq!(None, result.labels[1]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChordLabelResult {
+    /// How the chords were labeled
+    pub provenance: Provenance,
+    /// The chord of each group of tones, or None if it has no label
+    pub labels: Vec<Option<Chord>>,
+}
+
+impl ChordLabelResult {
+    /// The name of the algorithm
+    pub const ALGORITHM: &'static str = "chord::Chord::identify";
+    /// The version of the algorithm
+    pub const VERSION: u32 = 1;
+
+    /// Label each group of tones, with the first tone as the bass
+    #[must_use]
+    pub fn compute(chords: &[Vec<Tpc>]) -> Self {
+        Self {
+            provenance: Provenance::new(Self::ALGORITHM, Self::VERSION, hash_chords(chords)),
+            labels: chords.iter().map(|tones| Chord::identify(tones)).collect(),
+        }
+    }
+
+    /// Whether the result is still the one `compute` would give for the
+    /// chords
+    #[must_use]
+    pub fn is_valid_for(&self, chords: &[Vec<Tpc>]) -> bool {
+        self.provenance
+            .matches(Self::ALGORITHM, Self::VERSION, hash_chords(chords))
+    }
+}
+
+/// The spelling of pitch classes, from `spelling::spell_atonal`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpellingResult {
+    /// How the pitch classes were spelled
+    pub provenance: Provenance,
+    /// The tpc of each pitch class
+    pub tpcs: Vec<Tpc>,
+}
+
+impl SpellingResult {
+    /// The name of the algorithm
+    pub const ALGORITHM: &'static str = "spelling::spell_atonal";
+    /// The version of the algorithm
+    pub const VERSION: u32 = 1;
+
+    /// Spell the pitch classes, counted in semitones from C
+    #[must_use]
+    pub fn compute(pitch_classes: &[u8]) -> Self {
+        Self {
+            provenance: Provenance::new(
+                Self::ALGORITHM,
+                Self::VERSION,
+                hash_pitch_classes(pitch_classes),
+            ),
+            tpcs: spelling::spell_atonal(pitch_classes.iter().copied()),
+        }
+    }
+
+    /// Whether the result is still the one `compute` would give for the
+    /// pitch classes
+    #[must_use]
+    pub fn is_valid_for(&self, pitch_classes: &[u8]) -> bool {
+        self.provenance.matches(
+            Self::ALGORITHM,
+            Self::VERSION,
+            hash_pitch_classes(pitch_classes),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_stable() {
+        // The published FNV-1a test vectors
+        assert_eq!(0xcbf2_9ce4_8422_2325, InputHash::new().finish());
+        let mut hash = InputHash::new();
+        hash.write(b"a");
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hash.finish());
+    }
+
+    #[test]
+    fn test_chord_boundaries_change_hash() {
+        let together = vec![vec![Tpc::C, Tpc::E, Tpc::G]];
+        let apart = vec![vec![Tpc::C], vec![Tpc::E, Tpc::G]];
+        assert_ne!(hash_chords(&together), hash_chords(&apart));
+        let result = ChordLabelResult::compute(&together);
+        assert!(result.is_valid_for(&together));
+        assert!(!result.is_valid_for(&apart));
+    }
+
+    #[test]
+    fn test_spelling_result() {
+        let result = SpellingResult::compute(&[0, 4, 7]);
+        assert_eq!(vec![Tpc::C, Tpc::E, Tpc::G], result.tpcs);
+        assert_eq!(env!("CARGO_PKG_VERSION"), result.provenance.crate_version);
+        let mut outdated = result.clone();
+        outdated.provenance.algorithm_version = 0;
+        assert!(result.is_valid_for(&[0, 4, 7]));
+        assert!(!outdated.is_valid_for(&[0, 4, 7]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialized_format() {
+        use crate::chord::ChordQuality;
+
+        let provenance = |algorithm: &str| Provenance {
+            algorithm: algorithm.to_string(),
+            algorithm_version: 1,
+            crate_version: "0.1.1".to_string(),
+            input_hash: 42,
+        };
+        let key = KeyResult {
+            provenance: provenance(KeyResult::ALGORITHM),
+            key: Some(Key::Fs),
+        };
+        let labels = ChordLabelResult {
+            provenance: provenance(ChordLabelResult::ALGORITHM),
+            labels: vec![Some(Chord::new(Tpc::G, ChordQuality::Dominant7)), None],
+        };
+        let spelling = SpellingResult {
+            provenance: provenance(SpellingResult::ALGORITHM),
+            tpcs: vec![Tpc::C, Tpc::Eb, Tpc::Fs],
+        };
+        // The wire format and order of the fields are part of the format
+        let meta = |algorithm: &str| {
+            format!(
+                r#"{{"algorithm":"{algorithm}","algorithm_version":1,"crate_version":"0.1.1","input_hash":42}}"#
+            )
+        };
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(
+            format!(
+                r#"{{"provenance":{},"key":"F#"}}"#,
+                meta(KeyResult::ALGORITHM)
+            ),
+            json
+        );
+        assert_eq!(key, serde_json::from_str(&json).unwrap());
+        let json = serde_json::to_string(&labels).unwrap();
+        assert_eq!(
+            format!(
+                r#"{{"provenance":{},"labels":[{{"root":"G","quality":"Dominant7","bass":null}},null]}}"#,
+                meta(ChordLabelResult::ALGORITHM)
+            ),
+            json
+        );
+        assert_eq!(labels, serde_json::from_str(&json).unwrap());
+        let json = serde_json::to_string(&spelling).unwrap();
+        assert_eq!(
+            format!(
+                r#"{{"provenance":{},"tpcs":["C","Eb","F#"]}}"#,
+                meta(SpellingResult::ALGORITHM)
+            ),
+            json
+        );
+        assert_eq!(spelling, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,570 @@
+//! Roman numeral analysis, written in the `RomanText` and DCML annotation
+//! formats
+//!
+//! A `RomanNumeral` names a chord by the scale degree of its root in a major
+//! or minor key, its quality and its inversion. An `Annotation` places a
+//! numeral in a measure of a score, and `romantext` and `dcml` write
+//! annotations in the `RomanText` format read by music21 and in the harmony
+//! annotation standard of the DCML corpora, so that chords labeled with
+//! `Chord::identify` can be compared with existing datasets.
+//! ```
+//! # use tonality::{Key, Mode, Tpc};
+//! # use tonality::chord::{Chord, ChordQuality};
+//! # use tonality::roman::{self, Annotation};
+//! let dominant = Chord::new(Tpc::G, ChordQuality::Dominant7).with_bass(Tpc::B);
+//! let chords = [
+//!     (1, (0, 1), Chord::new(Tpc::C, ChordQuality::Major)),
+//!     (1, (1, 2), dominant),
+//!     (2, (0, 1), Chord::new(Tpc::A, ChordQuality::Minor)),
+//! ];
+//! let annotations: Vec<Annotation> = chords
+//!     .iter()
+//!     .filter_map(|&(measure, onset, chord)| {
+//!         Annotation::from_chord(measure, onset, Key::C, Mode::Major, chord)
+//!     })
+//!     .collect();
+//! assert_eq!(
+//!     "Time Signature: 4/4\n\nm1 C: I b3 V65\nm2 vi\n",
+//!     roman::romantext(&annotations, (4, 4))
+//! );
+//! ```
+use crate::chord::{Chord, ChordQuality};
+use crate::{Alteration, Interval, Key, Mode, Tpc};
+
+/// The numerals of the seven scale degrees
+const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+/// The figures of a triad in each inversion
+const TRIAD_FIGURES: [&str; 3] = ["", "6", "64"];
+
+/// The figures of a seventh chord in each inversion, in `RomanText`
+const ROMANTEXT_SEVENTH_FIGURES: [&str; 4] = ["7", "65", "43", "42"];
+
+/// The figures of a seventh chord in each inversion, in DCML
+const DCML_SEVENTH_FIGURES: [&str; 4] = ["7", "65", "43", "2"];
+
+/// A chord named by the scale degree of its root in a key
+/// ```
+/// # use tonality::{Key, Mode, Tpc};
+/// # use tonality::chord::{Chord, ChordQuality};
+/// # use tonality::roman::RomanNumeral;
+/// // The leading-tone seventh chord of A minor
+/// let chord = Chord::new(Tpc::Gs, ChordQuality::Diminished7);
+/// let numeral = RomanNumeral::from_chord(chord, Key::C, Mode::Minor).unwrap();
+/// assert_eq!("viio7", numeral.romantext(Mode::Minor));
+/// assert_eq!("#viio7", numeral.dcml());
+/// assert_eq!(Some(chord), numeral.chord(Key::C, Mode::Minor));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct RomanNumeral {
+    /// The zero-indexed scale degree of the root
+    pub degree: u8,
+    /// The alteration of the root from the scale degree, in the major scale
+    /// of major keys and the natural minor scale of minor keys
+    pub alteration: Alteration,
+    /// The quality of the chord
+    pub quality: ChordQuality,
+    /// Which chord tone is in the bass, as in `Chord::with_inversion`
+    pub inversion: usize,
+}
+
+impl RomanNumeral {
+    /// The numeral of a chord in a major or minor key. Returns None in the
+    /// other modes, for suspended chords, and if the bass is not a chord
+    /// tone.
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// # use tonality::roman::RomanNumeral;
+    /// let chord = Chord::new(Tpc::Ab, ChordQuality::Major).with_bass(Tpc::Eb);
+    /// let numeral = RomanNumeral::from_chord(chord, Key::C, Mode::Major).unwrap();
+    /// assert_eq!("bVI64", numeral.dcml());
+    /// let chord = Chord::new(Tpc::D, ChordQuality::Sus4);
+    /// assert_eq!(None, RomanNumeral::from_chord(chord, Key::C, Mode::Major));
+    /// ```
+    #[must_use]
+    pub fn from_chord(chord: Chord, key: Key, mode: Mode) -> Option<Self> {
+        if !is_major_or_minor(mode)
+            || matches!(chord.quality, ChordQuality::Sus2 | ChordQuality::Sus4)
+        {
+            return None;
+        }
+        let (degree, alteration) = degree_in(chord.root, key, mode);
+        Some(Self {
+            degree,
+            alteration,
+            quality: chord.quality,
+            inversion: chord.inversion()?,
+        })
+    }
+
+    /// The chord the numeral names in a key, or None if its root is outside
+    /// the domain of `Tpc` or its inversion is not a chord tone
+    #[must_use]
+    pub fn chord(self, key: Key, mode: Mode) -> Option<Chord> {
+        let root = key
+            .scale_degree(mode as isize + isize::from(self.degree))
+            .alter(self.alteration)?;
+        Chord::new(root, self.quality).with_inversion(self.inversion)
+    }
+
+    /// The numeral in `RomanText`, where the quality of a triad is given by the
+    /// case of the numeral and the marks `o` and `+`, and a seventh is
+    /// diatonic unless marked `o7`, `ø7` or altered in brackets. As in
+    /// music21, a lowercase sixth or seventh degree in a minor key is raised.
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// # use tonality::roman::RomanNumeral;
+    /// let c7 = Chord::new(Tpc::C, ChordQuality::Dominant7);
+    /// let numeral = RomanNumeral::from_chord(c7, Key::C, Mode::Major).unwrap();
+    /// assert_eq!("I7[b7]", numeral.romantext(Mode::Major));
+    /// let e7 = Chord::new(Tpc::E, ChordQuality::Dominant7).with_bass(Tpc::D);
+    /// let numeral = RomanNumeral::from_chord(e7, Key::C, Mode::Minor).unwrap();
+    /// assert_eq!("V42", numeral.romantext(Mode::Minor));
+    /// ```
+    #[must_use]
+    pub fn romantext(self, mode: Mode) -> String {
+        let lowercase = has_minor_third(self.quality);
+        let alteration = if mode == Mode::Minor && lowercase && self.degree % 7 >= 5 {
+            self.alteration.saturating_sub(1)
+        } else {
+            self.alteration
+        };
+        let mut label = numeral(self.degree, alteration, lowercase);
+        label.push_str(match self.quality {
+            ChordQuality::Diminished | ChordQuality::Diminished7 => "o",
+            ChordQuality::HalfDiminished7 => "ø",
+            ChordQuality::Augmented => "+",
+            _ => "",
+        });
+        label.push_str(self.figure(&ROMANTEXT_SEVENTH_FIGURES));
+        if let Some(seventh) = self.seventh_alteration(mode) {
+            let accidental = if seventh < 0 { "b" } else { "#" };
+            label.push('[');
+            label.push_str(&accidental.repeat(usize::from(seventh.unsigned_abs())));
+            label.push_str("7]");
+        }
+        label
+    }
+
+    /// The numeral in the DCML standard, where the quality of every chord is
+    /// given by the case of the numeral and the marks `o`, `%`, `+` and `M`
+    /// ```
+    /// # use tonality::{Key, Mode, Tpc};
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// # use tonality::roman::RomanNumeral;
+    /// let chord = Chord::new(Tpc::F, ChordQuality::Major7).with_bass(Tpc::A);
+    /// let numeral = RomanNumeral::from_chord(chord, Key::C, Mode::Major).unwrap();
+    /// assert_eq!("IVM65", numeral.dcml());
+    /// let chord = Chord::new(Tpc::D, ChordQuality::HalfDiminished7);
+    /// let numeral = RomanNumeral::from_chord(chord, Key::Eb, Mode::Minor).unwrap();
+    /// assert_eq!("ii%7", numeral.dcml());
+    /// ```
+    #[must_use]
+    pub fn dcml(self) -> String {
+        let mut label = numeral(self.degree, self.alteration, has_minor_third(self.quality));
+        label.push_str(match self.quality {
+            ChordQuality::Diminished | ChordQuality::Diminished7 => "o",
+            ChordQuality::HalfDiminished7 => "%",
+            ChordQuality::Augmented => "+",
+            ChordQuality::Major7 | ChordQuality::MinorMajor7 => "M",
+            _ => "",
+        });
+        label.push_str(self.figure(&DCML_SEVENTH_FIGURES));
+        label
+    }
+
+    /// The figure of the inversion, from the figures of seventh chords if
+    /// the chord has four tones
+    fn figure(self, seventh_figures: &[&'static str]) -> &'static str {
+        let figures = if self.quality.intervals().len() == 4 {
+            seventh_figures
+        } else {
+            &TRIAD_FIGURES
+        };
+        figures.get(self.inversion).copied().unwrap_or_default()
+    }
+
+    /// How far the seventh of the chord is from the diatonic seventh above
+    /// the root, if the quality leaves the seventh to the key and they
+    /// differ
+    fn seventh_alteration(self, mode: Mode) -> Option<Alteration> {
+        let seventh = match self.quality {
+            ChordQuality::Dominant7 | ChordQuality::Minor7 => Interval::Min7,
+            ChordQuality::Major7 | ChordQuality::MinorMajor7 => Interval::Maj7,
+            _ => return None,
+        };
+        // Positions on the line of fifths relative to the tonic
+        let degree = isize::from(self.degree % 7);
+        let root = i16::from(mode.degree_interval(degree) as i8) + 7 * i16::from(self.alteration);
+        let diatonic = i16::from(mode.degree_interval(degree + 6) as i8);
+        let alteration = (root + i16::from(seventh as i8) - diatonic) / 7;
+        // Within the alteration of the root and one more semitone
+        #[allow(clippy::cast_possible_truncation)]
+        let alteration = alteration as Alteration;
+        if alteration == 0 {
+            None
+        } else {
+            Some(alteration)
+        }
+    }
+}
+
+/// A Roman numeral at a position in a score
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct Annotation {
+    /// The measure number, counted from 1
+    pub measure: u32,
+    /// The onset within the measure in whole notes, as a numerator and a
+    /// denominator
+    pub onset: (u32, u32),
+    /// The key signature of the local key
+    pub key: Key,
+    /// The mode of the local key, major or minor
+    pub mode: Mode,
+    /// The numeral in the local key
+    pub numeral: RomanNumeral,
+}
+
+impl Annotation {
+    /// The annotation of a chord in a local key, or None if the chord has no
+    /// numeral in the key, as in `RomanNumeral::from_chord`
+    #[must_use]
+    pub fn from_chord(
+        measure: u32,
+        onset: (u32, u32),
+        key: Key,
+        mode: Mode,
+        chord: Chord,
+    ) -> Option<Self> {
+        Some(Self {
+            measure,
+            onset,
+            key,
+            mode,
+            numeral: RomanNumeral::from_chord(chord, key, mode)?,
+        })
+    }
+}
+
+/// The annotations in `RomanText`, with one line for each measure holding
+/// annotations. Beats are counted in the time signature, in dotted beats
+/// for compound meters, and the local key is written where it changes.
+/// ```
+/// # use tonality::{Key, Mode, Tpc};
+/// # use tonality::chord::{Chord, ChordQuality};
+/// # use tonality::roman::{self, Annotation};
+/// let chord = Chord::new(Tpc::E, ChordQuality::Major);
+/// let annotation = Annotation::from_chord(3, (3, 8), Key::C, Mode::Minor, chord).unwrap();
+/// assert_eq!("Time Signature: 6/8\n\nm3 b2 a: V\n", roman::romantext(&[annotation], (6, 8)));
+/// ```
+#[must_use]
+pub fn romantext(annotations: &[Annotation], time_signature: (u8, u8)) -> String {
+    let (beats, unit) = time_signature;
+    let mut lines = vec![format!("Time Signature: {beats}/{unit}"), String::new()];
+    // A dotted beat in compound meters
+    let beat_length = if beats > 3 && beats % 3 == 0 { 3 } else { 1 };
+    let mut measure = None;
+    let mut key = None;
+    for annotation in annotations {
+        if measure != Some(annotation.measure) {
+            lines.push(format!("m{}", annotation.measure));
+            measure = Some(annotation.measure);
+        }
+        let mut tokens = Vec::new();
+        let (numerator, denominator) = annotation.onset;
+        let beat = beat(
+            u64::from(numerator) * u64::from(unit),
+            u64::from(denominator) * beat_length,
+        );
+        if beat != "1" {
+            tokens.push(format!("b{beat}"));
+        }
+        if key != Some((annotation.key, annotation.mode)) {
+            let tonic = format!("{:#}", annotation.key.tonic(annotation.mode));
+            if annotation.mode.has_minor_third() {
+                tokens.push(format!("{}:", tonic.to_lowercase()));
+            } else {
+                tokens.push(format!("{tonic}:"));
+            }
+            key = Some((annotation.key, annotation.mode));
+        }
+        tokens.push(annotation.numeral.romantext(annotation.mode));
+        if let Some(line) = lines.last_mut() {
+            for token in tokens {
+                line.push(' ');
+                line.push_str(&token);
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// The annotations as a DCML harmony table, in tab-separated values with a
+/// header row. Each local key is given as a numeral in the global key, and
+/// the labels carry the global key on the first row and the local key where
+/// it changes.
+/// ```
+/// # use tonality::{Key, Mode, Tpc};
+/// # use tonality::chord::{Chord, ChordQuality};
+/// # use tonality::roman::{self, Annotation};
+/// let d7 = Chord::new(Tpc::D, ChordQuality::Dominant7);
+/// let g = Chord::new(Tpc::G, ChordQuality::Major);
+/// let annotations = [
+///     Annotation::from_chord(1, (0, 1), Key::C, Mode::Major, d7).unwrap(),
+///     Annotation::from_chord(1, (1, 2), Key::G, Mode::Major, g).unwrap(),
+/// ];
+/// let table = roman::dcml(&annotations, Key::C, Mode::Major);
+/// let rows: Vec<&str> = table.lines().collect();
+/// assert_eq!("1\t0\t.C.II7\tC\tI\tII7\t0\t0", rows[1]);
+/// assert_eq!("1\t1/2\tV.I\tC\tV\tI\t0\t0", rows[2]);
+/// ```
+#[must_use]
+pub fn dcml(annotations: &[Annotation], global_key: Key, global_mode: Mode) -> String {
+    let global_minor = global_mode.has_minor_third();
+    let global = format!("{:#}", global_key.tonic(global_mode));
+    let global = if global_minor {
+        global.to_lowercase()
+    } else {
+        global
+    };
+    let mut table = String::from(
+        "mn\tmn_onset\tlabel\tglobalkey\tlocalkey\tchord\tglobalkey_is_minor\tlocalkey_is_minor\n",
+    );
+    // The numeral of the global key as a local key
+    let mut previous = Some(numeral(0, 0, global_minor));
+    for (row, annotation) in annotations.iter().enumerate() {
+        let local_minor = annotation.mode.has_minor_third();
+        let (degree, alteration) = degree_in(
+            annotation.key.tonic(annotation.mode),
+            global_key,
+            global_mode,
+        );
+        let local = numeral(degree, alteration, local_minor);
+        let chord = annotation.numeral.dcml();
+        let mut label = if row == 0 {
+            format!(".{global}.")
+        } else {
+            String::new()
+        };
+        if previous.as_ref() != Some(&local) {
+            label.push_str(&local);
+            label.push('.');
+        }
+        label.push_str(&chord);
+        let (numerator, denominator) = annotation.onset;
+        let columns = [
+            annotation.measure.to_string(),
+            fraction(numerator, denominator),
+            label,
+            global.clone(),
+            local.clone(),
+            chord,
+            u8::from(global_minor).to_string(),
+            u8::from(local_minor).to_string(),
+        ];
+        table.push_str(&columns.join("\t"));
+        table.push('\n');
+        previous = Some(local);
+    }
+    table
+}
+
+/// Whether `RomanText` and DCML have numerals in the mode
+fn is_major_or_minor(mode: Mode) -> bool {
+    matches!(mode, Mode::Major | Mode::Minor)
+}
+
+/// Whether the chord has a minor third above the root, and so a lowercase
+/// numeral
+fn has_minor_third(quality: ChordQuality) -> bool {
+    quality.intervals().get(1) == Some(&Interval::Min3)
+}
+
+/// The zero-indexed degree of a tpc in the scale of a key, and its
+/// alteration from the scale
+fn degree_in(tpc: Tpc, key: Key, mode: Mode) -> (u8, Alteration) {
+    let degree = key.tonic(mode).step().diatonic_distance(tpc.step());
+    let scale_tone = key.scale_degree(mode as isize + isize::from(degree));
+    // On the same step, so a whole number of semitones apart
+    let alteration = (tpc as i8 - scale_tone as i8) / Tpc::DELTA_SEMITONE;
+    // Between 0 and 6
+    #[allow(clippy::cast_sign_loss)]
+    let degree = degree as u8;
+    (degree, alteration)
+}
+
+/// A numeral with flats or sharps for its alteration
+fn numeral(degree: u8, alteration: Alteration, lowercase: bool) -> String {
+    let accidental = if alteration < 0 { "b" } else { "#" };
+    let mut label = accidental.repeat(usize::from(alteration.unsigned_abs()));
+    let numeral = NUMERALS[usize::from(degree % 7)];
+    if lowercase {
+        label.push_str(&numeral.to_lowercase());
+    } else {
+        label.push_str(numeral);
+    }
+    label
+}
+
+/// The beat of an onset counted in beats, from 1, as a whole number or with
+/// up to two decimals
+fn beat(numerator: u64, denominator: u64) -> String {
+    if denominator == 0 || numerator.is_multiple_of(denominator) {
+        return (1 + numerator.checked_div(denominator).unwrap_or(0)).to_string();
+    }
+    // Onsets within a measure are far below the precision of f64
+    #[allow(clippy::cast_precision_loss)]
+    let beat = 1.0 + numerator as f64 / denominator as f64;
+    let beat = format!("{beat:.2}");
+    // Onsets that round to a whole beat lose the decimal point too
+    beat.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// A fraction in lowest terms, without a denominator of 1
+fn fraction(numerator: u32, denominator: u32) -> String {
+    if numerator == 0 || denominator == 0 {
+        return "0".to_string();
+    }
+    let (mut a, mut b) = (numerator, denominator);
+    while b != 0 {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    if denominator == a {
+        (numerator / a).to_string()
+    } else {
+        format!("{}/{}", numerator / a, denominator / a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        for key in Key::all() {
+            for &mode in &[Mode::Major, Mode::Minor] {
+                for degree in 0..7 {
+                    for &quality in &ChordQuality::ALL[..4] {
+                        let root = key.scale_degree(mode as isize + degree);
+                        // Some augmented triads reach beyond double sharps
+                        let Some(chord) = Chord::new(root, quality).with_inversion(1) else {
+                            continue;
+                        };
+                        let numeral = RomanNumeral::from_chord(chord, key, mode).unwrap();
+                        assert_eq!(0, numeral.alteration);
+                        assert_eq!(Some(chord), numeral.chord(key, mode));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_diatonic_sevenths() {
+        let labels = ["I7", "ii7", "iii7", "IV7", "V7", "vi7", "viiø7"];
+        for (degree, &label) in (0..7).zip(&labels) {
+            let quality = Key::C.seventh_quality(degree);
+            let chord = Chord::new(Key::C.scale_degree(degree), quality);
+            let numeral = RomanNumeral::from_chord(chord, Key::C, Mode::Major).unwrap();
+            assert_eq!(label, numeral.romantext(Mode::Major));
+        }
+    }
+
+    #[test]
+    fn test_minor_sixth_and_seventh() {
+        let numeral = |root, quality| {
+            RomanNumeral::from_chord(Chord::new(root, quality), Key::C, Mode::Minor).unwrap()
+        };
+        let subtonic = numeral(Tpc::G, ChordQuality::Major);
+        assert_eq!(
+            ("VII".to_string(), "VII".to_string()),
+            (subtonic.romantext(Mode::Minor), subtonic.dcml())
+        );
+        let leading = numeral(Tpc::Gs, ChordQuality::Diminished);
+        assert_eq!(
+            ("viio".to_string(), "#viio".to_string()),
+            (leading.romantext(Mode::Minor), leading.dcml())
+        );
+        let minor = numeral(Tpc::G, ChordQuality::Minor);
+        assert_eq!(
+            ("bvii".to_string(), "vii".to_string()),
+            (minor.romantext(Mode::Minor), minor.dcml())
+        );
+    }
+
+    #[test]
+    fn test_romantext_measures_and_keys() {
+        let annotations = [
+            Annotation::from_chord(
+                1,
+                (0, 1),
+                Key::G,
+                Mode::Major,
+                Chord::new(Tpc::G, ChordQuality::Major),
+            ),
+            Annotation::from_chord(
+                1,
+                (3, 8),
+                Key::G,
+                Mode::Major,
+                Chord::new(Tpc::D, ChordQuality::Major),
+            ),
+            Annotation::from_chord(
+                2,
+                (1, 4),
+                Key::D,
+                Mode::Major,
+                Chord::new(Tpc::A, ChordQuality::Dominant7),
+            ),
+        ];
+        let annotations: Vec<Annotation> = annotations.iter().map(|a| a.unwrap()).collect();
+        assert_eq!(
+            "Time Signature: 3/4\n\nm1 G: I b2.5 V\nm2 b2 D: V7\n",
+            romantext(&annotations, (3, 4))
+        );
+        assert_eq!("Time Signature: 3/4\n\n", romantext(&[], (3, 4)));
+    }
+
+    #[test]
+    fn test_dcml_local_keys() {
+        let chord = Chord::new(Tpc::E, ChordQuality::Minor);
+        let annotations = [
+            Annotation::from_chord(1, (0, 1), Key::G, Mode::Minor, chord).unwrap(),
+            Annotation::from_chord(2, (2, 4), Key::G, Mode::Major, chord).unwrap(),
+        ];
+        let table = dcml(&annotations, Key::G, Mode::Major);
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!("1\t0\t.G.vi.i\tG\tvi\ti\t0\t1", rows[1]);
+        assert_eq!("2\t1/2\tI.vi\tG\tI\tvi\t0\t0", rows[2]);
+    }
+
+    #[test]
+    fn test_out_of_range_fields() {
+        let numeral = RomanNumeral {
+            degree: u8::MAX,
+            alteration: i8::MIN,
+            quality: ChordQuality::Major7,
+            inversion: usize::MAX,
+        };
+        let _ = (numeral.romantext(Mode::Minor), numeral.dcml());
+        assert_eq!(None, numeral.chord(Key::C, Mode::Major));
+        assert_eq!("1", beat(1, 0));
+        assert_eq!("0", fraction(1, 0));
+    }
+
+    #[test]
+    fn test_beat_decimals() {
+        assert_eq!("2.5", beat(3, 2));
+        assert_eq!("1.33", beat(1, 3));
+        // Rounded up to the next beat, and down to the beat itself
+        assert_eq!("2", beat(999, 1000));
+        assert_eq!("1", beat(1, 1024));
+    }
+}
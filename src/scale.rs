@@ -0,0 +1,316 @@
+//! Scales defined by their intervals above the tonic
+//!
+//! Any type giving a list of intervals can be a `ScalePattern`, and gets
+//! spelling, membership tests and degree lookup in every tonic. The diatonic
+//! modes are scale patterns, and so is any slice of intervals:
+//! ```
+//! # use tonality::{Interval, Mode, Tpc};
+//! # use tonality::scale::ScalePattern;
+//! use Interval::*;
+//! // A Hijaz-like scale, without quarter tones
+//! let hijaz: &[Interval] = &[Unison, Min2, Maj3, P4, P5, Min6, Min7];
+//! assert_eq!(
+//!     Some(vec![Tpc::D, Tpc::Eb, Tpc::Fs, Tpc::G, Tpc::A, Tpc::Bb, Tpc::C]),
+//!     hijaz.spell(Tpc::D)
+//! );
+//! assert!(hijaz.contains_tpc(Tpc::D, Tpc::Fs));
+//! assert!(!hijaz.contains_tpc(Tpc::D, Tpc::Gb));
+//! assert_eq!(Some(Tpc::Bb), Mode::Minor.degree(Tpc::D, 5));
+//! ```
+use std::convert::TryFrom;
+
+use crate::repr::Contiguous;
+use crate::{Interval, Mode, Tpc};
+
+/// A scale, given by the intervals from its tonic up to each degree.
+///
+/// The intervals start with the unison and go up, with one interval for each
+/// degree within the octave. Every other method has a default
+/// implementation based on them.
+pub trait ScalePattern {
+    /// The intervals from the tonic up to each degree, starting with the
+    /// unison
+    fn intervals(&self) -> &[Interval];
+
+    /// The number of degrees in the scale within an octave
+    #[must_use]
+    fn degrees(&self) -> usize {
+        self.intervals().len()
+    }
+
+    /// The notes of the scale from the tonic up, or None if a note would be
+    /// beyond double sharp or double flat
+    #[must_use]
+    fn spell(&self, tonic: Tpc) -> Option<Vec<Tpc>> {
+        self.intervals()
+            .iter()
+            .map(|&interval| tonic + interval)
+            .collect()
+    }
+
+    /// The note on a zero-indexed degree of the scale, repeating in every
+    /// octave, or None if it would be beyond double sharp or double flat, or
+    /// if the scale has no degrees
+    #[must_use]
+    fn degree(&self, tonic: Tpc, degree: isize) -> Option<Tpc> {
+        let intervals = self.intervals();
+        let len = isize::try_from(intervals.len()).ok()?;
+        // Within the length of the intervals, which are not empty
+        #[allow(clippy::cast_sign_loss)]
+        let index = degree.checked_rem_euclid(len)? as usize;
+        tonic + *intervals.get(index)?
+    }
+
+    /// The zero-indexed degree of the scale which is spelled as the tpc, or
+    /// None if the tpc is not in the scale
+    /// ```
+    /// # use tonality::{Mode, Tpc};
+    /// # use tonality::scale::ScalePattern;
+    /// assert_eq!(Some(6), Mode::Major.degree_of(Tpc::Eb, Tpc::D));
+    /// assert_eq!(None, Mode::Major.degree_of(Tpc::Eb, Tpc::Db));
+    /// ```
+    #[must_use]
+    fn degree_of(&self, tonic: Tpc, tpc: Tpc) -> Option<usize> {
+        let interval = tonic.try_interval_to(tpc).ok()?;
+        self.intervals().iter().position(|&i| i == interval)
+    }
+
+    /// Whether the tpc is in the scale, with the same spelling. Not named
+    /// `contains`, which a slice of intervals has already.
+    #[must_use]
+    fn contains_tpc(&self, tonic: Tpc, tpc: Tpc) -> bool {
+        self.degree_of(tonic, tpc).is_some()
+    }
+
+    /// Whether the tpc is enharmonic to a note of the scale
+    /// ```
+    /// # use tonality::{Mode, Tpc};
+    /// # use tonality::scale::ScalePattern;
+    /// assert!(Mode::Major.contains_enharmonic(Tpc::C, Tpc::Fb));
+    /// assert!(!Mode::Major.contains_tpc(Tpc::C, Tpc::Fb));
+    /// ```
+    #[must_use]
+    fn contains_enharmonic(&self, tonic: Tpc, tpc: Tpc) -> bool {
+        let semitones =
+            (i16::from(tpc.pitch_class()) - i16::from(tonic.pitch_class())).rem_euclid(12);
+        self.intervals()
+            .iter()
+            .any(|interval| i16::from(interval.semitones()).rem_euclid(12) == semitones)
+    }
+}
+
+impl ScalePattern for [Interval] {
+    fn intervals(&self) -> &[Interval] {
+        self
+    }
+}
+
+impl ScalePattern for Vec<Interval> {
+    fn intervals(&self) -> &[Interval] {
+        self
+    }
+}
+
+impl<const N: usize> ScalePattern for [Interval; N] {
+    fn intervals(&self) -> &[Interval] {
+        self
+    }
+}
+
+impl ScalePattern for Mode {
+    fn intervals(&self) -> &[Interval] {
+        use Interval::{
+            Aug4, Dim5, Maj2, Maj3, Maj6, Maj7, Min2, Min3, Min6, Min7, Unison, P4, P5,
+        };
+
+        match self {
+            Mode::Major => &[Unison, Maj2, Maj3, P4, P5, Maj6, Maj7],
+            Mode::Dorian => &[Unison, Maj2, Min3, P4, P5, Maj6, Min7],
+            Mode::Phrygian => &[Unison, Min2, Min3, P4, P5, Min6, Min7],
+            Mode::Lydian => &[Unison, Maj2, Maj3, Aug4, P5, Maj6, Maj7],
+            Mode::Mixolydian => &[Unison, Maj2, Maj3, P4, P5, Maj6, Min7],
+            Mode::Minor => &[Unison, Maj2, Min3, P4, P5, Min6, Min7],
+            Mode::Locrian => &[Unison, Min2, Min3, P4, Dim5, Min6, Min7],
+        }
+    }
+}
+
+/// The harmonic minor scale, with a raised seventh degree
+pub const HARMONIC_MINOR: [Interval; 7] = [
+    Interval::Unison,
+    Interval::Maj2,
+    Interval::Min3,
+    Interval::P4,
+    Interval::P5,
+    Interval::Min6,
+    Interval::Maj7,
+];
+
+/// The ascending melodic minor scale, with raised sixth and seventh degrees
+pub const MELODIC_MINOR: [Interval; 7] = [
+    Interval::Unison,
+    Interval::Maj2,
+    Interval::Min3,
+    Interval::P4,
+    Interval::P5,
+    Interval::Maj6,
+    Interval::Maj7,
+];
+
+/// The whole tone scale, Messiaen's first mode of limited transposition
+pub const WHOLE_TONE: [Interval; 6] = [
+    Interval::Unison,
+    Interval::Maj2,
+    Interval::Maj3,
+    Interval::Aug4,
+    Interval::Aug5,
+    Interval::Aug6,
+];
+
+/// The octatonic scale of alternating semitones and whole tones, Messiaen's
+/// second mode of limited transposition
+pub const OCTATONIC: [Interval; 8] = [
+    Interval::Unison,
+    Interval::Min2,
+    Interval::Min3,
+    Interval::Maj3,
+    Interval::Aug4,
+    Interval::P5,
+    Interval::Maj6,
+    Interval::Min7,
+];
+
+/// The octatonic scale starting with a whole tone
+const OCTATONIC_WHOLE_HALF: [Interval; 8] = [
+    Interval::Unison,
+    Interval::Maj2,
+    Interval::Min3,
+    Interval::P4,
+    Interval::Dim5,
+    Interval::Min6,
+    Interval::Maj6,
+    Interval::Maj7,
+];
+
+/// The notes at each interval above the first note, spelled between G flat
+/// and A sharp on the line of fifths: with at most a sharp or flat, and never
+/// as E sharp, B sharp, C flat or F flat. The first note keeps its spelling.
+fn spell_readably<const N: usize>(start: Tpc, intervals: &[Interval; N]) -> [Tpc; N] {
+    const FLATTEST: i8 = Tpc::Gb as i8;
+    const SHARPEST: i8 = Tpc::As as i8;
+    let mut notes = [start; N];
+    for (note, &interval) in notes.iter_mut().zip(intervals).skip(1) {
+        let mut fifths = start as i8 + interval as i8;
+        while fifths > SHARPEST {
+            fifths -= Tpc::DELTA_ENHARMONIC;
+        }
+        while fifths < FLATTEST {
+            fifths += Tpc::DELTA_ENHARMONIC;
+        }
+        *note = Tpc::in_range(fifths);
+    }
+    notes
+}
+
+/// The whole tone scale from a note, spelled for reading rather than by
+/// stacking whole tones, which would reach double sharps within six notes.
+///
+/// The intervals of `WHOLE_TONE` are respelled with at most a sharp or flat,
+/// and never as E sharp, B sharp, C flat or F flat. The first note keeps its
+/// spelling.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::scale::spell_whole_tone;
+/// let scale = spell_whole_tone(Tpc::Fs);
+/// assert_eq!([Tpc::Fs, Tpc::Gs, Tpc::As, Tpc::C, Tpc::D, Tpc::E], scale);
+/// let scale = spell_whole_tone(Tpc::Db);
+/// assert_eq!([Tpc::Db, Tpc::Eb, Tpc::F, Tpc::G, Tpc::A, Tpc::B], scale);
+/// ```
+pub fn spell_whole_tone(start: Tpc) -> [Tpc; 6] {
+    spell_readably(start, &WHOLE_TONE)
+}
+
+/// The octatonic scale from a note, starting with a semitone as `OCTATONIC`
+/// or with a whole tone, spelled as `spell_whole_tone`
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::scale::spell_octatonic;
+/// let scale = spell_octatonic(Tpc::Fs, true);
+/// assert_eq!(
+///     [Tpc::Fs, Tpc::G, Tpc::A, Tpc::As, Tpc::C, Tpc::Cs, Tpc::Ds, Tpc::E],
+///     scale
+/// );
+/// let scale = spell_octatonic(Tpc::C, false);
+/// assert_eq!(
+///     [Tpc::C, Tpc::D, Tpc::Eb, Tpc::F, Tpc::Gb, Tpc::Ab, Tpc::A, Tpc::B],
+///     scale
+/// );
+/// ```
+pub fn spell_octatonic(start: Tpc, semitone_first: bool) -> [Tpc; 8] {
+    if semitone_first {
+        spell_readably(start, &OCTATONIC)
+    } else {
+        spell_readably(start, &OCTATONIC_WHOLE_HALF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    #[test]
+    fn test_modes_match_keys() {
+        for &mode in &Mode::ALL {
+            let spelled = mode.spell(Key::Eb.tonic(mode)).unwrap();
+            let scale: Vec<Tpc> = Key::Eb.modal_scale(mode).collect();
+            assert_eq!(scale, spelled);
+            for (degree, &interval) in (0..7).zip(mode.intervals()) {
+                assert_eq!(mode.degree_interval(degree), interval);
+            }
+        }
+    }
+
+    #[test]
+    fn test_patterns_ascend_within_an_octave() {
+        let patterns: [&[Interval]; 4] = [&HARMONIC_MINOR, &MELODIC_MINOR, &WHOLE_TONE, &OCTATONIC];
+        for pattern in &patterns {
+            assert_eq!(Interval::Unison, pattern[0]);
+            for pair in pattern.windows(2) {
+                assert!(pair[0].semitones() < pair[1].semitones());
+            }
+            assert!(pattern.last().unwrap().semitones() < 12);
+        }
+    }
+
+    #[test]
+    fn test_collections_are_readable() {
+        for value in Tpc::Fb as i8..=Tpc::Bs as i8 {
+            let start: Tpc = num_traits::FromPrimitive::from_i8(value).unwrap();
+            let whole_tone = spell_whole_tone(start);
+            let semitone_first = spell_octatonic(start, true);
+            let whole_tone_first = spell_octatonic(start, false);
+            let collections: [(&[Tpc], &[Interval]); 3] = [
+                (&whole_tone, &WHOLE_TONE),
+                (&semitone_first, &OCTATONIC),
+                (&whole_tone_first, &OCTATONIC_WHOLE_HALF),
+            ];
+            for (notes, intervals) in &collections {
+                assert_eq!(start, notes[0]);
+                for (&note, &interval) in notes.iter().zip(intervals.iter()).skip(1) {
+                    let semitones = (12 + note.pitch_class() - start.pitch_class()) % 12;
+                    assert_eq!(Ok(interval.semitones()), i8::try_from(semitones));
+                    assert!((Tpc::Gb as i8..=Tpc::As as i8).contains(&(note as i8)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_degree_wraps() {
+        assert_eq!(Some(Tpc::Gs), HARMONIC_MINOR.degree(Tpc::A, -1));
+        assert_eq!(Some(Tpc::Cs), WHOLE_TONE.degree(Tpc::Cs, 6));
+        assert_eq!(None, OCTATONIC.degree(Tpc::Bss, 4));
+        assert_eq!(None, Vec::new().degree(Tpc::C, 0));
+    }
+}
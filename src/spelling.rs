@@ -0,0 +1,608 @@
+//! Choosing between enharmonic spellings
+use crate::{Alteration, Interval, Key, Pitch, Tpc};
+
+/// A policy for choosing one of the enharmonic spellings of a pitch class.
+///
+/// Each policy picks a spelling from twelve consecutive positions on the line
+/// of fifths, so every pitch class has exactly one spelling under a policy.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::spelling::RespellPolicy;
+/// assert_eq!(Tpc::Cs, Tpc::Db.respell(RespellPolicy::PreferSharps));
+/// assert_eq!(Tpc::Db, Tpc::Cs.respell(RespellPolicy::PreferFlats));
+/// assert_eq!(Tpc::G, Tpc::Fss.respell(RespellPolicy::MinimizeAccidentals));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RespellPolicy {
+    /// Naturals and sharps: C, C♯, D, D♯, E, F, F♯, G, G♯, A, A♯, B
+    PreferSharps,
+    /// Naturals and flats: C, D♭, D, E♭, E, F, G♭, G, A♭, A, B♭, B
+    PreferFlats,
+    /// Naturals, or a single sharp or flat, centered around D on the line of
+    /// fifths: C, C♯, D, E♭, E, F, F♯, G, A♭, A, B♭, B
+    MinimizeAccidentals,
+    /// The key's scale, with chromatic notes spelled as the lowered second,
+    /// third, sixth and seventh degrees and the raised fourth degree. In C
+    /// major: C, D♭, D, E♭, E, F, F♯, G, A♭, A, B♭, B
+    KeyRelative(Key),
+    /// The minor key with this key signature, with chromatic notes spelled as
+    /// the raised sixth and seventh degrees, the lowered second and fifth
+    /// degrees and the raised third degree. In A minor: C, C♯, D, E♭, E, F, F♯,
+    /// G, G♯, A, B♭, B
+    KeyRelativeMinor(Key),
+}
+
+impl RespellPolicy {
+    /// The flattest position on the line of fifths allowed by the policy. The
+    /// eleven positions above it are also allowed.
+    #[inline]
+    fn flattest(self) -> i8 {
+        match self {
+            RespellPolicy::PreferSharps => Tpc::F as i8,
+            RespellPolicy::PreferFlats => Tpc::Gb as i8,
+            RespellPolicy::MinimizeAccidentals => Tpc::Ab as i8,
+            RespellPolicy::KeyRelative(key) => key as i8 - 5,
+            RespellPolicy::KeyRelativeMinor(key) => key as i8 - 3,
+        }
+    }
+
+    /// Spell any position on the line of fifths, including those outside the
+    /// domain of `Tpc`, according to the policy
+    #[inline]
+    pub(crate) fn spell(self, fifths: i8) -> Tpc {
+        let flattest = self.flattest();
+        // Between 0 and 11 positions above the flattest, which is in range
+        #[allow(clippy::cast_sign_loss)]
+        let index = (flattest - Tpc::MIN as i8
+            + (fifths - flattest).rem_euclid(Tpc::DELTA_ENHARMONIC)) as usize;
+        Tpc::from_index(index).unwrap_or(Tpc::MAX)
+    }
+}
+
+/// The costs `spell_atonal_with` weighs spellings by. The sequence with the
+/// lowest total cost is chosen, and ties are resolved in favor of sharps.
+///
+/// The default costs are those of `spell_atonal`: one for each switch between
+/// sharps and flats and for each pair of consecutive notes on the same step
+/// with different accidentals, and none for the accidentals themselves.
+/// `adjusted` learns costs from spellings corrected by a user.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpellingCosts {
+    /// The cost of a sharp after a flat, or a flat after a sharp, with any
+    /// naturals between them
+    pub direction_change: f32,
+    /// The cost of a note on the same step as the note before it, with a
+    /// different accidental
+    pub same_step: f32,
+    /// The cost of each note spelled with a sharp
+    pub sharp: f32,
+    /// The cost of each note spelled with a flat
+    pub flat: f32,
+}
+
+impl Default for SpellingCosts {
+    fn default() -> Self {
+        Self {
+            direction_change: 1.0,
+            same_step: 1.0,
+            sharp: 0.0,
+            flat: 0.0,
+        }
+    }
+}
+
+/// A spelling corrected by a user, such as in an engraving application
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserCorrection {
+    /// The spelling given by the speller
+    pub original: Tpc,
+    /// The spelling the user chose instead
+    pub corrected: Tpc,
+    /// The note before it in the sequence, if any
+    pub previous: Option<Tpc>,
+}
+
+impl SpellingCosts {
+    /// The costs after learning from corrections made by a user. For each
+    /// correction, the cost of every feature of the original spelling that
+    /// the corrected one lacks is increased by the rate, and that of every
+    /// feature the corrected one has in its place is decreased by the rate.
+    /// Costs never go below zero.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::spelling::{spell_atonal_with, SpellingCosts, UserCorrection};
+    /// let costs = SpellingCosts::default();
+    /// assert_eq!(vec![Tpc::Cs], spell_atonal_with(vec![1], &costs));
+    /// let correction = UserCorrection {
+    ///     original: Tpc::Cs,
+    ///     corrected: Tpc::Db,
+    ///     previous: None,
+    /// };
+    /// let costs = costs.adjusted(&[correction], 0.25);
+    /// assert_eq!(vec![Tpc::Db], spell_atonal_with(vec![1], &costs));
+    /// ```
+    #[must_use]
+    pub fn adjusted(mut self, corrections: &[UserCorrection], rate: f32) -> Self {
+        for &correction in corrections {
+            self.learn(correction, rate);
+        }
+        self
+    }
+
+    fn learn(&mut self, correction: UserCorrection, rate: f32) {
+        /// Move a cost by the rate, towards the spelling the user chose
+        fn nudge(cost: &mut f32, original: bool, corrected: bool, rate: f32) {
+            match (original, corrected) {
+                (true, false) => *cost += rate,
+                (false, true) => *cost = (*cost - rate).max(0.0),
+                _ => {}
+            }
+        }
+
+        let (original, corrected) = (correction.original, correction.corrected);
+        let sign = |tpc: Tpc| tpc.alteration(Key::C).signum();
+        nudge(
+            &mut self.sharp,
+            sign(original) > 0,
+            sign(corrected) > 0,
+            rate,
+        );
+        nudge(
+            &mut self.flat,
+            sign(original) < 0,
+            sign(corrected) < 0,
+            rate,
+        );
+        if let Some(previous) = correction.previous {
+            let changes_direction = |tpc: Tpc| sign(tpc) * sign(previous) < 0;
+            nudge(
+                &mut self.direction_change,
+                changes_direction(original),
+                changes_direction(corrected),
+                rate,
+            );
+            let same_step = |tpc: Tpc| tpc.step() == previous.step() && tpc != previous;
+            nudge(
+                &mut self.same_step,
+                same_step(original),
+                same_step(corrected),
+                rate,
+            );
+        }
+    }
+
+    /// The cost of spelling a note with an alteration in C major
+    fn accidental(&self, alteration: Alteration) -> f32 {
+        match alteration.signum() {
+            1 => self.sharp,
+            -1 => self.flat,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Spell a sequence of pitch classes, given as semitones above C, without a
+/// key, as is common in atonal music.
+///
+/// Every note is spelled as a natural or with a single sharp or flat. Among
+/// those spellings, the sequence has the fewest accidental changes: switches
+/// between sharps and flats, and consecutive notes on the same step with
+/// different accidentals. Ties are resolved in favor of sharps.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::spelling::spell_atonal;
+/// // F sharp followed by F would need a natural sign
+/// assert_eq!(vec![Tpc::Gb, Tpc::F, Tpc::Ab, Tpc::Db], spell_atonal(vec![6, 5, 8, 1]));
+/// assert_eq!(vec![Tpc::As, Tpc::B, Tpc::Cs], spell_atonal(vec![10, 11, 1]));
+/// ```
+#[must_use]
+pub fn spell_atonal<I>(pitch_classes: I) -> Vec<Tpc>
+where
+    I: IntoIterator<Item = u8>,
+{
+    spell_atonal_with(pitch_classes, &SpellingCosts::default())
+}
+
+/// Spell a sequence of pitch classes as `spell_atonal` does, with the lowest
+/// total of other costs
+#[must_use]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn spell_atonal_with<I>(pitch_classes: I, costs: &SpellingCosts) -> Vec<Tpc>
+where
+    I: IntoIterator<Item = u8>,
+{
+    /// A choice of spelling for a note, with the sign of the last sharp or
+    /// flat up to it, and the lowest cost it takes to get there
+    struct State {
+        tpc: Tpc,
+        direction: Alteration,
+        cost: f32,
+        previous: usize,
+    }
+
+    let mut layers: Vec<Vec<State>> = Vec::new();
+    for pitch_class in pitch_classes {
+        let sharp = Tpc::from_pitch_class(pitch_class, RespellPolicy::PreferSharps);
+        let flat = Tpc::from_pitch_class(pitch_class, RespellPolicy::PreferFlats);
+        let candidates = if sharp == flat {
+            vec![sharp]
+        } else {
+            vec![sharp, flat]
+        };
+        let mut layer: Vec<State> = Vec::new();
+        for &tpc in &candidates {
+            let alteration = tpc.alteration(Key::C);
+            let start = [State {
+                tpc,
+                direction: 0,
+                cost: 0.0,
+                previous: 0,
+            }];
+            let previous_layer = layers.last().map_or(&start[..], |layer| &layer[..]);
+            for (previous, state) in previous_layer.iter().enumerate() {
+                let mut cost = state.cost + costs.accidental(alteration);
+                if alteration * state.direction < 0 {
+                    cost += costs.direction_change;
+                }
+                if layers.last().is_some() && tpc.step() == state.tpc.step() && tpc != state.tpc {
+                    cost += costs.same_step;
+                }
+                let direction = if alteration == 0 {
+                    state.direction
+                } else {
+                    alteration
+                };
+                match layer
+                    .iter_mut()
+                    .find(|other| other.tpc == tpc && other.direction == direction)
+                {
+                    Some(other) if other.cost <= cost => {}
+                    Some(other) => {
+                        other.cost = cost;
+                        other.previous = previous;
+                    }
+                    None => layer.push(State {
+                        tpc,
+                        direction,
+                        cost,
+                        previous,
+                    }),
+                }
+            }
+        }
+        layers.push(layer);
+    }
+    // Follow the cheapest path back from the last note
+    let mut spelled = Vec::with_capacity(layers.len());
+    let mut index = layers.last().and_then(|layer| {
+        (0..layer.len()).min_by(|&a, &b| layer[a].cost.total_cmp(&layer[b].cost))
+    });
+    #[cfg(feature = "tracing")]
+    if let (Some(layer), Some(index)) = (layers.last(), index) {
+        tracing::debug!(
+            notes = layers.len(),
+            cost = layer[index].cost,
+            "chose the spelling with the lowest cost"
+        );
+    }
+    for layer in layers.iter().rev() {
+        let Some(current) = index else { break };
+        spelled.push(layer[current].tpc);
+        index = Some(layer[current].previous);
+    }
+    spelled.reverse();
+    spelled
+}
+
+/// Whether two sequences of notes are the same apart from spelling: equally
+/// long, and enharmonic note by note. Useful for comparing the output of an
+/// analysis against ground truth that spells some notes differently.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::spelling::slices_enharmonically_equal;
+/// let expected = [Tpc::B, Tpc::Ds, Tpc::Fs];
+/// assert!(slices_enharmonically_equal(&expected, &[Tpc::Cb, Tpc::Eb, Tpc::Gb]));
+/// assert!(slices_enharmonically_equal(&expected, &[Tpc::Ass, Tpc::Ds, Tpc::Ess]));
+/// assert!(!slices_enharmonically_equal(&expected, &expected[..2]));
+/// ```
+#[must_use]
+pub fn slices_enharmonically_equal(a: &[Tpc], b: &[Tpc]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(&a, &b)| a.enharmonic(b))
+}
+
+/// The pitch classes of the notes as a set, with bit `n` set for the pitch
+/// class `n` semitones above C
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::spelling::pitch_class_set;
+/// assert_eq!(0b1001_0001, pitch_class_set(vec![Tpc::C, Tpc::Fb, Tpc::G, Tpc::Bs]));
+/// ```
+#[must_use]
+pub fn pitch_class_set<I>(tpcs: I) -> u16
+where
+    I: IntoIterator<Item = Tpc>,
+{
+    tpcs.into_iter()
+        .fold(0, |set, tpc| set | 1 << tpc.pitch_class())
+}
+
+/// Whether two collections of notes contain the same pitch classes, in any
+/// order and any number of times
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::spelling::sets_enharmonically_equal;
+/// let chord = vec![Tpc::Gs, Tpc::C, Tpc::Ds];
+/// assert!(sets_enharmonically_equal(chord, vec![Tpc::Ab, Tpc::Eb, Tpc::Bs, Tpc::Ab]));
+/// assert!(!sets_enharmonically_equal(vec![Tpc::Gs], vec![Tpc::G]));
+/// ```
+#[must_use]
+pub fn sets_enharmonically_equal<A, B>(a: A, b: B) -> bool
+where
+    A: IntoIterator<Item = Tpc>,
+    B: IntoIterator<Item = Tpc>,
+{
+    pitch_class_set(a) == pitch_class_set(b)
+}
+
+/// Whether two sequences of pitches sound the same note by note in twelve
+/// tone equal temperament, so that B sharp 3 equals C4 but not C5
+/// ```
+/// # use tonality::{Pitch, Tpc};
+/// # use tonality::spelling::pitches_enharmonically_equal;
+/// let a = [Pitch::new(Tpc::Bs, 3), Pitch::new(Tpc::Fb, 4)];
+/// assert!(pitches_enharmonically_equal(&a, &[Pitch::new(Tpc::C, 4), Pitch::new(Tpc::E, 4)]));
+/// assert!(!pitches_enharmonically_equal(&a, &[Pitch::new(Tpc::C, 5), Pitch::new(Tpc::E, 4)]));
+/// ```
+#[must_use]
+pub fn pitches_enharmonically_equal(a: &[Pitch], b: &[Pitch]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.midi() == b.midi())
+}
+
+/// Why `autocorrect` respelled a note
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CorrectionReason {
+    /// A note altered twice in the key, between notes which are not, such as
+    /// a lone F double sharp in C major
+    IsolatedDoubleAccidental,
+    /// A chromatic note spelled on the same step as the note a semitone away
+    /// that follows it, such as C sharp going down to C
+    ChromaticDirection,
+}
+
+/// A note respelled by `autocorrect`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Correction {
+    /// The index of the note in the sequence
+    pub index: usize,
+    /// The spelling before the correction
+    pub original: Tpc,
+    /// The spelling after the correction
+    pub corrected: Tpc,
+    /// Why the note was respelled
+    pub reason: CorrectionReason,
+}
+
+/// Respell implausible notes of a sequence in a key, such as the output of
+/// optical music recognition, returning the corrected sequence and each
+/// correction made.
+///
+/// Notes altered twice in the key are respelled as `Tpc::respell_in_key`,
+/// unless a neighbouring note is also altered twice, as in a passage in a
+/// remote key. Then chromatic notes moving by a semitone to the next note
+/// are spelled on a different step from it, raised going up and lowered going
+/// down, if that spelling is altered at most once in the key. Notes of the
+/// key's scale are never changed.
+/// ```
+/// # use tonality::{Key, Tpc};
+/// # use tonality::spelling::{autocorrect, CorrectionReason};
+/// let notes = [Tpc::E, Tpc::Fss, Tpc::A, Tpc::Cs, Tpc::C];
+/// let (corrected, corrections) = autocorrect(&notes, Key::C);
+/// assert_eq!(vec![Tpc::E, Tpc::G, Tpc::A, Tpc::Db, Tpc::C], corrected);
+/// assert_eq!(CorrectionReason::IsolatedDoubleAccidental, corrections[0].reason);
+/// assert_eq!((3, Tpc::Cs), (corrections[1].index, corrections[1].original));
+/// ```
+#[must_use]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn autocorrect(notes: &[Tpc], key: Key) -> (Vec<Tpc>, Vec<Correction>) {
+    let twice_altered = |tpc: Tpc| tpc.alteration(key).abs() >= 2;
+    let mut corrected = notes.to_vec();
+    let mut corrections = Vec::new();
+    for (index, &tpc) in notes.iter().enumerate() {
+        let before = index.checked_sub(1).map(|before| notes[before]);
+        let after = notes.get(index + 1).copied();
+        let isolated = !before.into_iter().chain(after).any(twice_altered);
+        if twice_altered(tpc) && isolated {
+            corrected[index] = tpc.respell_in_key(key);
+            corrections.push(Correction {
+                index,
+                original: tpc,
+                corrected: corrected[index],
+                reason: CorrectionReason::IsolatedDoubleAccidental,
+            });
+        }
+    }
+    for index in 0..corrected.len().saturating_sub(1) {
+        let (tpc, next) = (corrected[index], corrected[index + 1]);
+        if tpc.alteration(key) == 0 || tpc.step() != next.step() {
+            continue;
+        }
+        let respelled = match (next.pitch_class() + 12 - tpc.pitch_class()) % 12 {
+            1 => next - Interval::Min2,
+            11 => next + Interval::Min2,
+            _ => None,
+        };
+        let Some(respelled) = respelled.filter(|respelled| respelled.alteration(key).abs() <= 1)
+        else {
+            continue;
+        };
+        corrected[index] = respelled;
+        let reason = CorrectionReason::ChromaticDirection;
+        match corrections.iter_mut().find(|c| c.index == index) {
+            Some(correction) => {
+                correction.corrected = respelled;
+                correction.reason = reason;
+            }
+            None => corrections.push(Correction {
+                index,
+                original: tpc,
+                corrected: respelled,
+                reason,
+            }),
+        }
+    }
+    corrections.sort_by_key(|correction| correction.index);
+    #[cfg(feature = "tracing")]
+    for correction in &corrections {
+        tracing::debug!(
+            index = correction.index,
+            original = %correction.original,
+            corrected = %correction.corrected,
+            reason = ?correction.reason,
+            "respelled note"
+        );
+    }
+    (corrected, corrections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_relative() {
+        let policy = RespellPolicy::KeyRelative(Key::F);
+        assert_eq!(Tpc::Bb, Tpc::As.respell(policy));
+        assert_eq!(Tpc::Gb, Tpc::Fs.respell(policy));
+        let policy = RespellPolicy::KeyRelative(Key::Cs);
+        assert_eq!(Tpc::D, Tpc::Css.respell(policy));
+        assert_eq!(Tpc::Fss, Tpc::G.respell(policy));
+        let policy = RespellPolicy::KeyRelativeMinor(Key::C);
+        assert_eq!(Tpc::Gs, Tpc::Ab.respell(policy));
+        assert_eq!(Tpc::Eb, Tpc::Ds.respell(policy));
+    }
+
+    #[test]
+    fn test_atonal_avoids_changes() {
+        // C sharp and D sharp would be followed by naturals on the same step
+        let expected = vec![Tpc::Db, Tpc::C, Tpc::Eb, Tpc::D];
+        assert_eq!(expected, spell_atonal(vec![1, 0, 3, 2]));
+        // E flat would follow E, and D flat would then switch to flats
+        let expected = vec![Tpc::Cs, Tpc::E, Tpc::Ds];
+        assert_eq!(expected, spell_atonal(vec![1, 4, 3]));
+        assert_eq!(Vec::<Tpc>::new(), spell_atonal(Vec::new()));
+    }
+
+    #[test]
+    fn test_learned_costs() {
+        // After a C sharp, the user keeps the sharps going by correcting G
+        // flat to F sharp. Flats and changes of direction cost more, sharps
+        // cannot cost less than nothing, and neither spelling shares a step
+        // with C sharp.
+        let correction = UserCorrection {
+            original: Tpc::Gb,
+            corrected: Tpc::Fs,
+            previous: Some(Tpc::Cs),
+        };
+        let costs = SpellingCosts::default().adjusted(&[correction; 3], 0.5);
+        let learned = SpellingCosts {
+            direction_change: 2.5,
+            same_step: 1.0,
+            sharp: 0.0,
+            flat: 1.5,
+        };
+        assert_eq!(learned, costs);
+        // An unchanged spelling teaches nothing
+        let unchanged = UserCorrection {
+            original: Tpc::E,
+            corrected: Tpc::E,
+            previous: Some(Tpc::Eb),
+        };
+        assert_eq!(costs, costs.adjusted(&[unchanged], 0.5));
+        // Costs do not go below zero
+        let towards_flats = UserCorrection {
+            original: Tpc::Cs,
+            corrected: Tpc::Db,
+            previous: None,
+        };
+        let costs = SpellingCosts::default().adjusted(&[towards_flats; 4], 1.0);
+        assert!(costs.flat.abs() < f32::EPSILON);
+        assert_eq!(
+            vec![Tpc::Db, Tpc::Eb],
+            spell_atonal_with(vec![1, 3], &costs)
+        );
+    }
+
+    #[test]
+    fn test_atonal_no_double_accidentals() {
+        let pitch_classes: Vec<u8> = (0..48).map(|i| (i * 5 + i / 3) % 12).collect();
+        let spelled = spell_atonal(pitch_classes.iter().copied());
+        for (&pitch_class, &tpc) in pitch_classes.iter().zip(&spelled) {
+            assert_eq!(pitch_class, tpc.pitch_class());
+            assert!(tpc.alteration(Key::C).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_each_pitch_class_once() {
+        let policies = [
+            RespellPolicy::PreferSharps,
+            RespellPolicy::PreferFlats,
+            RespellPolicy::MinimizeAccidentals,
+            RespellPolicy::KeyRelative(Key::Cb),
+            RespellPolicy::KeyRelative(Key::Cs),
+            RespellPolicy::KeyRelativeMinor(Key::Cb),
+            RespellPolicy::KeyRelativeMinor(Key::Cs),
+        ];
+        for &policy in &policies {
+            let mut spellings: Vec<Tpc> = (0..12)
+                .map(|pitch_class| Tpc::from_pitch_class(pitch_class, policy))
+                .collect();
+            spellings.sort();
+            spellings.dedup();
+            assert_eq!(12, spellings.len());
+        }
+    }
+
+    #[test]
+    fn test_enharmonic_equality_at_the_extremes() {
+        // Double accidentals an octave apart on the line of fifths
+        assert!(slices_enharmonically_equal(&[Tpc::Fbb], &[Tpc::Ds]));
+        assert!(slices_enharmonically_equal(&[Tpc::Bss], &[Tpc::Db]));
+        assert!(!slices_enharmonically_equal(&[Tpc::Cb], &[Tpc::C]));
+        assert!(sets_enharmonically_equal(
+            vec![Tpc::Cb, Tpc::Bs],
+            vec![Tpc::C, Tpc::B]
+        ));
+        assert!(sets_enharmonically_equal(Vec::new(), Vec::new()));
+        for tpc in Tpc::all() {
+            assert_eq!(1 << tpc.pitch_class(), pitch_class_set(vec![tpc]));
+        }
+    }
+
+    #[test]
+    fn test_autocorrect_keeps_remote_passages() {
+        // Two double sharps in a row are taken to be intended
+        let notes = [Tpc::Fss, Tpc::Css, Tpc::D];
+        let (corrected, corrections) = autocorrect(&notes, Key::C);
+        assert_eq!(notes.to_vec(), corrected);
+        assert!(corrections.is_empty());
+    }
+
+    #[test]
+    fn test_autocorrect_chromatic_direction() {
+        let notes = [Tpc::Db, Tpc::D, Tpc::Gs, Tpc::G, Tpc::Fs, Tpc::G];
+        let (corrected, corrections) = autocorrect(&notes, Key::C);
+        assert_eq!(
+            vec![Tpc::Cs, Tpc::D, Tpc::Ab, Tpc::G, Tpc::Fs, Tpc::G],
+            corrected
+        );
+        assert_eq!(2, corrections.len());
+        assert!(corrections
+            .iter()
+            .all(|c| c.reason == CorrectionReason::ChromaticDirection));
+        assert!(corrections
+            .iter()
+            .all(|c| c.original.enharmonic(c.corrected)));
+    }
+}
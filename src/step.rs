@@ -1,10 +1,13 @@
 //! A position on a music staff
-use std::convert::TryFrom;
+use std::fmt;
 use std::ops::{Add, Sub};
 
 use num_derive::FromPrimitive;
 
+use crate::interval::Direction;
+use crate::repr::Contiguous;
 use crate::Accidental;
+use crate::Interval;
 use crate::Key;
 use crate::Tpc;
 
@@ -13,6 +16,7 @@ use crate::Tpc;
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, FromPrimitive)]
 #[must_use]
 #[allow(missing_docs)]
+#[repr(i8)]
 pub enum Step {
     C = 0,
     D,
@@ -35,11 +39,86 @@ impl Step {
     /// B is the highest step
     pub const MAX: Step = Step::B;
 
+    /// Every step, from C up to B
+    /// ```
+    /// # use tonality::Step;
+    /// assert_eq!(7, Step::all().count());
+    /// assert_eq!(Some(Step::B), Step::all().last());
+    /// ```
+    #[must_use]
+    pub fn all() -> impl DoubleEndedIterator<Item = Step> {
+        Self::VARIANTS.iter().copied()
+    }
+
+    /// The number of semitones from C up to the unaltered step
+    /// ```
+    /// # use tonality::Step;
+    /// assert_eq!(0, Step::C.semitones());
+    /// assert_eq!(5, Step::F.semitones());
+    /// assert_eq!(11, Step::B.semitones());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn semitones(self) -> i8 {
+        const SEMITONES: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+        SEMITONES[self as usize]
+    }
+
+    /// The number of steps from this step up to another, between 0 and 6
+    /// ```
+    /// # use tonality::Step;
+    /// assert_eq!(2, Step::C.diatonic_distance(Step::E));
+    /// // From A up to F is a sixth
+    /// assert_eq!(5, Step::A.diatonic_distance(Step::F));
+    /// assert_eq!(0, Step::G.diatonic_distance(Step::G));
+    /// ```
+    #[must_use]
+    pub fn diatonic_distance(self, other: Step) -> i8 {
+        (other as i8 - self as i8).rem_euclid(7)
+    }
+
+    /// The steps from this step to another in a direction, both included,
+    /// wrapping around the octave. From a step to itself is only the step.
+    /// ```
+    /// # use tonality::Step;
+    /// # use tonality::interval::Direction;
+    /// let up: Vec<Step> = Step::A.range_to(Step::D, Direction::Up).collect();
+    /// assert_eq!(vec![Step::A, Step::B, Step::C, Step::D], up);
+    /// let down: Vec<Step> = Step::A.range_to(Step::D, Direction::Down).collect();
+    /// assert_eq!(vec![Step::A, Step::G, Step::F, Step::E, Step::D], down);
+    /// ```
+    pub fn range_to(self, other: Step, direction: Direction) -> impl Iterator<Item = Step> {
+        let (sign, distance) = match direction {
+            Direction::Up => (1, self.diatonic_distance(other)),
+            Direction::Down => (-1, other.diatonic_distance(self)),
+        };
+        (0..=isize::from(distance)).map(move |steps| self + sign * steps)
+    }
+
+    /// Whether this step, placed in closed position above the other, stays in
+    /// the octave of the other step.
+    ///
+    /// Octave numbers change between B and C, so the F above A flat is in the
+    /// next octave, while the B flat above it is in the same octave. A step
+    /// placed above itself is an octave higher, so it is never in the same
+    /// octave.
+    /// ```
+    /// # use tonality::Step;
+    /// assert!(!Step::F.same_octave_above(Step::A));
+    /// assert!(Step::B.same_octave_above(Step::A));
+    /// assert!(!Step::A.same_octave_above(Step::A));
+    /// ```
+    #[must_use]
+    pub fn same_octave_above(self, other: Step) -> bool {
+        self > other
+    }
+
     /// The tonal pitch class resulting from applying an accidental to the step
     /// ```
     /// # use tonality::{Accidental, Step, Tpc};
     /// assert_eq!(Tpc::Ab, Step::A.with_accidental(Accidental::Flat));
     /// ```
+    #[inline]
     pub fn with_accidental(self, alter: Accidental) -> Tpc {
         #[rustfmt::skip]
         const SPELLINGS: [Tpc; 35] = [
@@ -51,10 +130,10 @@ impl Step {
             Tpc::Abb, Tpc::Ab, Tpc::A, Tpc::As, Tpc::Ass,
             Tpc::Bbb, Tpc::Bb, Tpc::B, Tpc::Bs, Tpc::Bss,
         ];
-        let step = self as i8;
-        let alter = alter as i8;
-        let i = step * 5 + alter + 2;
-        SPELLINGS[usize::try_from(i).unwrap()]
+        // Accidentals range from -2 to 2
+        #[allow(clippy::cast_sign_loss)]
+        let alter = (alter as i8 + 2) as usize;
+        SPELLINGS[self as usize * 5 + alter]
     }
 
     /// The tonal pitch class of the step in the given key
@@ -62,6 +141,7 @@ impl Step {
     /// # use tonality::{Key, Step, Tpc};
     /// assert_eq!(Tpc::Ab, Step::A.with_key(Key::Eb));
     /// ```
+    #[inline]
     pub fn with_key(self, key: Key) -> Tpc {
         #[rustfmt::skip]
         const BY_STEP_AND_KEY: [Tpc; 7 * Key::NUM_OF as usize] = [
@@ -81,19 +161,169 @@ impl Step {
             Tpc::Cs, Tpc::Ds, Tpc::Es, Tpc::Fs, Tpc::Gs, Tpc::As, Tpc::B,  // F#
             Tpc::Cs, Tpc::Ds, Tpc::Es, Tpc::Fs, Tpc::Gs, Tpc::As, Tpc::Bs, // C#
         ];
-        let key = usize::try_from(key as i8 - Key::MIN as i8).unwrap();
+        // Key::MIN is the lowest discriminant
+        #[allow(clippy::cast_sign_loss)]
+        let key = (key as i8 - Key::MIN as i8) as usize;
         BY_STEP_AND_KEY[7 * key + self as usize]
     }
+
+    /// The tonal pitch class of a note written as the step with an optional
+    /// accidental, where no accidental means that the note follows the key
+    /// signature. This is the inverse of `Tpc::altered_step`.
+    /// ```
+    /// # use tonality::{Accidental, Key, Step, Tpc};
+    /// assert_eq!(Tpc::Bb, Step::B.resolve(None, Key::F));
+    /// assert_eq!(Tpc::B, Step::B.resolve(Some(Accidental::Natural), Key::F));
+    /// assert_eq!(Tpc::Fs, Step::F.resolve(Some(Accidental::Sharp), Key::C));
+    /// ```
+    #[inline]
+    pub fn resolve(self, accidental: Option<Accidental>, key: Key) -> Tpc {
+        match accidental {
+            Some(accidental) => self.with_accidental(accidental),
+            None => self.with_key(key),
+        }
+    }
+
+    /// Transpose a note written as a step with an optional accidental, where
+    /// no accidental means that the note follows the key signature.
+    ///
+    /// The note is read in the key `from`, transposed up by the interval, and
+    /// written in the key `to`. The result has an accidental if the new key
+    /// signature doesn't imply the transposed note. An explicit accidental that
+    /// was redundant in the old key, such as a courtesy accidental, stays
+    /// explicit.
+    ///
+    /// Returns None if the transposed note is outside the domain of `Tpc`.
+    /// ```
+    /// # use tonality::{Accidental, Interval, Key, Step};
+    /// // B flat in F major, transposed up a fifth to C major, is F natural
+    /// assert_eq!(
+    ///     Some((Step::F, None)),
+    ///     Step::B.transpose(None, Key::F, Key::C, Interval::P5)
+    /// );
+    /// // F sharp in C major, transposed up a second to D major, is G sharp
+    /// assert_eq!(
+    ///     Some((Step::G, Some(Accidental::Sharp))),
+    ///     Step::F.transpose(Some(Accidental::Sharp), Key::C, Key::D, Interval::Maj2)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn transpose(
+        self,
+        accidental: Option<Accidental>,
+        from: Key,
+        to: Key,
+        interval: Interval,
+    ) -> Option<(Step, Option<Accidental>)> {
+        let tpc = self.resolve(accidental, from);
+        let courtesy = accidental.is_some() && tpc == self.with_key(from);
+        let transposed = (tpc + interval)?;
+        match transposed.altered_step(Some(to)) {
+            (step, None) if courtesy => Some((step, Some(transposed.accidental()))),
+            spelled => Some(spelled),
+        }
+    }
+}
+
+/// A note as it is written on the staff in a key: its step, and the
+/// accidental that has to be written, if the key signature does not already
+/// give the note. Returned by `Tpc::spelled_step`.
+///
+/// A written natural sign is `Some(Accidental::Natural)`, which cancels the
+/// key signature. No accidental means that the key signature, or the lack of
+/// one, gives the note.
+/// ```
+/// # use tonality::{Accidental, Key, Step, Tpc};
+/// # use tonality::step::SpelledStep;
+/// let b = Tpc::B.spelled_step(Some(Key::F));
+/// assert_eq!(SpelledStep::with_accidental(Step::B, Accidental::Natural), b);
+/// assert!(b.needs_accidental());
+/// let b_flat = Tpc::Bb.spelled_step(Some(Key::F));
+/// assert_eq!(SpelledStep::covered(Step::B), b_flat);
+/// assert_eq!(Tpc::Bb, b_flat.resolve(Key::F));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct SpelledStep {
+    /// The step on the staff
+    pub step: Step,
+    /// The accidental to write, or None if the key signature gives the note
+    pub accidental: Option<Accidental>,
+    /// Whether the key signature gives the note, so that no accidental is
+    /// written
+    pub covered_by_key: bool,
+}
+
+impl SpelledStep {
+    /// A step which the key signature gives, with no accidental written
+    pub const fn covered(step: Step) -> Self {
+        Self {
+            step,
+            accidental: None,
+            covered_by_key: true,
+        }
+    }
+
+    /// A step with an accidental written, including a natural sign
+    pub const fn with_accidental(step: Step, accidental: Accidental) -> Self {
+        Self {
+            step,
+            accidental: Some(accidental),
+            covered_by_key: false,
+        }
+    }
+
+    /// Whether an accidental has to be written
+    #[must_use]
+    pub const fn needs_accidental(self) -> bool {
+        !self.covered_by_key
+    }
+
+    /// The tonal pitch class of the written note in a key, as `Step::resolve`
+    pub fn resolve(self, key: Key) -> Tpc {
+        self.step.resolve(self.accidental, key)
+    }
+}
+
+impl From<(Step, Option<Accidental>)> for SpelledStep {
+    fn from((step, accidental): (Step, Option<Accidental>)) -> Self {
+        match accidental {
+            Some(accidental) => Self::with_accidental(step, accidental),
+            None => Self::covered(step),
+        }
+    }
+}
+
+impl From<SpelledStep> for (Step, Option<Accidental>) {
+    fn from(spelled: SpelledStep) -> Self {
+        (spelled.step, spelled.accidental)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = match self {
+            Step::C => "C",
+            Step::D => "D",
+            Step::E => "E",
+            Step::F => "F",
+            Step::G => "G",
+            Step::A => "A",
+            Step::B => "B",
+        };
+        f.write_str(letter)
+    }
 }
 
 impl Add<isize> for Step {
     type Output = Step;
 
     fn add(self, rhs: isize) -> Self::Output {
-        use num_traits::FromPrimitive;
-
-        let new_step = (self as isize + rhs).rem_euclid(7);
-        FromPrimitive::from_isize(new_step).unwrap()
+        // Reduced first, so that no number of steps overflows. Between 0 and
+        // 12 before the last reduction.
+        #[allow(clippy::cast_possible_truncation)]
+        let new_step = (self as i8 + rhs.rem_euclid(7) as i8) % 7;
+        Step::in_range(new_step)
     }
 }
 
@@ -101,7 +331,7 @@ impl Sub<isize> for Step {
     type Output = Step;
 
     fn sub(self, rhs: isize) -> Self::Output {
-        self.add(-rhs)
+        self.add(7 - rhs.rem_euclid(7))
     }
 }
 
@@ -127,4 +357,73 @@ mod tests {
         assert_eq!(Tpc::G, Step::G.with_key(Key::D));
         assert_eq!(Tpc::E, Step::E.with_key(Key::F));
     }
+
+    #[test]
+    fn test_resolve_inverts_altered_step() {
+        for tpc in Tpc::all() {
+            for &key in &[Key::Cb, Key::C, Key::E] {
+                let (step, accidental) = tpc.altered_step(Some(key));
+                assert_eq!(tpc, step.resolve(accidental, key));
+            }
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let letters: String = Step::all().map(|step| step.to_string()).collect();
+        assert_eq!("CDEFGAB", letters);
+    }
+
+    #[test]
+    fn test_range_to() {
+        for from in Step::all() {
+            assert_eq!(
+                vec![from],
+                from.range_to(from, Direction::Down).collect::<Vec<_>>()
+            );
+            let octave: Vec<Step> = from.range_to(from - 1, Direction::Up).collect();
+            assert_eq!(7, octave.len());
+            let mut down: Vec<Step> = (from - 1).range_to(from, Direction::Down).collect();
+            down.reverse();
+            assert_eq!(octave, down);
+        }
+        let up: Vec<Step> = Step::E.range_to(Step::G, Direction::Up).collect();
+        assert_eq!(vec![Step::E, Step::F, Step::G], up);
+    }
+
+    #[test]
+    fn test_transpose_keeps_courtesy_accidental() {
+        // A redundant sharp on F in G major stays explicit in A major
+        assert_eq!(
+            Some((Step::G, Some(Accidental::Sharp))),
+            Step::F.transpose(Some(Accidental::Sharp), Key::G, Key::A, Interval::Maj2)
+        );
+    }
+
+    #[test]
+    fn test_transpose_drops_chromatic_accidental() {
+        // B natural is chromatic in F major, but C natural is diatonic in G major
+        assert_eq!(
+            Some((Step::C, None)),
+            Step::B.transpose(Some(Accidental::Natural), Key::F, Key::G, Interval::Min2)
+        );
+    }
+
+    #[test]
+    fn test_transpose_out_of_range() {
+        let ess = Step::E.transpose(Some(Accidental::DblSharp), Key::C, Key::D, Interval::Maj2);
+        assert_eq!(None, ess);
+    }
+
+    #[test]
+    fn test_spelled_step_matches_altered_step() {
+        for key in Key::all() {
+            for tpc in Tpc::all() {
+                let spelled = tpc.spelled_step(Some(key));
+                assert_eq!(tpc.altered_step(Some(key)), spelled.into());
+                assert_eq!(spelled.covered_by_key, spelled.accidental.is_none());
+                assert_eq!(tpc, spelled.resolve(key));
+            }
+        }
+    }
 }
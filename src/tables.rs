@@ -0,0 +1,124 @@
+//! Scale and chord spellings for every key, computed at compile time
+//!
+//! The tables are indexed by key from the flattest, as given by `key_index`,
+//! and then by zero-indexed scale degree, as in `Key::scale_degree`. They are
+//! generated from the positions of the notes on the line of fifths, so a
+//! lookup gives the same spelling as the corresponding computation.
+//! ```
+//! # use tonality::{Key, Tpc};
+//! # use tonality::tables;
+//! let scale = tables::MAJOR_SCALES[tables::key_index(Key::Eb)];
+//! assert_eq!([Tpc::Eb, Tpc::F, Tpc::G, Tpc::Ab, Tpc::Bb, Tpc::C, Tpc::D], scale);
+//! // The supertonic triad in E flat major
+//! let triad = tables::MAJOR_TRIADS[tables::key_index(Key::Eb)][1];
+//! assert_eq!([Tpc::F, Tpc::Ab, Tpc::C], triad);
+//! ```
+use crate::{Key, Tpc};
+
+const NUM_OF_KEYS: usize = Key::NUM_OF as usize;
+
+/// The major scale of each key signature, from the tonic up
+pub const MAJOR_SCALES: [[Tpc; 7]; NUM_OF_KEYS] = scales([0, 2, 4, -1, 1, 3, 5]);
+
+/// The natural minor scale of each key signature, from the tonic up. The
+/// scale of C major's key signature is A minor.
+pub const MINOR_SCALES: [[Tpc; 7]; NUM_OF_KEYS] = scales([3, 5, 0, 2, 4, -1, 1]);
+
+/// The triad on each degree of the major scale of each key signature
+pub const MAJOR_TRIADS: [[[Tpc; 3]; 7]; NUM_OF_KEYS] = triads(MAJOR_SCALES);
+
+/// The triad on each degree of the natural minor scale of each key signature
+pub const MINOR_TRIADS: [[[Tpc; 3]; 7]; NUM_OF_KEYS] = triads(MINOR_SCALES);
+
+/// The index of a key in the tables, from 0 for C flat to 14 for C sharp
+/// ```
+/// # use tonality::Key;
+/// # use tonality::tables::key_index;
+/// assert_eq!(0, key_index(Key::Cb));
+/// assert_eq!(7, key_index(Key::C));
+/// ```
+#[must_use]
+#[inline]
+pub const fn key_index(key: Key) -> usize {
+    // Key::MIN is the lowest discriminant
+    #[allow(clippy::cast_sign_loss)]
+    let index = (key as i8 - Key::MIN as i8) as usize;
+    index
+}
+
+/// The scale of each key signature, given each degree's distance in fifths
+/// from the key
+const fn scales(offsets: [i8; 7]) -> [[Tpc; 7]; NUM_OF_KEYS] {
+    let mut scales = [[Tpc::C; 7]; NUM_OF_KEYS];
+    let mut key = 0;
+    while key < NUM_OF_KEYS {
+        let mut degree = 0;
+        while degree < 7 {
+            // Keys and offsets are within seven fifths of C, so the sum is
+            // within the domain of Tpc
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            let fifths = Key::MIN as i8 + key as i8 + offsets[degree];
+            #[allow(clippy::cast_sign_loss)]
+            let index = (fifths - Tpc::MIN as i8) as usize;
+            scales[key][degree] = Tpc::LINE_OF_FIFTHS[index];
+            degree += 1;
+        }
+        key += 1;
+    }
+    scales
+}
+
+/// The triads built of every other note of each scale
+const fn triads(scales: [[Tpc; 7]; NUM_OF_KEYS]) -> [[[Tpc; 3]; 7]; NUM_OF_KEYS] {
+    let mut triads = [[[Tpc::C; 3]; 7]; NUM_OF_KEYS];
+    let mut key = 0;
+    while key < NUM_OF_KEYS {
+        let mut degree = 0;
+        while degree < 7 {
+            let scale = scales[key];
+            triads[key][degree] = [
+                scale[degree],
+                scale[(degree + 2) % 7],
+                scale[(degree + 4) % 7],
+            ];
+            degree += 1;
+        }
+        key += 1;
+    }
+    triads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chord::{Chord, ChordQuality};
+    use crate::Mode;
+
+    #[test]
+    fn test_scales_match_keys() {
+        for key in Key::all() {
+            let major = MAJOR_SCALES[key_index(key)].iter();
+            let minor = MINOR_SCALES[key_index(key)].iter();
+            for (degree, (&major, &minor)) in (0..).zip(major.zip(minor)) {
+                assert_eq!(key.scale_degree(degree), major);
+                // The relative minor starts on the sixth degree
+                assert_eq!(key.scale_degree(degree + 5), minor);
+            }
+            assert_eq!(key.tonic(Mode::Minor), MINOR_SCALES[key_index(key)][0]);
+        }
+    }
+
+    #[test]
+    fn test_triad_qualities() {
+        use ChordQuality::{Diminished, Major, Minor};
+
+        let qualities = [Major, Minor, Minor, Major, Major, Minor, Diminished];
+        for key in Key::all() {
+            for (degree, &quality) in qualities.iter().enumerate() {
+                let triad = MAJOR_TRIADS[key_index(key)][degree];
+                let chord = Chord::new(triad[0], quality);
+                assert_eq!(Some(triad.to_vec()), chord.tones());
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Proptest strategies for the core types, behind the `proptest` feature
+//!
+//! Crates building on this one can property-test their own logic with these
+//! strategies.
+//! ```
+//! # use proptest::prelude::*;
+//! # use tonality::testing::{any_interval, any_tpc};
+//! proptest! {
+//!     fn transposing_keeps_the_distance(tpc in any_tpc(), interval in any_interval()) {
+//!         if let Some(transposed) = tpc + interval {
+//!             prop_assert_eq!(Ok(interval), tpc.try_interval_to(transposed));
+//!         }
+//!     }
+//! }
+//! # transposing_keeps_the_distance();
+//! ```
+use proptest::arbitrary::any;
+use proptest::strategy::{BoxedStrategy, Just, Strategy};
+
+use crate::spelling::RespellPolicy;
+use crate::{Accidental, Interval, Key, Step, Tpc};
+
+/// Any tpc, from F double flat to B double sharp
+pub fn any_tpc() -> BoxedStrategy<Tpc> {
+    any::<Tpc>()
+}
+
+/// Any key, from C flat to C sharp
+pub fn any_key() -> BoxedStrategy<Key> {
+    any::<Key>()
+}
+
+/// Any interval, from the diminished second to the augmented seventh
+pub fn any_interval() -> BoxedStrategy<Interval> {
+    any::<Interval>()
+}
+
+/// Any step, from C to B
+pub fn any_step() -> BoxedStrategy<Step> {
+    any::<Step>()
+}
+
+/// Any accidental, from double flat to double sharp
+pub fn any_accidental() -> BoxedStrategy<Accidental> {
+    any::<Accidental>()
+}
+
+/// Any respelling policy, including the key relative policies in every key
+pub fn any_policy() -> BoxedStrategy<RespellPolicy> {
+    proptest::prop_oneof![
+        Just(RespellPolicy::PreferSharps),
+        Just(RespellPolicy::PreferFlats),
+        Just(RespellPolicy::MinimizeAccidentals),
+        any_key().prop_map(RespellPolicy::KeyRelative),
+        any_key().prop_map(RespellPolicy::KeyRelativeMinor),
+    ]
+    .boxed()
+}
@@ -1,8 +1,39 @@
 //! Tonal pitch classes
+use std::fmt;
+use std::ops::RangeInclusive;
+
 use num_derive::FromPrimitive;
-use num_traits::FromPrimitive;
 
-use crate::{Accidental, Alteration, Interval, Key, Step};
+use crate::error::{from_fifths, Domain};
+use crate::line_of_fifths;
+use crate::repr::Contiguous;
+use crate::spelling::RespellPolicy;
+use crate::step::SpelledStep;
+use crate::{Accidental, Alteration, Error, Interval, Key, Step};
+
+/// The smallest distance on the line of fifths from a key to a tpc
+const MIN_OFFSET: i8 = Tpc::MIN as i8 - Key::MAX as i8;
+
+/// The number of distances on the line of fifths from a key to a tpc
+// There are 49, so the sign is never lost
+#[allow(clippy::cast_sign_loss)]
+const OFFSETS: usize = (Tpc::MAX as i8 - Key::MIN as i8 - MIN_OFFSET + 1) as usize;
+
+/// The alteration of a tpc for each distance from the key on the line of
+/// fifths, counted from `MIN_OFFSET`. The seven distances from the fourth
+/// degree up to the seventh are not altered.
+const ALTERATIONS: [Alteration; OFFSETS] = {
+    let mut table = [0; OFFSETS];
+    let mut index = 0;
+    while index < OFFSETS {
+        // Between -3 and 3, as index is less than 49
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let offset = MIN_OFFSET + index as i8;
+        table[index] = (offset + 1).div_euclid(Tpc::DELTA_SEMITONE);
+        index += 1;
+    }
+    table
+};
 
 /// Tonal pitch class
 ///
@@ -14,10 +45,21 @@ use crate::{Accidental, Alteration, Interval, Key, Step};
 /// Note that the "s" and "ss" suffixes mean sharp and double sharp. Should not
 /// be confused with the names of flat notes, which in some languages use the -s
 /// suffix.
+///
+/// Displayed with Unicode accidental glyphs, or with ASCII characters when
+/// using the alternate flag.
+/// ```
+/// # use tonality::Tpc;
+/// assert_eq!("F♯", Tpc::Fs.to_string());
+/// assert_eq!("B𝄫", Tpc::Bbb.to_string());
+/// assert_eq!("Bbb", format!("{:#}", Tpc::Bbb));
+/// assert_eq!("E", format!("{:#}", Tpc::E));
+/// ```
 #[derive(Clone, Copy, PartialOrd, Ord, Eq, Debug, PartialEq, FromPrimitive)]
 #[must_use]
 #[rustfmt::skip]
 #[allow(missing_docs)]
+#[repr(i8)]
 pub enum Tpc {
     Fbb = -15,
          Cbb, Gbb, Dbb, Abb, Ebb, Bbb,
@@ -34,11 +76,50 @@ impl Tpc {
     /// The flattest valid Tpc: F double flat
     pub const MIN: Tpc = Tpc::Fbb;
 
+    /// The most flattened a tpc can be with respect to a key, as F double
+    /// flat in C sharp major
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert_eq!(Tpc::MIN_ALTERATION, Tpc::Fbb.alteration(Key::Cs));
+    /// ```
+    pub const MIN_ALTERATION: Alteration = -3;
+
+    /// The most raised a tpc can be with respect to a key, as B double sharp
+    /// in C flat major
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert_eq!(Tpc::MAX_ALTERATION, Tpc::Bss.alteration(Key::Cb));
+    /// ```
+    pub const MAX_ALTERATION: Alteration = 3;
+
+    /// Every tpc in order along the line of fifths, from the flattest
+    #[rustfmt::skip]
+    pub(crate) const LINE_OF_FIFTHS: [Tpc; 35] = [
+        Tpc::Fbb,
+                  Tpc::Cbb, Tpc::Gbb, Tpc::Dbb, Tpc::Abb, Tpc::Ebb, Tpc::Bbb,
+        Tpc::Fb,  Tpc::Cb,  Tpc::Gb,  Tpc::Db,  Tpc::Ab,  Tpc::Eb,  Tpc::Bb,
+        Tpc::F,   Tpc::C,   Tpc::G,   Tpc::D,   Tpc::A,   Tpc::E,   Tpc::B,
+        Tpc::Fs,  Tpc::Cs,  Tpc::Gs,  Tpc::Ds,  Tpc::As,  Tpc::Es,  Tpc::Bs,
+        Tpc::Fss, Tpc::Css, Tpc::Gss, Tpc::Dss, Tpc::Ass, Tpc::Ess, Tpc::Bss,
+    ];
+
     /// Number of fifths to add to be a semitone higher
-    const DELTA_SEMITONE: i8 = 7;
+    pub(crate) const DELTA_SEMITONE: i8 = 7;
 
     /// Number of fifths to the next enharmonic spelling
-    const DELTA_ENHARMONIC: i8 = 12;
+    pub(crate) const DELTA_ENHARMONIC: i8 = 12;
+
+    /// Every tpc, in order along the line of fifths from F double flat to B
+    /// double sharp
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(35, Tpc::all().count());
+    /// assert_eq!(Some(Tpc::Cbb), Tpc::all().nth(1));
+    /// ```
+    #[must_use]
+    pub fn all() -> impl DoubleEndedIterator<Item = Tpc> {
+        line_of_fifths::between(Self::MIN, Self::MAX)
+    }
 
     /// The basic step of the Tpc, or where it is placed on the staff
     /// ```
@@ -47,20 +128,28 @@ impl Tpc {
     /// assert_eq!(Step::C, Tpc::Cs.step());
     /// assert_eq!(Tpc::Gb.step(), Tpc::Gs.step());
     /// ```
+    #[inline]
     pub fn step(self) -> Step {
-        match (self as i8).rem_euclid(7) {
-            0 => Step::C,
-            1 => Step::G,
-            2 => Step::D,
-            3 => Step::A,
-            4 => Step::E,
-            5 => Step::B,
-            _ => Step::F,
-        }
+        #[rustfmt::skip]
+        const STEPS: [Step; 35] = [
+            Step::F,
+            Step::C, Step::G, Step::D, Step::A, Step::E, Step::B, Step::F,
+            Step::C, Step::G, Step::D, Step::A, Step::E, Step::B, Step::F,
+            Step::C, Step::G, Step::D, Step::A, Step::E, Step::B, Step::F,
+            Step::C, Step::G, Step::D, Step::A, Step::E, Step::B, Step::F,
+            Step::C, Step::G, Step::D, Step::A, Step::E, Step::B,
+        ];
+        STEPS[self.index()]
     }
 
     /// The number of semitones by which the tpc is altered with respect to the key
     ///
+    /// The result is between `MIN_ALTERATION` and `MAX_ALTERATION`, -3 and
+    /// 3. The extremes are beyond what an accidental can write: F double flat
+    /// is three semitones flat in C sharp major, where F is sharp, and B double
+    /// sharp is three semitones sharp in C flat major. Use `try_alteration` or
+    /// `bounded_alteration` to stay within double sharps and flats.
+    ///
     /// # Example
     ///
     /// ```
@@ -73,29 +162,103 @@ impl Tpc {
     /// assert_eq!(2, Tpc::As.alteration(Key::Db));
     /// ```
     #[must_use]
+    #[inline]
     pub fn alteration(self, key: Key) -> Alteration {
-        let tpc = self as i8;
-        let key = key as i8;
-        (tpc - key - Self::MIN as i8 + Key::MAX as i8) / Self::DELTA_SEMITONE - 3
+        // The distance is never below MIN_OFFSET
+        #[allow(clippy::cast_sign_loss)]
+        let index = (self as i8 - key as i8 - MIN_OFFSET) as usize;
+        ALTERATIONS[index]
     }
 
-    /// The accidental for the Tpc
+    /// The alteration of the tpc in the key, as `alteration`, if it is at most
+    /// two semitones either way
     ///
-    /// Private because you rarely want an accidental without the context of a key.
-    fn accidental(self) -> Accidental {
-        match (self as i8 + 1).div_euclid(7) {
-            -2 => Accidental::DblFlat,
-            -1 => Accidental::Flat,
-            0 => Accidental::Natural,
-            1 => Accidental::Sharp,
-            2 => Accidental::DblSharp,
-            _ => unreachable!("Tpc out of range"),
+    /// # Errors
+    ///
+    /// Returns an error in `Domain::Accidental` with the alteration, if the tpc
+    /// is altered by three semitones.
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert_eq!(Ok(-2), Tpc::Fb.try_alteration(Key::Cs));
+    /// assert!(Tpc::Fbb.try_alteration(Key::Cs).unwrap_err().is_flatwards());
+    /// assert!(Tpc::Bss.try_alteration(Key::Cb).unwrap_err().is_sharpwards());
+    /// ```
+    pub fn try_alteration(self, key: Key) -> Result<Alteration, Error> {
+        let alteration = self.alteration(key);
+        if (Accidental::DblFlat as i8..=Accidental::DblSharp as i8).contains(&alteration) {
+            Ok(alteration)
+        } else {
+            Err(Error::OutOfRange {
+                value: alteration,
+                domain: Domain::Accidental,
+            })
+        }
+    }
+
+    /// The tpc and its alteration in the key, respelled on a neighboring step
+    /// if it is altered by three semitones. The respelling is enharmonic, and
+    /// its alteration is always at most two semitones either way.
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert_eq!((Tpc::Eb, -2), Tpc::Fbb.bounded_alteration(Key::Cs));
+    /// assert_eq!((Tpc::Cs, 2), Tpc::Bss.bounded_alteration(Key::Cb));
+    /// assert_eq!((Tpc::Fb, -2), Tpc::Fb.bounded_alteration(Key::Cs));
+    /// ```
+    pub fn bounded_alteration(self, key: Key) -> (Tpc, Alteration) {
+        match self.try_alteration(key) {
+            Ok(alteration) => (self, alteration),
+            Err(error) => {
+                // Twelve fifths towards the key never leaves the domain
+                let delta = if error.is_sharpwards() {
+                    -Self::DELTA_ENHARMONIC
+                } else {
+                    Self::DELTA_ENHARMONIC
+                };
+                let tpc = Tpc::in_range(self as i8 + delta);
+                (tpc, tpc.alteration(key))
+            }
         }
     }
 
+    /// The accidental for the Tpc
+    ///
+    /// Crate-private because you rarely want an accidental without the context
+    /// of a key.
+    #[inline]
+    pub(crate) fn accidental(self) -> Accidental {
+        // Seven tpcs in a row on the line of fifths share an accidental
+        const ACCIDENTALS: [Accidental; 5] = [
+            Accidental::DblFlat,
+            Accidental::Flat,
+            Accidental::Natural,
+            Accidental::Sharp,
+            Accidental::DblSharp,
+        ];
+        ACCIDENTALS[self.index() / 7]
+    }
+
+    /// The position on the line of fifths counted from the flattest tpc,
+    /// between 0 and 34
+    #[inline]
+    pub(crate) fn index(self) -> usize {
+        // Tpc::MIN is the lowest discriminant
+        #[allow(clippy::cast_sign_loss)]
+        let index = (self as i8 - Self::MIN as i8) as usize;
+        index
+    }
+
+    /// The tpc at a position on the line of fifths counted from the flattest,
+    /// as in `index`
+    #[inline]
+    pub(crate) fn from_index(index: usize) -> Option<Tpc> {
+        Self::LINE_OF_FIFTHS.get(index).copied()
+    }
+
     /// Find the appropriate accidental for the Tpc in a key.
     ///
-    /// If no key is given, default to C major with no fixed accidentals
+    /// If no key is given, default to C major with no fixed accidentals. To
+    /// account for accidentals earlier in the measure, use
+    /// `measure::AccidentalState`. The inverse is `Step::resolve`.
     ///
     /// # Example
     ///
@@ -112,16 +275,34 @@ impl Tpc {
     /// let key: Option<Key> = None;
     /// assert_eq!((Step::F, Some(Accidental::DblSharp)), tpc.altered_step(key));
     /// ```
+    ///
+    /// `spelled_step` gives the same result in a struct with named fields.
+    #[inline]
     pub fn altered_step(self, key: Option<Key>) -> (Step, Option<Accidental>) {
         let key = key.unwrap_or_default();
         let step = self.step();
-        if step.with_key(key) == self {
+        if self.alteration(key) == 0 {
             (step, None)
         } else {
             (step, Some(self.accidental()))
         }
     }
 
+    /// The step of the tpc in a key, and the accidental to write if the key
+    /// signature does not give the note. The same as `altered_step`, with
+    /// named fields.
+    /// ```
+    /// # use tonality::{Accidental, Key, Tpc};
+    /// let spelled = Tpc::C.spelled_step(Some(Key::A));
+    /// assert_eq!(Some(Accidental::Natural), spelled.accidental);
+    /// assert!(!spelled.covered_by_key);
+    /// assert!(Tpc::C.spelled_step(None).covered_by_key);
+    /// ```
+    #[inline]
+    pub fn spelled_step(self, key: Option<Key>) -> SpelledStep {
+        self.altered_step(key).into()
+    }
+
     /// Adjust alteration while maintaining the step value
     ///
     /// Returns None if the alteration would be sharper than double sharp or
@@ -134,8 +315,244 @@ impl Tpc {
     /// ```
     #[must_use]
     pub fn alter(self, by: Alteration) -> Option<Tpc> {
-        let new = self as i8 + by * Self::DELTA_SEMITONE;
-        num_traits::FromPrimitive::from_i8(new)
+        self.try_alter(by).ok()
+    }
+
+    /// Adjust alteration while maintaining the step value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error telling the direction in which the result would be out
+    /// of range, if it would be beyond double sharp or double flat.
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(Ok(Tpc::Gs), Tpc::G.try_alter(1));
+    /// assert!(Tpc::Bbb.try_alter(-1).unwrap_err().is_flatwards());
+    /// ```
+    pub fn try_alter(self, by: Alteration) -> Result<Tpc, Error> {
+        let new = by
+            .saturating_mul(Self::DELTA_SEMITONE)
+            .saturating_add(self as i8);
+        from_fifths(new, Domain::Tpc)
+    }
+
+    /// Adjust alteration while maintaining the step value, stopping at double
+    /// sharp or double flat instead of failing
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(Tpc::Gs, Tpc::G.clamp_alteration(1));
+    /// assert_eq!(Tpc::Gss, Tpc::G.clamp_alteration(5));
+    /// assert_eq!(Tpc::Gbb, Tpc::Gs.clamp_alteration(i8::MIN));
+    /// ```
+    pub fn clamp_alteration(self, by: Alteration) -> Tpc {
+        let headroom = self.headroom();
+        let by = by.clamp(*headroom.start(), *headroom.end());
+        self.alter(by).unwrap_or(self)
+    }
+
+    /// The alterations that keep the tpc within double sharp and double flat
+    /// on the same step
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(-3..=1, Tpc::Fs.headroom());
+    /// assert!(Tpc::Bbb.headroom().contains(&4));
+    /// ```
+    #[must_use]
+    pub fn headroom(self) -> RangeInclusive<Alteration> {
+        let accidental = self.accidental() as i8;
+        let (flattest, sharpest) = (Accidental::DblFlat as i8, Accidental::DblSharp as i8);
+        flattest - accidental..=sharpest - accidental
+    }
+
+    /// Raise the tpc by a semitone on the same step
+    ///
+    /// # Errors
+    ///
+    /// Returns a sharpwards error if the tpc is already double sharp.
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(Ok(Tpc::Fss), Tpc::F.sharpen().and_then(Tpc::sharpen));
+    /// assert!(Tpc::Fss.sharpen().unwrap_err().is_sharpwards());
+    /// ```
+    pub fn sharpen(self) -> Result<Tpc, Error> {
+        self.try_alter(1)
+    }
+
+    /// Lower the tpc by a semitone on the same step
+    ///
+    /// # Errors
+    ///
+    /// Returns a flatwards error if the tpc is already double flat.
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(Ok(Tpc::Bb), Tpc::Bs.flatten().and_then(Tpc::flatten));
+    /// assert!(Tpc::Bbb.flatten().unwrap_err().is_flatwards());
+    /// ```
+    pub fn flatten(self) -> Result<Tpc, Error> {
+        self.try_alter(-1)
+    }
+
+    /// The unaltered tpc on the same step
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(Tpc::G, Tpc::Gbb.natural());
+    /// assert_eq!(Tpc::B, Tpc::Bs.natural());
+    /// ```
+    #[inline]
+    pub fn natural(self) -> Tpc {
+        self.step().with_accidental(Accidental::Natural)
+    }
+
+    /// Transpose the tpc up by an interval
+    ///
+    /// # Errors
+    ///
+    /// Returns an error telling the direction in which the result would be out
+    /// of range, if it would be beyond double sharp or double flat.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// assert_eq!(Ok(Tpc::E), Tpc::C.try_add(Interval::Maj3));
+    /// assert!(Tpc::Dss.try_add(Interval::Maj3).unwrap_err().is_sharpwards());
+    /// ```
+    pub fn try_add(self, interval: Interval) -> Result<Tpc, Error> {
+        from_fifths(self as i8 + interval as i8, Domain::Tpc)
+    }
+
+    /// Transpose the tpc down by an interval
+    ///
+    /// # Errors
+    ///
+    /// Returns an error telling the direction in which the result would be out
+    /// of range, if it would be beyond double sharp or double flat.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// assert_eq!(Ok(Tpc::A), Tpc::C.try_sub(Interval::Min3));
+    /// assert!(Tpc::Fbb.try_sub(Interval::P5).unwrap_err().is_flatwards());
+    /// ```
+    pub fn try_sub(self, interval: Interval) -> Result<Tpc, Error> {
+        from_fifths(self as i8 - interval as i8, Domain::Tpc)
+    }
+
+    /// The interval from this tpc up to another
+    ///
+    /// # Errors
+    ///
+    /// Returns an error telling the direction in which the interval would be
+    /// out of range, if it would be more than augmented or diminished.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// assert_eq!(Ok(Interval::P4), Tpc::C.try_interval_to(Tpc::F));
+    /// assert!(Tpc::Fb.try_interval_to(Tpc::Bs).unwrap_err().is_sharpwards());
+    /// ```
+    pub fn try_interval_to(self, other: Tpc) -> Result<Interval, Error> {
+        from_fifths(other as i8 - self as i8, Domain::Interval)
+    }
+
+    /// Transpose the tpc up by an interval in place
+    ///
+    /// # Errors
+    ///
+    /// Leaves the tpc unchanged and returns an error telling the direction in
+    /// which the result would be out of range, if it would be beyond double
+    /// sharp or double flat.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// let mut tpc = Tpc::D;
+    /// tpc.try_add_assign(Interval::Min3).unwrap();
+    /// assert_eq!(Tpc::F, tpc);
+    /// ```
+    pub fn try_add_assign(&mut self, interval: Interval) -> Result<(), Error> {
+        *self = self.try_add(interval)?;
+        Ok(())
+    }
+
+    /// Transpose the tpc down by an interval in place
+    ///
+    /// # Errors
+    ///
+    /// Leaves the tpc unchanged and returns an error telling the direction in
+    /// which the result would be out of range, if it would be beyond double
+    /// sharp or double flat.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// let mut tpc = Tpc::Fbb;
+    /// assert!(tpc.try_sub_assign(Interval::P5).is_err());
+    /// assert_eq!(Tpc::Fbb, tpc);
+    /// ```
+    pub fn try_sub_assign(&mut self, interval: Interval) -> Result<(), Error> {
+        *self = self.try_sub(interval)?;
+        Ok(())
+    }
+
+    /// Adjust alteration in place while maintaining the step value
+    ///
+    /// # Errors
+    ///
+    /// Leaves the tpc unchanged and returns an error telling the direction in
+    /// which the result would be out of range, if it would be beyond double
+    /// sharp or double flat.
+    /// ```
+    /// # use tonality::Tpc;
+    /// let mut tpc = Tpc::Eb;
+    /// tpc.try_alter_assign(2).unwrap();
+    /// assert_eq!(Tpc::Es, tpc);
+    /// ```
+    pub fn try_alter_assign(&mut self, by: Alteration) -> Result<(), Error> {
+        *self = self.try_alter(by)?;
+        Ok(())
+    }
+
+    /// Move the tpc a number of steps up or down the scale of the key.
+    ///
+    /// A chromatic note keeps its alteration with respect to the key, so a
+    /// raised fourth degree moved up a step becomes a raised fifth degree. In
+    /// the rare case where this would be beyond double sharp or double flat,
+    /// the result is respelled to fit the key instead.
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// // Up a third in C major
+    /// assert_eq!(Tpc::E, Tpc::C.transpose_diatonic(2, Key::C));
+    /// assert_eq!(Tpc::Bb, Tpc::D.transpose_diatonic(-2, Key::Eb));
+    /// // F sharp is a raised fourth in C major, and G sharp a raised fifth
+    /// assert_eq!(Tpc::Gs, Tpc::Fs.transpose_diatonic(1, Key::C));
+    /// ```
+    pub fn transpose_diatonic(self, steps: i8, key: Key) -> Tpc {
+        let alteration = self.alteration(key);
+        let natural = (self.step() + isize::from(steps)).with_key(key);
+        let value = natural as i8 + alteration * Self::DELTA_SEMITONE;
+        from_fifths(value, Domain::Tpc)
+            .unwrap_or_else(|_| RespellPolicy::KeyRelative(key).spell(value))
+    }
+
+    /// Whether the tpc is in the scale of the key, unaltered
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert!(Tpc::Bb.is_diatonic_in(Key::F));
+    /// assert!(!Tpc::B.is_diatonic_in(Key::F));
+    /// ```
+    #[must_use]
+    pub fn is_diatonic_in(self, key: Key) -> bool {
+        self.alteration(key) == 0
+    }
+
+    /// How the tpc relates to the scale of the key: which zero-indexed scale
+    /// degree it is on, and whether that degree is raised or lowered
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// # use tonality::tpc::ChromaticClass;
+    /// assert_eq!(ChromaticClass::Raised { degree: 3 }, Tpc::Fs.chromatic_function_in(Key::C));
+    /// assert_eq!(ChromaticClass::Lowered { degree: 6 }, Tpc::Ab.chromatic_function_in(Key::Bb));
+    /// // A raised fourth leads to the dominant
+    /// assert!(Tpc::Fs.chromatic_function_in(Key::C).is_secondary_leading_tone());
+    /// ```
+    pub fn chromatic_function_in(self, key: Key) -> ChromaticClass {
+        let degree = key.root_step().diatonic_distance(self.step());
+        match self.alteration(key) {
+            0 => ChromaticClass::Diatonic { degree },
+            1 => ChromaticClass::Raised { degree },
+            -1 => ChromaticClass::Lowered { degree },
+            alteration => ChromaticClass::Altered { degree, alteration },
+        }
     }
 
     /// Whether the two tpcs are enharmonic, i.e. represent the same pitch
@@ -154,14 +571,215 @@ impl Tpc {
     pub fn enharmonic(self, other: Tpc) -> bool {
         (self as i8 - other as i8) % Self::DELTA_ENHARMONIC == 0
     }
+
+    /// The number of semitones from C up to the tpc, between 0 and 11
+    /// ```
+    /// # use tonality::Tpc;
+    /// assert_eq!(0, Tpc::C.pitch_class());
+    /// assert_eq!(8, Tpc::Ab.pitch_class());
+    /// assert_eq!(0, Tpc::Bs.pitch_class());
+    /// assert_eq!(1, Tpc::Bss.pitch_class());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn pitch_class(self) -> u8 {
+        // Seven semitones in a fifth, and the result is between 0 and 11
+        #[allow(clippy::cast_sign_loss)]
+        let pitch_class = ((self as i8).rem_euclid(12) * 7 % 12) as u8;
+        pitch_class
+    }
+
+    /// Spell a pitch class, given as semitones above C, according to a policy.
+    /// Pitch classes above 11 are reduced by octaves.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::spelling::RespellPolicy;
+    /// assert_eq!(Tpc::Ab, Tpc::from_pitch_class(8, RespellPolicy::PreferFlats));
+    /// assert_eq!(Tpc::Gs, Tpc::from_pitch_class(20, RespellPolicy::PreferSharps));
+    /// ```
+    #[inline]
+    pub fn from_pitch_class(pitch_class: u8, policy: RespellPolicy) -> Tpc {
+        // A semitone is seven fifths up, modulo enharmonic equivalence
+        #[allow(clippy::cast_possible_wrap)]
+        let fifths = (pitch_class % 12) as i8 * 7;
+        policy.spell(fifths)
+    }
+
+    /// The enharmonic spelling of the tpc chosen by the policy
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// # use tonality::spelling::RespellPolicy;
+    /// let policy = RespellPolicy::KeyRelative(Key::F);
+    /// assert_eq!(Tpc::Bb, Tpc::As.respell(policy));
+    /// assert_eq!(Tpc::F, Tpc::Es.respell(policy));
+    /// ```
+    #[inline]
+    pub fn respell(self, policy: RespellPolicy) -> Tpc {
+        policy.spell(self as i8)
+    }
+
+    /// The enharmonic spelling of the tpc that fits the key best: the one
+    /// nearest to the key's root on the line of fifths.
+    ///
+    /// Diatonic notes are spelled as in the scale. Chromatic notes become the
+    /// lowered second, third, sixth or seventh degree or the raised fourth
+    /// degree. The note a tritone from the root is as near either way, and is
+    /// spelled as the raised fourth. This is the same spelling as
+    /// `RespellPolicy::KeyRelative`.
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// assert_eq!(Tpc::Bb, Tpc::As.respell_in_key(Key::F));
+    /// assert_eq!(Tpc::Fs, Tpc::Gb.respell_in_key(Key::C));
+    /// assert_eq!(Tpc::Ab, Tpc::Gs.respell_in_key(Key::C));
+    /// assert_eq!(Tpc::Es, Tpc::F.respell_in_key(Key::Cs));
+    /// ```
+    #[inline]
+    pub fn respell_in_key(self, key: Key) -> Tpc {
+        self.respell(RespellPolicy::KeyRelative(key))
+    }
+
+    /// Transpose the tpc up by an interval. If the result would be outside the
+    /// domain of `Tpc`, it is respelled according to the policy instead.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// # use tonality::spelling::RespellPolicy;
+    /// let policy = RespellPolicy::PreferSharps;
+    /// assert_eq!(Tpc::Es, Tpc::Ds.wrapping_add(Interval::Maj2, policy));
+    /// // F triple sharp is out of range
+    /// assert_eq!(Tpc::Gs, Tpc::Ess.wrapping_add(Interval::Maj2, policy));
+    /// ```
+    pub fn wrapping_add(self, interval: Interval, policy: RespellPolicy) -> Tpc {
+        self.try_add(interval)
+            .unwrap_or_else(|_| policy.spell(self as i8 + interval as i8))
+    }
+
+    /// Transpose the tpc down by an interval. If the result would be outside
+    /// the domain of `Tpc`, it is respelled according to the policy instead.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// # use tonality::spelling::RespellPolicy;
+    /// let policy = RespellPolicy::PreferFlats;
+    /// // E triple flat is out of range
+    /// assert_eq!(Tpc::Db, Tpc::Fbb.wrapping_sub(Interval::Maj2, policy));
+    /// ```
+    pub fn wrapping_sub(self, interval: Interval, policy: RespellPolicy) -> Tpc {
+        self.try_sub(interval)
+            .unwrap_or_else(|_| policy.spell(self as i8 - interval as i8))
+    }
+
+    /// The endless cycle of tpcs starting with this one, each an interval
+    /// above the previous. Once the cycle leaves the domain of `Tpc`, every
+    /// following item is None.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// let cycle: Vec<_> = Tpc::Gs.cycle(Interval::Maj3).take(4).collect();
+    /// assert_eq!(vec![Some(Tpc::Gs), Some(Tpc::Bs), Some(Tpc::Dss), None], cycle);
+    /// ```
+    pub fn cycle(self, interval: Interval) -> impl Iterator<Item = Option<Tpc>> {
+        std::iter::successors(Some(Some(self)), move |&tpc| {
+            Some(tpc.and_then(|tpc| tpc + interval))
+        })
+    }
+
+    /// The endless cycle of tpcs starting with this one, each an interval
+    /// above the previous. Where the next tpc would be outside the domain of
+    /// `Tpc`, it is respelled according to the policy, and the cycle continues
+    /// from there.
+    /// ```
+    /// # use tonality::{Interval, Tpc};
+    /// # use tonality::spelling::RespellPolicy;
+    /// let policy = RespellPolicy::MinimizeAccidentals;
+    /// let cycle: Vec<_> = Tpc::Gs.wrapping_cycle(Interval::Maj3, policy).take(5).collect();
+    /// // F triple sharp is out of range, and is respelled as A flat
+    /// assert_eq!(vec![Tpc::Gs, Tpc::Bs, Tpc::Dss, Tpc::Ab, Tpc::C], cycle);
+    /// ```
+    pub fn wrapping_cycle(
+        self,
+        interval: Interval,
+        policy: RespellPolicy,
+    ) -> impl Iterator<Item = Tpc> {
+        std::iter::successors(Some(self), move |&tpc| {
+            Some(tpc.wrapping_add(interval, policy))
+        })
+    }
+}
+
+/// The relation of a tpc to the scale of a key, as given by
+/// `Tpc::chromatic_function_in`. Degrees are zero-indexed, as in
+/// `Key::scale_degree`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub enum ChromaticClass {
+    /// A note of the scale
+    Diatonic {
+        /// The scale degree
+        degree: i8,
+    },
+    /// A scale degree raised by a semitone, such as F sharp in C major
+    Raised {
+        /// The scale degree
+        degree: i8,
+    },
+    /// A scale degree lowered by a semitone, such as B flat in C major
+    Lowered {
+        /// The scale degree
+        degree: i8,
+    },
+    /// A scale degree altered by more than a semitone, such as F double sharp
+    /// in C major
+    Altered {
+        /// The scale degree
+        degree: i8,
+        /// The alteration, as in `Tpc::alteration`
+        alteration: Alteration,
+    },
+}
+
+impl ChromaticClass {
+    /// The scale degree the tpc is on
+    #[must_use]
+    pub fn degree(self) -> i8 {
+        match self {
+            ChromaticClass::Diatonic { degree }
+            | ChromaticClass::Raised { degree }
+            | ChromaticClass::Lowered { degree }
+            | ChromaticClass::Altered { degree, .. } => degree,
+        }
+    }
+
+    /// Whether the tpc is a raised degree a diatonic semitone below the next
+    /// degree of the scale, which it leads to. Raising the third or the
+    /// seventh degree leads to a chromatic note instead.
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// // C sharp leads to D in C major, but E sharp leads to F sharp
+    /// assert!(Tpc::Cs.chromatic_function_in(Key::C).is_secondary_leading_tone());
+    /// assert!(!Tpc::Es.chromatic_function_in(Key::C).is_secondary_leading_tone());
+    /// ```
+    #[must_use]
+    pub fn is_secondary_leading_tone(self) -> bool {
+        match self {
+            ChromaticClass::Raised { degree } => degree != 2 && degree != 6,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Tpc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.step())?;
+        match self.accidental() {
+            Accidental::Natural => Ok(()),
+            accidental => f.write_str(accidental.symbol(f.alternate())),
+        }
+    }
 }
 
 impl std::ops::Add<Interval> for Tpc {
     type Output = Option<Tpc>;
 
     fn add(self, rhs: Interval) -> Self::Output {
-        let value = self as i8 + rhs as i8;
-        FromPrimitive::from_i8(value)
+        self.try_add(rhs).ok()
     }
 }
 
@@ -169,8 +787,30 @@ impl std::ops::Sub<Interval> for Tpc {
     type Output = Option<Tpc>;
 
     fn sub(self, rhs: Interval) -> Self::Output {
-        let value = self as i8 - rhs as i8;
-        FromPrimitive::from_i8(value)
+        self.try_sub(rhs).ok()
+    }
+}
+
+/// Adding an alteration is the same as `Tpc::alter`
+/// ```
+/// # use tonality::Tpc;
+/// assert_eq!(Some(Tpc::Fs), Tpc::F + 1);
+/// assert_eq!(Some(Tpc::Bbb), Tpc::B - 2);
+/// assert_eq!(None, Tpc::Bb - 2);
+/// ```
+impl std::ops::Add<Alteration> for Tpc {
+    type Output = Option<Tpc>;
+
+    fn add(self, rhs: Alteration) -> Self::Output {
+        self.alter(rhs)
+    }
+}
+
+impl std::ops::Sub<Alteration> for Tpc {
+    type Output = Option<Tpc>;
+
+    fn sub(self, rhs: Alteration) -> Self::Output {
+        self.alter(rhs.checked_neg()?)
     }
 }
 
@@ -178,13 +818,40 @@ impl std::ops::Sub<Tpc> for Tpc {
     type Output = Option<Interval>;
 
     fn sub(self, rhs: Tpc) -> Self::Output {
-        FromPrimitive::from_i8(rhs as i8 - self as i8)
+        self.try_interval_to(rhs).ok()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_bounded_alteration() {
+        for key in Key::all() {
+            for tpc in Tpc::all() {
+                let (respelled, alteration) = tpc.bounded_alteration(key);
+                assert!(respelled.enharmonic(tpc));
+                assert!((-2..=2).contains(&alteration));
+                assert_eq!(tpc.try_alteration(key).is_ok(), tpc == respelled);
+            }
+        }
+    }
+
+    #[test]
+    fn test_respell_in_key_is_nearest_to_root() {
+        for key in Key::all() {
+            for tpc in Tpc::all() {
+                let respelled = tpc.respell_in_key(key);
+                assert!(respelled.enharmonic(tpc));
+                assert!((-5..=6).contains(&(respelled as i8 - key as i8)));
+                if tpc.is_diatonic_in(key) {
+                    assert_eq!(tpc, respelled);
+                }
+            }
+        }
+    }
 
     #[test]
     fn test_enharmonic_transpose() {
@@ -194,6 +861,79 @@ mod tests {
         assert_eq!(enharmonic, Tpc::Bs);
     }
 
+    #[test]
+    fn test_pitch_class_of_every_tpc() {
+        for &tpc in &Tpc::LINE_OF_FIFTHS {
+            let semitones = tpc.step().semitones() + tpc.alteration(Key::C);
+            let expected = u8::try_from(semitones.rem_euclid(12)).unwrap();
+            assert_eq!(expected, tpc.pitch_class(), "{tpc:?}");
+        }
+    }
+
+    #[test]
+    fn test_index_roundtrip() {
+        for tpc in Tpc::all() {
+            assert_eq!(Some(tpc), Tpc::from_index(tpc.index()));
+            assert_eq!(tpc, tpc.step().with_accidental(tpc.accidental()));
+        }
+        assert_eq!(None, Tpc::from_index(35));
+    }
+
+    #[test]
+    fn test_named_alterations() {
+        for tpc in Tpc::all() {
+            assert_eq!(Accidental::Natural, tpc.natural().accidental());
+            assert_eq!(tpc.step(), tpc.natural().step());
+            if let Ok(sharp) = tpc.sharpen() {
+                assert_eq!(Ok(tpc), sharp.flatten());
+            }
+        }
+    }
+
+    #[test]
+    fn test_chromatic_functions() {
+        for key in Key::all() {
+            for degree in 0..7 {
+                let tpc = key.scale_degree(degree as isize);
+                assert!(tpc.is_diatonic_in(key));
+                assert_eq!(
+                    ChromaticClass::Diatonic { degree },
+                    tpc.chromatic_function_in(key)
+                );
+                if let Ok(raised) = tpc.sharpen() {
+                    let class = raised.chromatic_function_in(key);
+                    assert_eq!(ChromaticClass::Raised { degree }, class);
+                    // A secondary leading tone is a semitone below a diatonic note
+                    let above = key.scale_degree(degree as isize + 1);
+                    let leads = raised.try_interval_to(above) == Ok(Interval::Min2);
+                    assert_eq!(leads, class.is_secondary_leading_tone());
+                }
+            }
+        }
+        let class = Tpc::Fss.chromatic_function_in(Key::C);
+        assert_eq!(
+            ChromaticClass::Altered {
+                degree: 3,
+                alteration: 2
+            },
+            class
+        );
+        assert_eq!(3, class.degree());
+    }
+
+    #[test]
+    fn test_headroom_matches_alter() {
+        for tpc in Tpc::all() {
+            for by in -5..=5 {
+                assert_eq!(tpc.headroom().contains(&by), tpc.alter(by).is_some());
+            }
+            for key in Key::all() {
+                let alteration = tpc.alteration(key);
+                assert!((Tpc::MIN_ALTERATION..=Tpc::MAX_ALTERATION).contains(&alteration));
+            }
+        }
+    }
+
     #[test]
     fn test_to_alter_with_key() {
         // A in C Maj: No alteration
@@ -233,4 +973,20 @@ mod tests {
         // A major 3rd above D## would be F### - out of range
         assert_eq!(None, Tpc::Dss + Interval::Maj3);
     }
+
+    #[test]
+    fn transpose_diatonic_out_of_range() {
+        // G double sharp is the doubly raised first degree of G major. Down a
+        // step, it would be F triple sharp.
+        assert_eq!(Tpc::Ab, Tpc::Gss.transpose_diatonic(-1, Key::G));
+        assert_eq!(Tpc::Fbb, Tpc::Fbb.transpose_diatonic(0, Key::Cs));
+    }
+
+    #[test]
+    fn sub_alteration() {
+        assert_eq!(Some(Tpc::C), Tpc::Cs - 1);
+        assert_eq!(None, Tpc::C - Tpc::MIN_ALTERATION);
+        // Negating the smallest i8 would overflow
+        assert_eq!(None, Tpc::C - i8::MIN);
+    }
 }
@@ -0,0 +1,270 @@
+//! Composable transformations of notes
+//!
+//! A `NoteTransform` maps each tpc of a sequence to another, or to None if it
+//! can't. Transformations are chained with `then` and restricted with `when`
+//! or `outside_key`, and `apply` runs the pipeline lazily over an iterator,
+//! without collecting the notes between the steps.
+//! ```
+//! # use tonality::{Interval, Key, Tpc};
+//! # use tonality::transform::{NoteTransform, RespellInKey, Transpose};
+//! // Up a major third to B major, then spelled as in B major
+//! let pipeline = Transpose(Interval::Maj3).then(RespellInKey(Key::B));
+//! let melody = [Tpc::G, Tpc::Bb, Tpc::D];
+//! let result: Vec<Option<Tpc>> = pipeline.apply(melody.iter().copied()).collect();
+//! assert_eq!(vec![Some(Tpc::B), Some(Tpc::D), Some(Tpc::Fs)], result);
+//! ```
+use crate::{Interval, Key, Tpc};
+
+/// A transformation of notes, one at a time
+///
+/// `transform` takes `&mut self`, so a transformation may keep state from one
+/// note to the next. Closures taking a tpc and returning an `Option<Tpc>` are
+/// transformations.
+pub trait NoteTransform {
+    /// The transformed note, or None if the note can't be transformed
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc>;
+
+    /// This transformation followed by another. A note which this
+    /// transformation turns to None is not passed on.
+    fn then<T>(self, next: T) -> Chain<Self, T>
+    where
+        Self: Sized,
+        T: NoteTransform,
+    {
+        Chain {
+            first: self,
+            second: next,
+        }
+    }
+
+    /// This transformation applied only to the notes for which the predicate
+    /// holds. Other notes are left as they are.
+    fn when<P>(self, predicate: P) -> When<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(Tpc) -> bool,
+    {
+        When {
+            transform: self,
+            predicate,
+        }
+    }
+
+    /// This transformation applied only to the notes which are altered in
+    /// the key, leaving the notes of its scale as they are
+    /// ```
+    /// # use tonality::{Key, Tpc};
+    /// # use tonality::transform::{NoteTransform, RespellInKey};
+    /// let mut flats = RespellInKey(Key::F).outside_key(Key::C);
+    /// assert_eq!(Some(Tpc::Bb), flats.transform(Tpc::As));
+    /// assert_eq!(Some(Tpc::B), flats.transform(Tpc::B));
+    /// ```
+    fn outside_key(self, key: Key) -> OutsideKey<Self>
+    where
+        Self: Sized,
+    {
+        OutsideKey {
+            transform: self,
+            key,
+        }
+    }
+
+    /// Transform each note of a sequence lazily
+    fn apply<I>(self, notes: I) -> Apply<I::IntoIter, Self>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = Tpc>,
+    {
+        Apply {
+            notes: notes.into_iter(),
+            transform: self,
+        }
+    }
+}
+
+impl<F> NoteTransform for F
+where
+    F: FnMut(Tpc) -> Option<Tpc>,
+{
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc> {
+        self(tpc)
+    }
+}
+
+/// Transposes each note by an interval, keeping its spelling
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transpose(pub Interval);
+
+impl NoteTransform for Transpose {
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc> {
+        tpc + self.0
+    }
+}
+
+/// Respells each note as `Tpc::respell_in_key`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RespellInKey(pub Key);
+
+impl NoteTransform for RespellInKey {
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc> {
+        Some(tpc.respell_in_key(self.0))
+    }
+}
+
+/// Respells each note enharmonically into a range on the line of fifths,
+/// such as to avoid double accidentals. Gives None for a note with no
+/// spelling in the range.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::transform::{Clamp, NoteTransform};
+/// let mut single = Clamp { flattest: Tpc::Fb, sharpest: Tpc::Bs };
+/// assert_eq!(Some(Tpc::A), single.transform(Tpc::Bbb));
+/// assert_eq!(Some(Tpc::Eb), single.transform(Tpc::Eb));
+/// let mut narrow = Clamp { flattest: Tpc::C, sharpest: Tpc::A };
+/// assert_eq!(None, narrow.transform(Tpc::Fs));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Clamp {
+    /// The flattest spelling allowed
+    pub flattest: Tpc,
+    /// The sharpest spelling allowed
+    pub sharpest: Tpc,
+}
+
+impl NoteTransform for Clamp {
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc> {
+        let delta = Tpc::DELTA_ENHARMONIC;
+        let offset = (tpc as i8 - self.flattest as i8).rem_euclid(delta);
+        Some(self.flattest as i8 + offset)
+            .filter(|&fifths| fifths <= self.sharpest as i8)
+            .and_then(num_traits::FromPrimitive::from_i8)
+    }
+}
+
+/// Two transformations, one after the other. Made by `NoteTransform::then`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> NoteTransform for Chain<A, B>
+where
+    A: NoteTransform,
+    B: NoteTransform,
+{
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc> {
+        self.first
+            .transform(tpc)
+            .and_then(|tpc| self.second.transform(tpc))
+    }
+}
+
+/// A transformation of the notes for which a predicate holds. Made by
+/// `NoteTransform::when`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct When<T, P> {
+    transform: T,
+    predicate: P,
+}
+
+impl<T, P> NoteTransform for When<T, P>
+where
+    T: NoteTransform,
+    P: FnMut(Tpc) -> bool,
+{
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc> {
+        if (self.predicate)(tpc) {
+            self.transform.transform(tpc)
+        } else {
+            Some(tpc)
+        }
+    }
+}
+
+/// A transformation of the notes which are altered in a key. Made by
+/// `NoteTransform::outside_key`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutsideKey<T> {
+    transform: T,
+    key: Key,
+}
+
+impl<T> NoteTransform for OutsideKey<T>
+where
+    T: NoteTransform,
+{
+    fn transform(&mut self, tpc: Tpc) -> Option<Tpc> {
+        if tpc.alteration(self.key) == 0 {
+            Some(tpc)
+        } else {
+            self.transform.transform(tpc)
+        }
+    }
+}
+
+/// The notes of a sequence, transformed lazily. Made by
+/// `NoteTransform::apply`.
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Apply<I, T> {
+    notes: I,
+    transform: T,
+}
+
+impl<I, T> Iterator for Apply<I, T>
+where
+    I: Iterator<Item = Tpc>,
+    T: NoteTransform,
+{
+    type Item = Option<Tpc>;
+
+    fn next(&mut self) -> Option<Option<Tpc>> {
+        let tpc = self.notes.next()?;
+        Some(self.transform.transform(tpc))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.notes.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_stops_at_none() {
+        let mut calls = 0;
+        let counter = |tpc| {
+            calls += 1;
+            Some(tpc)
+        };
+        let pipeline = Transpose(Interval::Aug1).then(counter);
+        let result: Vec<_> = pipeline.apply(vec![Tpc::Bss, Tpc::C]).collect();
+        assert_eq!(vec![None, Some(Tpc::Cs)], result);
+        assert_eq!(1, calls);
+    }
+
+    #[test]
+    fn test_when_and_stateful_transform() {
+        let mut previous = Tpc::C;
+        let repeat_previous = move |tpc| Some(std::mem::replace(&mut previous, tpc));
+        let pipeline = repeat_previous.when(|tpc: Tpc| tpc != Tpc::G);
+        let result: Vec<_> = pipeline.apply(vec![Tpc::D, Tpc::G, Tpc::E]).collect();
+        assert_eq!(vec![Some(Tpc::C), Some(Tpc::G), Some(Tpc::D)], result);
+    }
+
+    #[test]
+    fn test_clamp_keeps_pitch_class() {
+        let mut clamp = Clamp {
+            flattest: Tpc::Gb,
+            sharpest: Tpc::B,
+        };
+        for tpc in Tpc::all() {
+            let clamped = clamp.transform(tpc).unwrap();
+            assert!(clamped.enharmonic(tpc));
+            assert!((Tpc::Gb as i8..=Tpc::B as i8).contains(&(clamped as i8)));
+        }
+    }
+}
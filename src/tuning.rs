@@ -0,0 +1,449 @@
+//! Equal divisions of the octave where enharmonic spellings differ
+//!
+//! In twelve tone equal temperament, C sharp and D flat are the same pitch. In
+//! tunings such as 19 or 31 equal divisions of the octave (EDO), the fifth is
+//! slightly narrower, and the spellings of a pitch class become distinct
+//! pitches. In 31-EDO, C sharp is a step below D flat:
+//! ```
+//! # use tonality::Tpc;
+//! # use tonality::tuning::Edo;
+//! assert_eq!(2, Edo::THIRTY_ONE.pitch_class(Tpc::Cs));
+//! assert_eq!(3, Edo::THIRTY_ONE.pitch_class(Tpc::Db));
+//! // C double sharp is distinct from D
+//! assert_eq!(4, Edo::THIRTY_ONE.pitch_class(Tpc::Css));
+//! assert_eq!(5, Edo::THIRTY_ONE.pitch_class(Tpc::D));
+//! ```
+//!
+//! A keyboard tuned in a `Temperament` such as quarter-comma meantone has only
+//! twelve of those pitches, so some spellings are played on the key of their
+//! enharmonic, and the intervals they make can be badly out of tune.
+use crate::repr::Contiguous;
+use crate::{Key, Pitch, Step, Tpc};
+
+/// An equal division of the octave into steps, where a number of steps make
+/// up the fifth
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct Edo {
+    divisions: u8,
+    fifth: u8,
+}
+
+impl Edo {
+    /// Twelve tone equal temperament, where sharps and flats are enharmonic
+    pub const TWELVE: Edo = Edo {
+        divisions: 12,
+        fifth: 7,
+    };
+
+    /// 19-EDO, where a sharp raises a note by one step and a whole tone has
+    /// three steps
+    pub const NINETEEN: Edo = Edo {
+        divisions: 19,
+        fifth: 11,
+    };
+
+    /// 31-EDO, where a sharp raises a note by two steps and a whole tone has
+    /// five steps
+    pub const THIRTY_ONE: Edo = Edo {
+        divisions: 31,
+        fifth: 18,
+    };
+
+    /// A division of the octave with a fifth of the given number of steps.
+    /// Returns None unless the fifth gives a diatonic scale, with whole tones
+    /// larger than semitones.
+    /// ```
+    /// # use tonality::tuning::Edo;
+    /// assert_eq!(Some(Edo::NINETEEN), Edo::new(19, 11));
+    /// // A fifth of 12 steps in 19-EDO would make semitones larger than
+    /// // whole tones
+    /// assert_eq!(None, Edo::new(19, 12));
+    /// ```
+    #[must_use]
+    pub fn new(divisions: u8, fifth: u8) -> Option<Edo> {
+        let edo = Edo { divisions, fifth };
+        if edo.semitone() > 0 && edo.whole_tone() > edo.semitone() {
+            Some(edo)
+        } else {
+            None
+        }
+    }
+
+    /// The number of steps in the octave
+    #[must_use]
+    pub fn divisions(self) -> u8 {
+        self.divisions
+    }
+
+    /// The steps from C up to the tpc, between 0 and one less than the
+    /// number of divisions
+    #[must_use]
+    pub fn pitch_class(self, tpc: Tpc) -> u8 {
+        let steps =
+            (i32::from(tpc as i8) * i32::from(self.fifth)).rem_euclid(i32::from(self.divisions));
+        // Less than the number of divisions, which is a u8
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let steps = steps as u8;
+        steps
+    }
+
+    /// The steps from C0 up to the pitch, where the octave of the pitch is
+    /// that of its written step, as in the `octave` module
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::tuning::Edo;
+    /// // The same as the MIDI note number, less the octave below C0
+    /// assert_eq!(57, Edo::TWELVE.steps(Pitch::new(Tpc::A, 4)));
+    /// // B sharp 3 is a step below C4 in 19-EDO
+    /// assert_eq!(75, Edo::NINETEEN.steps(Pitch::new(Tpc::Bs, 3)));
+    /// assert_eq!(76, Edo::NINETEEN.steps(Pitch::new(Tpc::C, 4)));
+    /// ```
+    #[must_use]
+    pub fn steps(self, pitch: Pitch) -> i32 {
+        let tone = self.whole_tone();
+        let semitone = self.semitone();
+        let natural = match pitch.step() {
+            Step::C => 0,
+            Step::D => tone,
+            Step::E => 2 * tone,
+            Step::F => 2 * tone + semitone,
+            Step::G => 3 * tone + semitone,
+            Step::A => 4 * tone + semitone,
+            Step::B => 5 * tone + semitone,
+        };
+        let alteration = i32::from(pitch.tpc.alteration(Key::C));
+        i32::from(pitch.octave) * i32::from(self.divisions) + natural + alteration * self.chroma()
+    }
+
+    /// The frequency of the pitch in hertz, given the frequency of A4. The
+    /// pitch may be in any octave, not only those of MIDI notes.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::tuning::Edo;
+    /// let a = Pitch::new(Tpc::A, 4);
+    /// assert!((Edo::THIRTY_ONE.frequency(a, 440.0) - 440.0).abs() < 1e-9);
+    /// let a = Pitch::new(Tpc::A, 5);
+    /// assert!((Edo::THIRTY_ONE.frequency(a, 440.0) - 880.0).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn frequency(self, pitch: Pitch, a4: f64) -> f64 {
+        let steps = self.steps(pitch) - self.steps(Pitch::new(Tpc::A, 4));
+        a4 * (f64::from(steps) / f64::from(self.divisions)).exp2()
+    }
+
+    /// The pitch in twelve tone equal temperament, with the offset in cents
+    /// that brings it to its pitch in this tuning. The offsets are relative to
+    /// A4, which is the same in every tuning.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::tuning::Edo;
+    /// let c = Edo::THIRTY_ONE.tuned(Pitch::new(Tpc::C, 4));
+    /// let e = Edo::THIRTY_ONE.tuned(Pitch::new(Tpc::E, 4));
+    /// // The major third of 31-EDO is almost 13 cents narrower
+    /// assert!((e.cents_offset - c.cents_offset + 12.9).abs() < 0.1);
+    /// ```
+    pub fn tuned(self, pitch: Pitch) -> TunedNote {
+        let a4 = Pitch::new(Tpc::A, 4);
+        let steps = self.steps(pitch) - self.steps(a4);
+        let cents = f64::from(steps) * 1200.0 / f64::from(self.divisions);
+        let semitones = f64::from(pitch.midi() - a4.midi());
+        // Offsets are less than an octave
+        #[allow(clippy::cast_possible_truncation)]
+        let cents_offset = (cents - 100.0 * semitones) as f32;
+        TunedNote {
+            pitch,
+            cents_offset,
+        }
+    }
+
+    /// The steps in a whole tone, two fifths up and an octave down
+    fn whole_tone(self) -> i32 {
+        2 * i32::from(self.fifth) - i32::from(self.divisions)
+    }
+
+    /// The steps in a diatonic semitone, such as from E to F
+    fn semitone(self) -> i32 {
+        3 * i32::from(self.divisions) - 5 * i32::from(self.fifth)
+    }
+
+    /// The steps in a chromatic semitone, such as from F to F sharp
+    fn chroma(self) -> i32 {
+        7 * i32::from(self.fifth) - 4 * i32::from(self.divisions)
+    }
+}
+
+/// The just intervals of the consonances, in cents, by their number of
+/// semitones: minor and major thirds, the fourth, the fifth and minor and
+/// major sixths
+const JUST_CONSONANCES: [(u8, f64); 6] = [
+    (3, 315.641_287_000_552_55),
+    (4, 386.313_713_864_834_8),
+    (5, 498.044_999_134_612_45),
+    (7, 701.955_000_865_387_4),
+    (8, 813.686_286_135_165_2),
+    (9, 884.358_712_999_447_5),
+];
+
+/// A regular temperament of twelve pitches to the octave, as on a keyboard,
+/// where every fifth has the same size except the one closing the circle
+/// between the sharpest and flattest spellings: the wolf.
+///
+/// The twelve pitches have twelve consecutive spellings on the line of
+/// fifths, and notes with other spellings are played on the key of their
+/// enharmonic. Intervals between such notes are the ones which can sound as
+/// wolf intervals.
+/// ```
+/// # use tonality::Tpc;
+/// # use tonality::tuning::Temperament;
+/// let meantone = Temperament::QUARTER_COMMA_MEANTONE;
+/// // A flat is played as G sharp, so the fifth from A flat to E flat is the
+/// // wolf, more than 35 cents wider than pure
+/// assert_eq!(Tpc::Gs, meantone.played(Tpc::Ab));
+/// assert!(meantone.is_wolf(Tpc::Ab, Tpc::Eb, 20.0));
+/// assert!(!meantone.is_wolf(Tpc::C, Tpc::E, 20.0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct Temperament {
+    fifth: f64,
+    flattest: Tpc,
+}
+
+impl Temperament {
+    /// Quarter-comma meantone from E flat to G sharp, with pure major thirds
+    /// and fifths a quarter of a syntonic comma narrow
+    pub const QUARTER_COMMA_MEANTONE: Temperament = Temperament {
+        fifth: 696.578_428_466_208_7,
+        flattest: Tpc::Eb,
+    };
+
+    /// Pythagorean tuning from E flat to G sharp, with pure fifths
+    pub const PYTHAGOREAN: Temperament = Temperament {
+        fifth: 701.955_000_865_387_4,
+        flattest: Tpc::Eb,
+    };
+
+    /// A temperament with fifths of the given size in cents, and twelve
+    /// pitches from the flattest spelling up along the line of fifths.
+    /// Returns None if the eleventh fifth above the flattest spelling would be
+    /// beyond double sharps.
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::tuning::Temperament;
+    /// // Meantone with A flat instead of G sharp
+    /// let meantone = Temperament::new(696.58, Tpc::Ab).unwrap();
+    /// assert_eq!(Tpc::Ab, meantone.played(Tpc::Gs));
+    /// assert_eq!(None, Temperament::new(696.58, Tpc::As));
+    /// ```
+    #[must_use]
+    pub fn new(fifth: f64, flattest: Tpc) -> Option<Temperament> {
+        if flattest as i8 + 11 > Tpc::MAX as i8 {
+            return None;
+        }
+        Some(Temperament { fifth, flattest })
+    }
+
+    /// The twelve spellings with pitches of their own, from the flattest up
+    /// along the line of fifths
+    pub fn notes(self) -> impl Iterator<Item = Tpc> {
+        Tpc::all().skip(self.flattest.index()).take(12)
+    }
+
+    /// The spelling whose key a note is played on: the note itself, or the
+    /// enharmonic spelling among the twelve
+    pub fn played(self, tpc: Tpc) -> Tpc {
+        let flattest = self.flattest as i8;
+        let fifths = flattest + (tpc as i8 - flattest).rem_euclid(Tpc::DELTA_ENHARMONIC);
+        Tpc::in_range(fifths)
+    }
+
+    /// The size in cents of the interval as played from one note up to
+    /// another, less than an octave
+    #[must_use]
+    pub fn cents(self, from: Tpc, to: Tpc) -> f64 {
+        let fifths = self.played(to) as i8 - self.played(from) as i8;
+        (f64::from(fifths) * self.fifth).rem_euclid(1200.0)
+    }
+
+    /// How many cents the interval as played from one note up to another is
+    /// wider than the just consonance with the same number of semitones, or
+    /// None if the notes are not a third, fourth, fifth or sixth apart in
+    /// semitones
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::tuning::Temperament;
+    /// let meantone = Temperament::QUARTER_COMMA_MEANTONE;
+    /// assert!(meantone.deviation(Tpc::C, Tpc::E).unwrap().abs() < 1e-9);
+    /// // B to E flat is played as a diminished fourth, in place of a third
+    /// assert!((meantone.deviation(Tpc::B, Tpc::Eb).unwrap() - 41.1).abs() < 0.1);
+    /// assert_eq!(None, meantone.deviation(Tpc::C, Tpc::D));
+    /// ```
+    #[must_use]
+    pub fn deviation(self, from: Tpc, to: Tpc) -> Option<f64> {
+        let semitones = (to.pitch_class() + 12 - from.pitch_class()) % 12;
+        JUST_CONSONANCES
+            .iter()
+            .find(|&&(consonance, _)| consonance == semitones)
+            .map(|&(_, just)| self.cents(from, to) - just)
+    }
+
+    /// Whether the interval between the notes, as played, deviates from a
+    /// just consonance by more than the tolerance in cents. The wolf fifth of
+    /// quarter-comma meantone deviates by about 36 cents, and its wolf thirds
+    /// by 41 and 46, while its other consonances deviate by at most 6.
+    #[must_use]
+    pub fn is_wolf(self, from: Tpc, to: Tpc, tolerance: f64) -> bool {
+        self.deviation(from, to)
+            .is_some_and(|deviation| deviation.abs() > tolerance)
+    }
+
+    /// Every pair of the twelve spellings which makes a wolf interval with the
+    /// tolerance, from the lower to the upper note, ordered by the lower note
+    /// and then the upper along the line of fifths
+    /// ```
+    /// # use tonality::Tpc;
+    /// # use tonality::tuning::Temperament;
+    /// let wolves = Temperament::PYTHAGOREAN.wolf_intervals(23.0);
+    /// // Only the fifth from G sharp to E flat and the fourth back
+    /// assert_eq!(vec![(Tpc::Eb, Tpc::Gs), (Tpc::Gs, Tpc::Eb)], wolves);
+    /// ```
+    #[must_use]
+    pub fn wolf_intervals(self, tolerance: f64) -> Vec<(Tpc, Tpc)> {
+        self.notes()
+            .flat_map(|from| self.notes().map(move |to| (from, to)))
+            .filter(|&(from, to)| self.is_wolf(from, to, tolerance))
+            .collect()
+    }
+}
+
+/// A spelled pitch with an offset in cents from its pitch in twelve tone
+/// equal temperament.
+///
+/// The offset carries the tuning of the note, such as a just or EDO tuning,
+/// through to frequency and pitch bend conversion, while the pitch keeps its
+/// spelling for notation.
+/// ```
+/// # use tonality::{Pitch, Tpc};
+/// # use tonality::tuning::TunedNote;
+/// // A just major third above C4 is 13.7 cents flat
+/// let e = TunedNote::new(Pitch::new(Tpc::E, 4), -13.7);
+/// assert_eq!(64, e.pitch.midi());
+/// assert!((e.frequency(440.0) - 327.03).abs() < 0.01);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct TunedNote {
+    /// The spelled pitch
+    pub pitch: Pitch,
+    /// The offset from the pitch in twelve tone equal temperament, in cents
+    pub cents_offset: f32,
+}
+
+impl TunedNote {
+    /// A pitch with an offset in cents
+    pub const fn new(pitch: Pitch, cents_offset: f32) -> Self {
+        TunedNote {
+            pitch,
+            cents_offset,
+        }
+    }
+
+    /// The frequency of the note in hertz, given the frequency of A4
+    #[must_use]
+    pub fn frequency(self, a4: f64) -> f64 {
+        let cents = f64::from(self.cents_offset);
+        Edo::TWELVE.frequency(self.pitch, a4) * (cents / 1200.0).exp2()
+    }
+
+    /// The 14-bit MIDI pitch bend value that applies the offset to the MIDI
+    /// note of the pitch, given the bend range in semitones. MPE uses a range
+    /// of 48 semitones by default. Offsets beyond the range are clamped.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::tuning::TunedNote;
+    /// let pitch = Pitch::new(Tpc::E, 4);
+    /// assert_eq!(8192, TunedNote::new(pitch, 0.0).pitch_bend(2.0));
+    /// assert_eq!(12288, TunedNote::new(pitch, 100.0).pitch_bend(2.0));
+    /// assert_eq!(16383, TunedNote::new(pitch, 300.0).pitch_bend(2.0));
+    /// ```
+    #[must_use]
+    pub fn pitch_bend(self, range: f32) -> u16 {
+        let bend = 8192.0 + f64::from(self.cents_offset / (100.0 * range)) * 8192.0;
+        // Clamped to the 14-bit range
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let bend = bend.round().clamp(0.0, 16383.0) as u16;
+        bend
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_twelve_matches_midi() {
+        for tpc in Tpc::all() {
+            let pitch = Pitch::new(tpc, 4);
+            assert_eq!(i32::from(pitch.midi()) - 12, Edo::TWELVE.steps(pitch));
+            assert_eq!(tpc.pitch_class(), Edo::TWELVE.pitch_class(tpc));
+        }
+    }
+
+    #[test]
+    fn test_steps_match_pitch_classes() {
+        for &edo in &[Edo::TWELVE, Edo::NINETEEN, Edo::THIRTY_ONE] {
+            for tpc in Tpc::all() {
+                let steps = edo.steps(Pitch::new(tpc, 4));
+                let pitch_class = steps.rem_euclid(i32::from(edo.divisions()));
+                assert_eq!(i32::from(edo.pitch_class(tpc)), pitch_class);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tuned_frequency_matches_edo() {
+        for &edo in &[Edo::TWELVE, Edo::NINETEEN, Edo::THIRTY_ONE] {
+            for tpc in Tpc::all() {
+                let pitch = Pitch::new(tpc, 3);
+                let tuned = edo.tuned(pitch);
+                let expected = edo.frequency(pitch, 440.0);
+                assert!((tuned.frequency(440.0) - expected).abs() < 1e-3);
+            }
+        }
+        let tuned = Edo::TWELVE.tuned(Pitch::new(Tpc::Bs, 3));
+        assert!(tuned.cents_offset.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_quarter_comma_wolves() {
+        let meantone = Temperament::QUARTER_COMMA_MEANTONE;
+        let wolves = meantone.wolf_intervals(20.0);
+        // The fifth and fourth between G sharp and E flat, four thirds and
+        // three minor thirds, each with its inversion
+        assert_eq!(16, wolves.len());
+        assert!(wolves.contains(&(Tpc::Gs, Tpc::Eb)));
+        assert!(wolves.contains(&(Tpc::Fs, Tpc::Bb)));
+        assert!(wolves.contains(&(Tpc::Eb, Tpc::Fs)));
+        for &(from, to) in &wolves {
+            assert!(meantone.deviation(from, to).unwrap().abs() > 35.0);
+        }
+        // Every other consonance is within six cents
+        let others = meantone
+            .notes()
+            .flat_map(|from| meantone.notes().map(move |to| (from, to)))
+            .filter(|pair| !wolves.contains(pair));
+        for (from, to) in others {
+            assert!(meantone.deviation(from, to).is_none_or(|d| d.abs() < 6.0));
+        }
+    }
+
+    #[test]
+    fn test_nineteen_distinguishes_double_accidentals() {
+        let edo = Edo::NINETEEN;
+        assert_ne!(edo.pitch_class(Tpc::Fss), edo.pitch_class(Tpc::G));
+        assert_ne!(edo.pitch_class(Tpc::Abb), edo.pitch_class(Tpc::G));
+        // But E sharp and F flat are the same step
+        assert_eq!(edo.pitch_class(Tpc::Es), edo.pitch_class(Tpc::Fb));
+    }
+}
@@ -0,0 +1,423 @@
+//! Chord voicings: spelled chords placed in concrete octaves
+use std::convert::TryFrom;
+
+use crate::chord::Chord;
+use crate::{Interval, Pitch, Tpc};
+
+/// How the notes of a voicing are spaced
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Spacing {
+    /// All chord tones stacked as tightly as possible, within an octave
+    #[default]
+    Closed,
+    /// Every other voice of the closed voicing, counted from the bass, is
+    /// raised an octave. For a triad in root position, this gives root, fifth,
+    /// third.
+    Open,
+    /// The second voice from the top of the closed voicing is lowered an
+    /// octave.
+    Drop2,
+}
+
+impl Spacing {
+    /// Respace a closed voicing, keeping it sorted from the bass up. Returns
+    /// None if a voice would move beyond the range of octaves.
+    fn apply(self, pitches: &mut [Pitch]) -> Option<()> {
+        match self {
+            Spacing::Closed => {}
+            Spacing::Open => {
+                for pitch in pitches.iter_mut().skip(1).step_by(2) {
+                    pitch.octave = pitch.octave.checked_add(1)?;
+                }
+            }
+            Spacing::Drop2 => {
+                if let Some(i) = pitches.len().checked_sub(2) {
+                    pitches[i].octave = pitches[i].octave.checked_sub(1)?;
+                }
+            }
+        }
+        pitches.sort_by_key(|p| p.midi());
+        Some(())
+    }
+}
+
+/// Builds the voicings of a chord satisfying a set of constraints.
+///
+/// The chord is given as a root and the intervals above it. Every inversion of
+/// the chord is considered, and each voicing is returned as a list of pitches
+/// from the bass upwards.
+/// ```
+/// # use tonality::{Interval, Pitch, Tpc};
+/// # use tonality::voicing::{Spacing, VoicingBuilder};
+/// use Interval::*;
+/// let voicings = VoicingBuilder::new(Tpc::Eb, &[Unison, Maj3, P5])
+///     .range(Pitch::new(Tpc::C, 4), Pitch::new(Tpc::C, 5))
+///     .build()
+///     .unwrap();
+/// let expected = vec![
+///     Pitch::new(Tpc::Eb, 4),
+///     Pitch::new(Tpc::G, 4),
+///     Pitch::new(Tpc::Bb, 4),
+/// ];
+/// assert_eq!(vec![expected], voicings);
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct VoicingBuilder {
+    root: Tpc,
+    intervals: Vec<Interval>,
+    lowest: Pitch,
+    highest: Pitch,
+    max_spread: Option<u8>,
+    spacing: Spacing,
+}
+
+impl VoicingBuilder {
+    /// Start building voicings of the chord with the given root and intervals.
+    ///
+    /// By default, voicings are closed and may span the range of a piano.
+    pub fn new(root: Tpc, intervals: &[Interval]) -> Self {
+        Self {
+            root,
+            intervals: intervals.to_vec(),
+            lowest: Pitch::new(Tpc::A, 0),
+            highest: Pitch::new(Tpc::C, 8),
+            max_spread: None,
+            spacing: Spacing::default(),
+        }
+    }
+
+    /// Only allow voicings where every pitch is within the range, inclusive.
+    /// Pitches are compared by their sounding height.
+    pub fn range(mut self, lowest: Pitch, highest: Pitch) -> Self {
+        self.lowest = lowest;
+        self.highest = highest;
+        self
+    }
+
+    /// Only allow voicings spanning at most this many semitones from the bass
+    /// to the top voice
+    pub fn max_spread(mut self, semitones: u8) -> Self {
+        self.max_spread = Some(semitones);
+        self
+    }
+
+    /// Set the spacing of the voicings
+    pub fn spacing(mut self, spacing: Spacing) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// All voicings satisfying the constraints, ordered by the height of the
+    /// bass.
+    ///
+    /// Returns None if any of the chord tones is outside the domain of `Tpc`.
+    /// Voicings which would reach beyond the range of octave numbers are left
+    /// out.
+    ///
+    /// ```
+    /// # use tonality::{Interval, Pitch, Tpc};
+    /// # use tonality::voicing::{Spacing, VoicingBuilder};
+    /// use Interval::*;
+    /// let voicings = VoicingBuilder::new(Tpc::C, &[Unison, Maj3, P5, Min7])
+    ///     .range(Pitch::new(Tpc::C, 3), Pitch::new(Tpc::C, 5))
+    ///     .spacing(Spacing::Drop2)
+    ///     .build()
+    ///     .unwrap();
+    /// let expected = vec![
+    ///     Pitch::new(Tpc::C, 3),
+    ///     Pitch::new(Tpc::G, 3),
+    ///     Pitch::new(Tpc::Bb, 3),
+    ///     Pitch::new(Tpc::E, 4),
+    /// ];
+    /// assert_eq!(expected, voicings[0]);
+    /// ```
+    #[must_use]
+    pub fn build(&self) -> Option<Vec<Vec<Pitch>>> {
+        let tones = self
+            .intervals
+            .iter()
+            .map(|&interval| self.root + interval)
+            .collect::<Option<Vec<Tpc>>>()?;
+        let mut voicings = Vec::new();
+        // One octave beyond the range on either side, so that inversions and
+        // spacings moving voices by an octave are found
+        let octaves = i16::from(self.lowest.octave) - 1..=i16::from(self.highest.octave) + 1;
+        for inversion in 0..tones.len() {
+            for octave in octaves
+                .clone()
+                .filter_map(|octave| i8::try_from(octave).ok())
+            {
+                let Some(voicing) = self.voice(&tones, inversion, octave) else {
+                    continue;
+                };
+                if self.allows(&voicing) && !voicings.contains(&voicing) {
+                    voicings.push(voicing);
+                }
+            }
+        }
+        voicings.sort_by_key(|voicing: &Vec<Pitch>| {
+            voicing.iter().map(|p| p.midi()).collect::<Vec<_>>()
+        });
+        Some(voicings)
+    }
+
+    /// Stack the chord tones from the given inversion upwards, then apply the
+    /// spacing. Returns None if the voicing would go beyond the range of
+    /// octaves.
+    fn voice(&self, tones: &[Tpc], inversion: usize, octave: i8) -> Option<Vec<Pitch>> {
+        let mut pitches = Vec::with_capacity(tones.len());
+        let mut current = Pitch::new(tones[inversion], octave);
+        pitches.push(current);
+        for i in 1..tones.len() {
+            current = current.next_above(tones[(inversion + i) % tones.len()])?;
+            pitches.push(current);
+        }
+        self.spacing.apply(&mut pitches)?;
+        Some(pitches)
+    }
+
+    /// Whether the voicing satisfies the range and spread constraints
+    fn allows(&self, voicing: &[Pitch]) -> bool {
+        let (lowest, highest) = (self.lowest.midi(), self.highest.midi());
+        if voicing
+            .iter()
+            .any(|p| p.midi() < lowest || p.midi() > highest)
+        {
+            return false;
+        }
+        match (self.max_spread, voicing.first(), voicing.last()) {
+            (Some(spread), Some(bass), Some(top)) => top.midi() - bass.midi() <= i16::from(spread),
+            _ => true,
+        }
+    }
+}
+
+/// A ready-made voicing of a chord, for previewing it in playback.
+///
+/// Every preset starts from the lowest pitch at or above a given pitch, which
+/// sets the register. The bass of a slash chord or inversion is kept as the
+/// lowest note, with the rest of the preset above it.
+/// ```
+/// # use tonality::{Pitch, Tpc};
+/// # use tonality::chord::{Chord, ChordQuality};
+/// # use tonality::voicing::Preset;
+/// let chord = Chord::new(Tpc::D, ChordQuality::Minor7);
+/// let c3 = Pitch::new(Tpc::C, 3);
+/// let voicing = Preset::RootlessA.voice(chord, c3).unwrap();
+/// let expected = vec![
+///     Pitch::new(Tpc::F, 3),
+///     Pitch::new(Tpc::A, 3),
+///     Pitch::new(Tpc::C, 4),
+///     Pitch::new(Tpc::E, 4),
+/// ];
+/// assert_eq!(expected, voicing);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    /// The chord tones in closed position above the bass, as
+    /// `Chord::voicing`
+    Close,
+    /// The closed voicing with every other voice above the bass raised an
+    /// octave, as `Spacing::Open`
+    Spread,
+    /// The root, third and seventh, or the whole triad for chords without a
+    /// seventh
+    Shell,
+    /// The third, fifth, seventh and ninth from the bottom up, for a left
+    /// hand without the root. In triads, the root takes the place of the
+    /// seventh.
+    RootlessA,
+    /// The seventh, ninth, third and fifth from the bottom up, the other
+    /// inversion of `RootlessA`
+    RootlessB,
+}
+
+impl Preset {
+    /// The voicing of the chord in the register starting at the lowest pitch.
+    /// Returns None if a chord tone is outside the domain of `Tpc`, or if the
+    /// voicing would go beyond the range of octaves.
+    /// ```
+    /// # use tonality::{Pitch, Tpc};
+    /// # use tonality::chord::{Chord, ChordQuality};
+    /// # use tonality::voicing::Preset;
+    /// let chord = Chord::new(Tpc::G, ChordQuality::Dominant7).with_bass(Tpc::B);
+    /// let voicing = Preset::Shell.voice(chord, Pitch::new(Tpc::A, 2)).unwrap();
+    /// // The bass, then the root and seventh of the shell
+    /// let expected = vec![
+    ///     Pitch::new(Tpc::B, 2),
+    ///     Pitch::new(Tpc::G, 3),
+    ///     Pitch::new(Tpc::F, 4),
+    /// ];
+    /// assert_eq!(expected, voicing);
+    /// ```
+    #[must_use]
+    pub fn voice(self, chord: Chord, lowest: Pitch) -> Option<Vec<Pitch>> {
+        let tones = self.tones(chord)?;
+        let mut pitches = Vec::with_capacity(tones.len());
+        let mut current = Pitch::new(tones[0], lowest.octave).fold_into_octave(lowest);
+        pitches.push(current);
+        for &tone in &tones[1..] {
+            current = current.next_above(tone)?;
+            pitches.push(current);
+        }
+        if self == Preset::Spread {
+            Spacing::Open.apply(&mut pitches)?;
+        }
+        Some(pitches)
+    }
+
+    /// The tones of the chord in the preset, from the bottom up
+    fn tones(self, chord: Chord) -> Option<Vec<Tpc>> {
+        let tones = chord.tones()?;
+        let ninth = (chord.root + Interval::Maj2)?;
+        let seventh = tones.get(3).copied().unwrap_or(tones[0]);
+        let upper = match self {
+            Preset::Close | Preset::Spread => return chord.tones_from_bass(),
+            Preset::Shell if tones.len() > 3 => vec![tones[0], tones[1], tones[3]],
+            Preset::Shell => tones,
+            Preset::RootlessA => vec![tones[1], tones[2], seventh, ninth],
+            Preset::RootlessB => vec![seventh, ninth, tones[1], tones[2]],
+        };
+        let Some(bass) = chord.bass else {
+            return Some(upper);
+        };
+        let mut voiced = vec![bass];
+        voiced.extend(upper.into_iter().filter(|&tone| tone != bass));
+        Some(voiced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chord::ChordQuality;
+    use Interval::*;
+
+    #[test]
+    fn test_closed_inversions() {
+        let voicings = VoicingBuilder::new(Tpc::C, &[Unison, Maj3, P5])
+            .range(Pitch::new(Tpc::C, 4), Pitch::new(Tpc::C, 5))
+            .build()
+            .unwrap();
+        let first = vec![
+            Pitch::new(Tpc::C, 4),
+            Pitch::new(Tpc::E, 4),
+            Pitch::new(Tpc::G, 4),
+        ];
+        let second = vec![
+            Pitch::new(Tpc::E, 4),
+            Pitch::new(Tpc::G, 4),
+            Pitch::new(Tpc::C, 5),
+        ];
+        assert_eq!(vec![first, second], voicings);
+    }
+
+    #[test]
+    fn test_open_spacing() {
+        let expected = vec![
+            Pitch::new(Tpc::A, 2),
+            Pitch::new(Tpc::E, 3),
+            Pitch::new(Tpc::C, 4),
+        ];
+        let voicings = VoicingBuilder::new(Tpc::A, &[Unison, Min3, P5])
+            .range(Pitch::new(Tpc::A, 2), Pitch::new(Tpc::C, 4))
+            .spacing(Spacing::Open)
+            .build()
+            .unwrap();
+        assert_eq!(vec![expected], voicings);
+    }
+
+    #[test]
+    fn test_max_spread() {
+        let voicings = VoicingBuilder::new(Tpc::G, &[Unison, Maj3, P5, Min7])
+            .range(Pitch::new(Tpc::C, 3), Pitch::new(Tpc::C, 5))
+            .max_spread(8)
+            .build()
+            .unwrap();
+        let expected = vec![
+            Pitch::new(Tpc::B, 3),
+            Pitch::new(Tpc::D, 4),
+            Pitch::new(Tpc::F, 4),
+            Pitch::new(Tpc::G, 4),
+        ];
+        assert_eq!(vec![expected], voicings);
+    }
+
+    #[test]
+    fn test_unspellable_chord() {
+        let builder = VoicingBuilder::new(Tpc::Bss, &[Unison, Maj3]);
+        assert_eq!(None, builder.build());
+    }
+
+    #[test]
+    fn test_presets() {
+        let chord = Chord::new(Tpc::C, ChordQuality::Dominant7);
+        let c3 = Pitch::new(Tpc::C, 3);
+        let names = |preset: Preset| -> Vec<String> {
+            preset
+                .voice(chord, c3)
+                .unwrap()
+                .iter()
+                .map(|pitch| format!("{}{}", pitch.tpc, pitch.octave))
+                .collect()
+        };
+        assert_eq!(vec!["C3", "E3", "G3", "B♭3"], names(Preset::Close));
+        assert_eq!(vec!["C3", "G3", "E4", "B♭4"], names(Preset::Spread));
+        assert_eq!(vec!["C3", "E3", "B♭3"], names(Preset::Shell));
+        assert_eq!(vec!["E3", "G3", "B♭3", "D4"], names(Preset::RootlessA));
+        assert_eq!(vec!["B♭3", "D4", "E4", "G4"], names(Preset::RootlessB));
+    }
+
+    #[test]
+    fn test_preset_register() {
+        let chord = Chord::new(Tpc::A, ChordQuality::Minor);
+        let lowest = Pitch::new(Tpc::Bs, 3);
+        for &preset in &[
+            Preset::Close,
+            Preset::Spread,
+            Preset::Shell,
+            Preset::RootlessA,
+            Preset::RootlessB,
+        ] {
+            let voicing = preset.voice(chord, lowest).unwrap();
+            assert!(voicing[0].midi() >= lowest.midi());
+            assert!(voicing[0].midi() < lowest.midi() + 12);
+            assert!(voicing
+                .windows(2)
+                .all(|pair| pair[0].midi() < pair[1].midi()));
+        }
+        let unspellable = Chord::new(Tpc::Bss, ChordQuality::Major);
+        assert_eq!(None, Preset::Close.voice(unspellable, lowest));
+        let top = Pitch::new(Tpc::C, i8::MAX);
+        assert_eq!(None, Preset::Close.voice(chord, top));
+    }
+
+    #[test]
+    fn test_extreme_octaves() {
+        let top = Pitch::new(Tpc::C, i8::MAX);
+        let voicings = VoicingBuilder::new(Tpc::C, &[Unison, Maj3, P5])
+            .range(Pitch::new(Tpc::C, i8::MAX - 1), top)
+            .build()
+            .unwrap();
+        let expected = vec![
+            Pitch::new(Tpc::C, i8::MAX - 1),
+            Pitch::new(Tpc::E, i8::MAX - 1),
+            Pitch::new(Tpc::G, i8::MAX - 1),
+        ];
+        assert_eq!(Some(&expected), voicings.first());
+        assert!(voicings
+            .iter()
+            .flatten()
+            .all(|pitch| pitch.midi() <= top.midi()));
+        let bottom = Pitch::new(Tpc::C, i8::MIN);
+        for &spacing in &[Spacing::Closed, Spacing::Open, Spacing::Drop2] {
+            let voicings = VoicingBuilder::new(Tpc::C, &[Unison, Maj3, P5, Min7])
+                .range(bottom, Pitch::new(Tpc::C, i8::MIN + 2))
+                .spacing(spacing)
+                .build()
+                .unwrap();
+            assert!(!voicings.is_empty());
+        }
+    }
+}
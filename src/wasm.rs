@@ -0,0 +1,192 @@
+//! A flat API for use from JavaScript through `wasm-bindgen`
+//!
+//! The functions take note names, key names and integers, and return note
+//! names in ASCII, such as `"F#4"` or `"Bb"`. Octave numbers are those of the
+//! written step, where middle C is C4, as in the `octave` module. Invalid input
+//! or results outside the domain of the library give `undefined` in JavaScript.
+//!
+//! ```
+//! # use tonality::wasm;
+//! assert_eq!(Some("A#4".to_string()), wasm::spell_midi(70, "B"));
+//! assert_eq!(Some("Eb5".to_string()), wasm::transpose("Bb4", 3, 5));
+//! assert_eq!(Some("Bb Eb".to_string()), wasm::key_signature("Bb"));
+//! assert_eq!(Some("D F# A C".to_string()), wasm::spell_chord("D", "7"));
+//! ```
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::chord::{Chord, ChordQuality};
+use crate::spelling::RespellPolicy;
+use crate::{Accidental, Interval, Key, Pitch, Step, Tpc};
+
+/// Spell a MIDI note number with its octave, as it would be written in a key
+#[wasm_bindgen]
+#[must_use]
+pub fn spell_midi(note: u8, key: &str) -> Option<String> {
+    let policy = RespellPolicy::KeyRelative(parse_key(key)?);
+    Some(format_pitch(Pitch::from_midi(note, policy)))
+}
+
+/// Transpose a note with an octave, such as `"C#4"`, by an interval given as
+/// a number of steps and a number of semitones, where 2 and 4 is a major
+/// third up. Negative numbers transpose down, and steps beyond an octave give
+/// compound intervals. The numbers must not have opposite signs, except that
+/// a second may span no semitones, and the direction of a unison is given by
+/// the sign of its semitones.
+#[wasm_bindgen]
+#[must_use]
+pub fn transpose(pitch: &str, steps: i8, semitones: i8) -> Option<String> {
+    if steps.signum() * semitones.signum() < 0 {
+        return None;
+    }
+    let pitch = parse_pitch(pitch)?;
+    let interval =
+        Interval::from_step_and_semitones(steps.unsigned_abs(), semitones.checked_abs()?)?;
+    let simple = if steps < 0 || (steps == 0 && semitones < 0) {
+        (pitch - interval)?
+    } else {
+        (pitch + interval)?
+    };
+    // Steps beyond an octave add or remove whole octaves
+    let octave = simple.octave.checked_add(steps / 7)?;
+    Some(format_pitch(Pitch::new(simple.tpc, octave)))
+}
+
+/// The accidentals of the key signature in the order they are written,
+/// separated by spaces
+#[wasm_bindgen]
+#[must_use]
+pub fn key_signature(key: &str) -> Option<String> {
+    // Sharps are written from F to B along the line of fifths, flats backwards
+    const STEPS: [Step; 7] = [
+        Step::F,
+        Step::C,
+        Step::G,
+        Step::D,
+        Step::A,
+        Step::E,
+        Step::B,
+    ];
+    let key = parse_key(key)?;
+    let mut accidentals: Vec<String> = STEPS
+        .iter()
+        .map(|step| step.with_key(key))
+        .filter(|&tpc| tpc.accidental() != Accidental::Natural)
+        .map(|tpc| format!("{tpc:#}"))
+        .collect();
+    if (key as i8) < 0 {
+        accidentals.reverse();
+    }
+    Some(accidentals.join(" "))
+}
+
+/// Spell the notes of a chord from the root up, separated by spaces.
+///
+/// The quality is the ASCII suffix of a chord symbol, such as `""` or `"maj"`,
+/// `"m"`, `"dim"`, `"7"`, `"maj7"` or `"m7b5"`.
+#[wasm_bindgen]
+#[must_use]
+pub fn spell_chord(root: &str, quality: &str) -> Option<String> {
+    let quality = match quality {
+        "maj" => ChordQuality::Major,
+        _ => *ChordQuality::ALL
+            .iter()
+            .find(|&&q| q.symbol(true) == quality)?,
+    };
+    let tones = Chord::new(parse_tpc(root)?, quality).tones()?;
+    let tones: Vec<String> = tones.iter().map(|tpc| format!("{tpc:#}")).collect();
+    Some(tones.join(" "))
+}
+
+/// Parse a note name such as `"C"`, `"F#"` or `"Ebb"`, in ASCII or Unicode
+fn parse_tpc(name: &str) -> Option<Tpc> {
+    const ACCIDENTALS: [Accidental; 5] = [
+        Accidental::DblFlat,
+        Accidental::Flat,
+        Accidental::Natural,
+        Accidental::Sharp,
+        Accidental::DblSharp,
+    ];
+    let mut chars = name.chars();
+    let step = match chars.next()?.to_ascii_uppercase() {
+        'C' => Step::C,
+        'D' => Step::D,
+        'E' => Step::E,
+        'F' => Step::F,
+        'G' => Step::G,
+        'A' => Step::A,
+        'B' => Step::B,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    if rest.is_empty() {
+        return Some(step.with_accidental(Accidental::Natural));
+    }
+    ACCIDENTALS
+        .iter()
+        .find(|accidental| rest == accidental.symbol(true) || rest == accidental.symbol(false))
+        .map(|&accidental| step.with_accidental(accidental))
+}
+
+/// Parse a note name followed by an octave number, such as `"Bb3"`
+fn parse_pitch(name: &str) -> Option<Pitch> {
+    let start = name
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '-' || c.is_ascii_digit())?
+        .0;
+    let tpc = parse_tpc(&name[..start])?;
+    let octave = name[start..].parse().ok()?;
+    Some(Pitch::new(tpc, octave))
+}
+
+/// Parse the name of a major key, such as `"Eb"`
+fn parse_key(name: &str) -> Option<Key> {
+    num_traits::FromPrimitive::from_i8(parse_tpc(name)? as i8)
+}
+
+fn format_pitch(pitch: Pitch) -> String {
+    format!("{:#}{}", pitch.tpc, pitch.octave)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Some(Tpc::Fss), parse_tpc("F##"));
+        assert_eq!(Some(Tpc::Eb), parse_tpc("E♭"));
+        assert_eq!(None, parse_tpc("H"));
+        assert_eq!(Some(Pitch::new(Tpc::Cb, -1)), parse_pitch("Cb-1"));
+        assert_eq!(None, parse_key("G#"));
+    }
+
+    #[test]
+    fn test_key_signature() {
+        assert_eq!(Some(String::new()), key_signature("C"));
+        assert_eq!(Some("F# C# G# D#".to_string()), key_signature("E"));
+        assert_eq!(
+            Some("Bb Eb Ab Db Gb Cb Fb".to_string()),
+            key_signature("Cb")
+        );
+    }
+
+    #[test]
+    fn test_transpose() {
+        assert_eq!(Some("E5".to_string()), transpose("C4", 9, 16));
+        assert_eq!(Some("F#3".to_string()), transpose("C4", -4, -6));
+        assert_eq!(None, transpose("C4", 2, 6));
+    }
+
+    #[test]
+    fn test_transpose_rejects_opposite_signs() {
+        assert_eq!(None, transpose("C4", 2, -3));
+        assert_eq!(None, transpose("C4", -2, 3));
+        // A diminished second spans no semitones, in either direction
+        assert_eq!(Some("Dbb4".to_string()), transpose("C4", 1, 0));
+        assert_eq!(Some("B#3".to_string()), transpose("C4", -1, 0));
+        // The sign of the semitones gives the direction of a unison
+        assert_eq!(Some("Cb4".to_string()), transpose("C4", 0, -1));
+        assert_eq!(Some("C#4".to_string()), transpose("C4", 0, 1));
+    }
+}
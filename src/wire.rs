@@ -0,0 +1,230 @@
+//! A stable text format for saving values
+//!
+//! Values are written as ASCII names: tpcs and keys by their note names, such
+//! as `"F#"` and `"Eb"`, intervals by quality and number, such as `"P5"` and
+//! `"m3"`, and pitches by note name and octave, such as `"Bb3"`. Tpcs, keys
+//! and intervals can also be read from their position on the line of fifths,
+//! such as `"-3"` for E flat.
+//!
+//! The format is independent of the order and discriminants of the enums.
+//! Every string written by a version of the crate with the same
+//! `FORMAT_VERSION` reads back as the same value, and later versions keep
+//! reading it.
+//! ```
+//! # use tonality::{Interval, Key, Tpc};
+//! # use tonality::wire::WireFormat;
+//! assert_eq!("F#", Tpc::Fs.to_wire());
+//! assert_eq!(Some(Tpc::Eb), Tpc::from_wire("Eb"));
+//! assert_eq!(Some(Tpc::Eb), Tpc::from_wire("-3"));
+//! assert_eq!("m3", Interval::Min3.to_wire());
+//! assert_eq!(Some(Key::A), Key::from_wire("3"));
+//! ```
+use crate::interval::{IntervalClass, IntervalQuality};
+use crate::{Accidental, Interval, Key, LineOfFifths, Pitch, Step, Tpc};
+
+/// The version of the format. It changes only if strings written by earlier
+/// versions would be read differently.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Conversion to and from the stable text format
+pub trait WireFormat: Sized {
+    /// Write the value as a string
+    fn to_wire(self) -> String;
+
+    /// Read a value written by `to_wire`, or None if the string is not a
+    /// value of the type
+    fn from_wire(wire: &str) -> Option<Self>;
+}
+
+impl WireFormat for Tpc {
+    fn to_wire(self) -> String {
+        format!("{self:#}")
+    }
+
+    fn from_wire(wire: &str) -> Option<Self> {
+        if let Ok(fifths) = wire.parse() {
+            return Tpc::from_fifths(fifths);
+        }
+        let mut chars = wire.chars();
+        let step = match chars.next()? {
+            'C' => Step::C,
+            'D' => Step::D,
+            'E' => Step::E,
+            'F' => Step::F,
+            'G' => Step::G,
+            'A' => Step::A,
+            'B' => Step::B,
+            _ => return None,
+        };
+        let accidental = match chars.as_str() {
+            "bb" => Accidental::DblFlat,
+            "b" => Accidental::Flat,
+            "" => Accidental::Natural,
+            "#" => Accidental::Sharp,
+            "##" => Accidental::DblSharp,
+            _ => return None,
+        };
+        Some(step.with_accidental(accidental))
+    }
+}
+
+impl WireFormat for Key {
+    fn to_wire(self) -> String {
+        self.root().to_wire()
+    }
+
+    fn from_wire(wire: &str) -> Option<Self> {
+        Key::from_fifths(Tpc::from_wire(wire)?.fifths())
+    }
+}
+
+impl WireFormat for Interval {
+    fn to_wire(self) -> String {
+        let quality = match self.quality() {
+            IntervalQuality::Diminished => 'd',
+            IntervalQuality::Minor => 'm',
+            IntervalQuality::Perfect => 'P',
+            IntervalQuality::Major => 'M',
+            IntervalQuality::Augmented => 'A',
+        };
+        format!("{}{}", quality, self.class() as u8 + 1)
+    }
+
+    fn from_wire(wire: &str) -> Option<Self> {
+        if let Ok(fifths) = wire.parse() {
+            return Interval::from_fifths(fifths);
+        }
+        let mut chars = wire.chars();
+        let quality = match chars.next()? {
+            'd' => IntervalQuality::Diminished,
+            'm' => IntervalQuality::Minor,
+            'P' => IntervalQuality::Perfect,
+            'M' => IntervalQuality::Major,
+            'A' => IntervalQuality::Augmented,
+            _ => return None,
+        };
+        let class = match chars.as_str() {
+            "1" => IntervalClass::Unison,
+            "2" => IntervalClass::Second,
+            "3" => IntervalClass::Third,
+            "4" => IntervalClass::Fourth,
+            "5" => IntervalClass::Fifth,
+            "6" => IntervalClass::Sixth,
+            "7" => IntervalClass::Seventh,
+            _ => return None,
+        };
+        Interval::with_quality(class, quality)
+    }
+}
+
+/// Written as the tpc followed by the octave, such as `"C4"` or `"B#-1"`
+impl WireFormat for Pitch {
+    fn to_wire(self) -> String {
+        format!("{}{}", self.tpc.to_wire(), self.octave)
+    }
+
+    fn from_wire(wire: &str) -> Option<Self> {
+        let start = wire.find(|c: char| c == '-' || c.is_ascii_digit())?;
+        let tpc = Tpc::from_wire(&wire[..start])?;
+        let octave = wire[start..].parse().ok()?;
+        Some(Pitch::new(tpc, octave))
+    }
+}
+
+/// With the `serde` feature, values are serialized as their wire strings
+#[cfg(feature = "serde")]
+macro_rules! impl_serde {
+    ($($type:ty),*) => {
+        $(
+            impl serde::Serialize for $type {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(&self.to_wire())
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $type {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let wire = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                    Self::from_wire(&wire).ok_or_else(|| {
+                        serde::de::Error::custom(format!("invalid {}: {:?}", stringify!($type), wire))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "serde")]
+impl_serde!(Tpc, Key, Interval, Pitch);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written<T: WireFormat>(values: impl Iterator<Item = T>) -> String {
+        let written: Vec<String> = values.map(WireFormat::to_wire).collect();
+        written.join(" ")
+    }
+
+    /// The strings must never change from one version to the next
+    #[test]
+    fn test_format_is_stable() {
+        assert_eq!(
+            "Fbb Cbb Gbb Dbb Abb Ebb Bbb Fb Cb Gb Db Ab Eb Bb F C G D A E B \
+             F# C# G# D# A# E# B# F## C## G## D## A## E## B##",
+            written(Tpc::all())
+        );
+        assert_eq!("Cb Gb Db Ab Eb Bb F C G D A E B F# C#", written(Key::all()));
+        assert_eq!(
+            "d2 d6 d3 d7 d4 d1 d5 m2 m6 m3 m7 P4 P1 P5 M2 M6 M3 M7 A4 A1 A5 A2 A6 A3 A7",
+            written(Interval::all())
+        );
+        assert_eq!("Cb-1", Pitch::new(Tpc::Cb, -1).to_wire());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for tpc in Tpc::all() {
+            assert_eq!(Some(tpc), Tpc::from_wire(&tpc.to_wire()));
+            assert_eq!(Some(tpc), Tpc::from_wire(&tpc.fifths().to_string()));
+            for octave in -1..10 {
+                let pitch = Pitch::new(tpc, octave);
+                assert_eq!(Some(pitch), Pitch::from_wire(&pitch.to_wire()));
+            }
+        }
+        for key in Key::all() {
+            assert_eq!(Some(key), Key::from_wire(&key.to_wire()));
+        }
+        for interval in Interval::all() {
+            assert_eq!(Some(interval), Interval::from_wire(&interval.to_wire()));
+            assert_eq!(
+                Some(interval),
+                Interval::from_wire(&interval.fifths().to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(None, Tpc::from_wire("f#"));
+        assert_eq!(None, Tpc::from_wire("F♯"));
+        assert_eq!(None, Tpc::from_wire("20"));
+        assert_eq!(None, Key::from_wire("G#"));
+        assert_eq!(None, Interval::from_wire("P3"));
+        assert_eq!(None, Interval::from_wire("M8"));
+        assert_eq!(None, Pitch::from_wire("C"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() {
+        use serde::de::value::{Error, StrDeserializer};
+        use serde::Deserialize;
+
+        let tpc = Tpc::deserialize(StrDeserializer::<Error>::new("F#"));
+        assert_eq!(Ok(Tpc::Fs), tpc);
+        let pitch = Pitch::deserialize(StrDeserializer::<Error>::new("Bb3"));
+        assert_eq!(Ok(Pitch::new(Tpc::Bb, 3)), pitch);
+        assert!(Interval::deserialize(StrDeserializer::<Error>::new("P4 ")).is_err());
+    }
+}
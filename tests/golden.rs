@@ -0,0 +1,139 @@
+//! Golden-file tests of every formatted output covered by the stability
+//! guarantee in the crate documentation.
+//!
+//! Each test renders a table of outputs and compares it byte for byte with a
+//! file in `tests/golden`. After an intended change of output, in a release
+//! which is allowed to make it, regenerate the files with
+//! `TONALITY_BLESS=1 cargo test --test golden` and review the diff.
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use tonality::abc;
+use tonality::chord::{Chord, ChordQuality};
+use tonality::key::NameStyle;
+use tonality::spelling::RespellPolicy;
+use tonality::wire::WireFormat;
+use tonality::{Accidental, Interval, Key, Mode, Octave, Pitch, Step, Tpc};
+
+/// Compare the output with its golden file, or overwrite the file when
+/// `TONALITY_BLESS` is set
+fn check(name: &str, actual: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if std::env::var_os("TONALITY_BLESS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("cannot read {}: {}", path.display(), error));
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "{} differs from the output at line {}; rerun with TONALITY_BLESS=1 if the change is intended",
+            name,
+            line + 1
+        );
+    }
+}
+
+#[test]
+fn golden_tpc_names() {
+    let mut out = String::new();
+    for tpc in Tpc::all() {
+        let pitch = Pitch::new(tpc, 4);
+        writeln!(
+            out,
+            "{}\t{:#}\t{}\t{}",
+            tpc,
+            tpc,
+            tpc.to_wire(),
+            pitch.to_wire()
+        )
+        .unwrap();
+    }
+    check("tpc_names.txt", &out);
+}
+
+#[test]
+fn golden_accidentals_steps_and_octaves() {
+    let mut out = String::new();
+    for accidental in Accidental::all() {
+        writeln!(out, "{}\t{:#}", accidental, accidental).unwrap();
+    }
+    for step in Step::all() {
+        writeln!(out, "{}", step).unwrap();
+    }
+    for octave in i8::from(Octave::MIN)..=i8::from(Octave::MAX) {
+        writeln!(out, "{}", Octave::new(octave).unwrap()).unwrap();
+    }
+    check("accidentals_steps_octaves.txt", &out);
+}
+
+#[test]
+fn golden_interval_names() {
+    let mut out = String::new();
+    for interval in Interval::all() {
+        writeln!(out, "{}", interval.to_wire()).unwrap();
+    }
+    check("interval_names.txt", &out);
+}
+
+#[test]
+fn golden_key_names() {
+    let styles = [
+        NameStyle::English,
+        NameStyle::EnglishLowercaseMinor,
+        NameStyle::German,
+    ];
+    let mut out = String::new();
+    for key in Key::all() {
+        writeln!(out, "{}\t{:#}\t{}", key, key, key.to_wire()).unwrap();
+        for &mode in &Mode::ALL {
+            let names: Vec<String> = styles.iter().map(|&style| key.name(mode, style)).collect();
+            writeln!(out, "\t{}", names.join("\t")).unwrap();
+        }
+    }
+    check("key_names.txt", &out);
+}
+
+#[test]
+fn golden_chord_symbols() {
+    let mut out = String::new();
+    for root in Tpc::all() {
+        for &quality in &ChordQuality::ALL {
+            let chord = Chord::new(root, quality);
+            write!(out, "{}\t{:#}", chord, chord).unwrap();
+            if let Some(&third) = chord.tones().as_ref().and_then(|tones| tones.get(1)) {
+                let inverted = chord.with_bass(third);
+                write!(out, "\t{}\t{:#}", inverted, inverted).unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+    }
+    for key in Key::all() {
+        let chord = Chord::new(Tpc::Cb, ChordQuality::Dominant7);
+        let symbol = chord.symbol_in_key(key);
+        let sharps = symbol.with_policy(RespellPolicy::PreferSharps);
+        writeln!(out, "{}\t{}\t{:#}\t{}", key, symbol, symbol, sharps).unwrap();
+    }
+    check("chord_symbols.txt", &out);
+}
+
+#[test]
+fn golden_abc_tokens() {
+    let mut out = String::new();
+    for key in std::iter::once(None).chain(Key::all().map(Some)) {
+        let tokens: Vec<String> = Tpc::all()
+            .flat_map(|tpc| (2..=6).map(move |octave| Pitch::new(tpc, octave)))
+            .map(|pitch| abc::format_pitch(pitch, key))
+            .collect();
+        writeln!(out, "{}", tokens.join(" ")).unwrap();
+    }
+    check("abc_tokens.txt", &out);
+}
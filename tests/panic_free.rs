@@ -0,0 +1,454 @@
+//! The `panic-free` contract: no public function of the core types panics,
+//! for any value of its arguments. Each test calls the functions over the
+//! full domain of their arguments, or the extremes and a range around zero
+//! where the domain is too large. Run with `cargo test --features panic-free`
+//! in the dev profile, so that arithmetic overflow panics too.
+#![cfg(feature = "panic-free")]
+
+use tonality::chord::{self, Chord, ChordQuality};
+use tonality::collections::{TpcMap, TpcSet};
+use tonality::dictionary::ChordDictionary;
+use tonality::interval::{Direction, IntervalClass, IntervalQuality, SpellingPreference};
+use tonality::key::{NameStyle, Rotation};
+use tonality::roman::RomanNumeral;
+use tonality::scale::{self, ScalePattern};
+use tonality::spelling::{self, RespellPolicy};
+use tonality::voicing::{Preset, Spacing, VoicingBuilder};
+use tonality::{Accidental, Interval, Key, Mode, Octave, Pitch, Step, Tpc};
+
+fn policies() -> Vec<RespellPolicy> {
+    let mut policies = vec![
+        RespellPolicy::PreferSharps,
+        RespellPolicy::PreferFlats,
+        RespellPolicy::MinimizeAccidentals,
+    ];
+    for key in Key::all() {
+        policies.push(RespellPolicy::KeyRelative(key));
+        policies.push(RespellPolicy::KeyRelativeMinor(key));
+    }
+    policies
+}
+
+/// Every `i8`
+fn all_i8() -> impl Iterator<Item = i8> {
+    i8::MIN..=i8::MAX
+}
+
+/// The extremes of `isize`, and a range around zero
+fn degrees() -> impl Iterator<Item = isize> {
+    [isize::MIN, isize::MIN + 1, isize::MAX - 1, isize::MAX]
+        .iter()
+        .copied()
+        .chain(-15..=15)
+}
+
+#[test]
+fn tpc() {
+    for tpc in Tpc::all() {
+        let _ = (tpc.step(), tpc.headroom(), tpc.sharpen(), tpc.flatten());
+        let _ = (tpc.natural(), tpc.pitch_class(), tpc.to_string());
+        let _ = (tpc.altered_step(None), tpc.spelled_step(None));
+        for by in all_i8() {
+            let _ = (tpc.alter(by), tpc.try_alter(by), tpc.clamp_alteration(by));
+            let mut copy = tpc;
+            let _ = (tpc + by, tpc - by, copy.try_alter_assign(by));
+        }
+        for key in Key::all() {
+            let _ = (tpc.alteration(key), tpc.try_alteration(key));
+            let _ = (tpc.bounded_alteration(key), tpc.altered_step(Some(key)));
+            let _ = (tpc.spelled_step(Some(key)), tpc.is_diatonic_in(key));
+            let _ = (tpc.chromatic_function_in(key), tpc.respell_in_key(key));
+            for steps in all_i8() {
+                let _ = tpc.transpose_diatonic(steps, key);
+            }
+        }
+        for interval in Interval::all() {
+            let _ = (tpc + interval, tpc - interval);
+            let _ = (tpc.try_add(interval), tpc.try_sub(interval));
+            let mut copy = tpc;
+            let _ = (copy.try_add_assign(interval), copy.try_sub_assign(interval));
+            let _ = tpc.cycle(interval).take(40).count();
+            for policy in policies() {
+                let _ = tpc.wrapping_add(interval, policy);
+                let _ = tpc.wrapping_sub(interval, policy);
+                let _ = tpc.wrapping_cycle(interval, policy).take(40).count();
+            }
+        }
+        for other in Tpc::all() {
+            let _ = (
+                tpc - other,
+                tpc.try_interval_to(other),
+                tpc.enharmonic(other),
+            );
+        }
+        for policy in policies() {
+            let _ = tpc.respell(policy);
+        }
+    }
+    for pitch_class in 0..=u8::MAX {
+        for policy in policies() {
+            let _ = Tpc::from_pitch_class(pitch_class, policy);
+        }
+    }
+}
+
+#[test]
+fn collections() {
+    let mut map: TpcMap<usize> = Tpc::all().zip(0..).collect();
+    let mut set = TpcSet::new();
+    for tpc in Tpc::all() {
+        let _ = (map.get(tpc), map.contains_key(tpc));
+        let _ = map.get_mut(tpc);
+        *map.get_or_insert(tpc, 0) += 1;
+        let _ = (map.remove(tpc), map.insert(tpc, 0), set.insert(tpc));
+        let _ = (set.contains(tpc), set.remove(tpc), set.insert(tpc));
+    }
+    let _ = (
+        map.len(),
+        map.keys(),
+        map.iter().count(),
+        map.iter_chromatic().count(),
+    );
+    let _ = (
+        set.len(),
+        TpcSet::all().iter().count(),
+        set.iter_chromatic().count(),
+    );
+}
+
+#[test]
+fn key() {
+    let styles = [
+        NameStyle::English,
+        NameStyle::EnglishLowercaseMinor,
+        NameStyle::German,
+    ];
+    for key in Key::all() {
+        let _ = (key.root_step(), key.root(), key.to_string());
+        let _ = (key.scale().count(), key.scale().rev().count());
+        for degree in degrees() {
+            let _ = (key.scale_degree(degree), key.triad(degree));
+            let _ = (key.seventh_chord(degree), key.triad_quality(degree));
+            let _ = key.seventh_quality(degree);
+        }
+        for &mode in &Mode::ALL {
+            let _ = (key.modal_scale(mode).count(), key.tonic(mode));
+            for &style in &styles {
+                let name = key.name(mode, style);
+                let _ = Key::from_name(&name);
+            }
+            for tpc in Tpc::all() {
+                let _ = (key.degree_name(tpc, mode), key.solfege(tpc, mode));
+                let _ = Key::from_tonic(tpc, mode);
+            }
+        }
+        for &rotation in &[Rotation::Clockwise, Rotation::Counterclockwise] {
+            let _ = key.circle_of_fifths(rotation).count();
+        }
+        for interval in Interval::all() {
+            let _ = (
+                key.transposed(interval),
+                key.transposed_enharmonic(interval),
+            );
+        }
+        for other in Key::all() {
+            let _ = (key.fifths_distance(other), key.closeness(other));
+            let _ = (key.is_closely_related(other), key.alterations_to(other));
+        }
+    }
+}
+
+#[test]
+fn step_and_accidental() {
+    for step in Step::all() {
+        let _ = (step.semitones(), step.to_string());
+        for other in Step::all() {
+            let _ = step.diatonic_distance(other);
+            let _ = step.same_octave_above(other);
+            for &direction in &[Direction::Up, Direction::Down] {
+                let _ = step.range_to(other, direction).count();
+            }
+        }
+        for rhs in degrees() {
+            let _ = (step + rhs, step - rhs);
+        }
+        for key in Key::all() {
+            let _ = step.with_key(key);
+            let _ = step.resolve(None, key);
+            for accidental in Accidental::all() {
+                let _ = (
+                    step.with_accidental(accidental),
+                    step.resolve(Some(accidental), key),
+                );
+                for to in Key::all() {
+                    for interval in Interval::all() {
+                        let _ = step.transpose(Some(accidental), key, to, interval);
+                    }
+                }
+            }
+        }
+    }
+    for accidental in Accidental::all() {
+        let _ = (accidental.to_string(), format!("{:#}", accidental));
+    }
+}
+
+#[test]
+fn interval() {
+    for interval in Interval::all() {
+        let _ = (interval.class(), interval.quality(), interval.augmented());
+        let _ = (interval.diminished(), interval.up(), interval.down());
+        let _ = (interval.semitones(), interval.is_dissonant_against_bass());
+        let _ = (interval.to_step_alteration(), -interval);
+        let _ = (interval.semitone_span(), interval.step_span());
+        for octaves in 0..=u8::MAX {
+            let compound = interval.compound_with_octaves(octaves);
+            let _ = (compound.semitones(), compound.steps());
+        }
+        for other in Interval::all() {
+            let _ = (interval + other, interval - other);
+            let _ = (interval.try_add(other), interval.try_sub(other));
+            let _ = (interval.enharmonic(other), interval.cmp_by_semitones(other));
+        }
+        let directed = interval.up();
+        let _ = (directed.semitones(), -directed);
+    }
+    for value in all_i8() {
+        for &prefer in &[
+            SpellingPreference::Augmented,
+            SpellingPreference::Diminished,
+        ] {
+            let _ = Interval::from_semitones(value, prefer);
+        }
+        for alteration in all_i8() {
+            let _ = Interval::from_step_alteration(value, alteration);
+        }
+    }
+    for steps in 0..=u8::MAX {
+        for semitones in all_i8() {
+            let _ = Interval::from_step_and_semitones(steps, semitones);
+        }
+    }
+    let qualities = [
+        IntervalQuality::Diminished,
+        IntervalQuality::Minor,
+        IntervalQuality::Perfect,
+        IntervalQuality::Major,
+        IntervalQuality::Augmented,
+    ];
+    for class in IntervalClass::all() {
+        for &quality in &qualities {
+            let _ = Interval::with_quality(class, quality);
+        }
+    }
+    let _ = (-Direction::Up, -Direction::Down);
+}
+
+#[test]
+fn mode() {
+    for &mode in &Mode::ALL {
+        let _ = (mode.offset(), mode.has_minor_third());
+        for degree in degrees() {
+            let _ = (mode.degree_interval(degree), mode.degree_name(degree));
+            let _ = mode.solfege(degree);
+            for &to in &Mode::ALL {
+                let _ = Mode::map_degree(mode, to, degree);
+            }
+        }
+    }
+}
+
+fn scale_pattern<P: ScalePattern + ?Sized>(pattern: &P) {
+    let _ = pattern.degrees();
+    for tonic in Tpc::all() {
+        let _ = pattern.spell(tonic);
+        for degree in degrees() {
+            let _ = pattern.degree(tonic, degree);
+        }
+        for tpc in Tpc::all() {
+            let _ = (
+                pattern.degree_of(tonic, tpc),
+                pattern.contains_tpc(tonic, tpc),
+            );
+            let _ = pattern.contains_enharmonic(tonic, tpc);
+        }
+    }
+}
+
+#[test]
+fn scales() {
+    let empty: &[Interval] = &[];
+    scale_pattern(empty);
+    scale_pattern(&Vec::new());
+    scale_pattern(&[]);
+    scale_pattern(&Interval::all().collect::<Vec<_>>());
+    scale_pattern(&scale::OCTATONIC);
+    for &mode in &Mode::ALL {
+        scale_pattern(&mode);
+    }
+    for start in Tpc::all() {
+        let _ = (
+            scale::spell_whole_tone(start),
+            scale::spell_octatonic(start, true),
+        );
+        let _ = scale::spell_octatonic(start, false);
+    }
+}
+
+#[test]
+fn pitch_and_octave() {
+    for tpc in Tpc::all() {
+        for octave in all_i8() {
+            let pitch = Pitch::new(tpc, octave);
+            let _ = (pitch.written_octave(), pitch.sounding_octave());
+            let _ = (pitch.step(), pitch.midi());
+            for other in Tpc::all() {
+                let _ = pitch.next_above(other);
+            }
+            for interval in Interval::all() {
+                let _ = (pitch + interval, pitch - interval);
+                let _ = (pitch + interval.up(), pitch + interval.down());
+                let _ = pitch + interval.compound_with_octaves(u8::MAX);
+            }
+            for &other_octave in &[i8::MIN, -1, 0, 4, i8::MAX] {
+                let other = Pitch::new(tpc, other_octave);
+                let _ = (pitch.interval_to(other), pitch.fold_into_octave(other));
+                let _ = pitch.clamp_to_range(other..=Pitch::new(Tpc::C, 4));
+                let _ = pitch.clamp_to_range(Pitch::new(Tpc::C, 4)..=other);
+            }
+        }
+    }
+    for note in 0..=u8::MAX {
+        let _ = Octave::from_midi(note);
+        for policy in policies() {
+            let _ = Pitch::from_midi(note, policy);
+        }
+    }
+    for number in all_i8() {
+        if let Some(octave) = Octave::new(number) {
+            let _ = (octave.midi(), octave.to_string(), octave - Octave::MIDDLE);
+            for rhs in all_i8() {
+                let _ = (octave + rhs, octave - rhs);
+            }
+        }
+    }
+}
+
+#[test]
+fn chord_and_spelling() {
+    for root in Tpc::all() {
+        for &quality in &ChordQuality::ALL {
+            let chord = Chord::new(root, quality);
+            let _ = (chord.tones(), chord.inversion(), chord.to_string());
+            for bass in Tpc::all() {
+                let chord = chord.with_bass(bass);
+                let _ = (chord.tones_from_bass(), chord.bass(), chord.to_string());
+            }
+            for inversion in 0..6 {
+                let _ = chord.with_inversion(inversion);
+            }
+            for &octave in &[i8::MIN, 0, 4, i8::MAX] {
+                let _ = chord.voicing(octave);
+            }
+            for key in Key::all() {
+                let _ = chord.symbol_in_key(key).to_string();
+                for &mode in &Mode::ALL {
+                    if let Some(numeral) = RomanNumeral::from_chord(chord, key, mode) {
+                        let _ = (numeral.romantext(mode), numeral.dcml());
+                        let _ = numeral.chord(key, mode);
+                    }
+                }
+            }
+            for policy in policies() {
+                let _ = chord.respell(policy);
+            }
+        }
+    }
+    let _ = chord::respell_minimal(&Tpc::all().collect::<Vec<_>>());
+    let dictionary = ChordDictionary::builtin();
+    for root in Tpc::all() {
+        for template in dictionary.templates() {
+            let _ = (template.voicings(root).build(), template.symbol_for(root));
+            if let Some(tones) = template.tones(root) {
+                let _ = dictionary.identify(&tones);
+            }
+        }
+    }
+    for symbol in &["", "b", "#", "Cb", "C##", "Cbbb7", "Fbm7b5", "ß", "Cß"] {
+        let _ = dictionary.parse(symbol);
+    }
+    let _ = dictionary.identify(&Tpc::all().collect::<Vec<_>>());
+    let pitch_classes: Vec<u8> = (0..=u8::MAX).collect();
+    let _ = spelling::spell_atonal(pitch_classes.iter().copied());
+    for key in Key::all() {
+        let _ = spelling::autocorrect(&Tpc::all().collect::<Vec<_>>(), key);
+    }
+}
+
+#[test]
+fn voicing() {
+    let spacings = [Spacing::Closed, Spacing::Open, Spacing::Drop2];
+    let presets = [
+        Preset::Close,
+        Preset::Spread,
+        Preset::Shell,
+        Preset::RootlessA,
+        Preset::RootlessB,
+    ];
+    let octaves = [i8::MIN, i8::MIN + 1, -1, 0, 4, i8::MAX - 1, i8::MAX];
+    let intervals = [Interval::Maj3, Interval::P5, Interval::Min7, Interval::Maj2];
+    for root in Tpc::all() {
+        for &lowest in &octaves {
+            for &highest in &octaves {
+                for &spacing in &spacings {
+                    let _ = VoicingBuilder::new(root, &intervals)
+                        .range(Pitch::new(Tpc::C, lowest), Pitch::new(Tpc::B, highest))
+                        .spacing(spacing)
+                        .build();
+                }
+            }
+            for &quality in &ChordQuality::ALL {
+                let chord = Chord::new(root, quality);
+                for &preset in &presets {
+                    let _ = preset.voice(chord, Pitch::new(Tpc::C, lowest));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+#[test]
+fn audio() {
+    use tonality::audio::{self, SineRenderer};
+    use tonality::tuning::{Edo, TunedNote};
+
+    let durations = [
+        0.0,
+        -1.0,
+        f64::NAN,
+        f64::NEG_INFINITY,
+        1e-9,
+        0.01,
+        f64::MAX,
+        f64::INFINITY,
+    ];
+    let pitches = [Pitch::new(Tpc::Fbb, i8::MIN), Pitch::new(Tpc::Bss, i8::MAX)];
+    for &sample_rate in &[0, 1, 8000, u32::MAX] {
+        for &a4 in &[0.0, -440.0, 440.0, f64::NAN] {
+            let renderer = SineRenderer::new(sample_rate, a4);
+            for &seconds in &durations {
+                let _ = renderer.render_tuned(&[], seconds);
+                let _ = renderer.render_chord(&[], Edo::TWELVE, seconds);
+                let _ = renderer.render_melody(&[], Edo::TWELVE, seconds);
+                if sample_rate <= 8000 && seconds <= 0.01 {
+                    let note = TunedNote::new(pitches[0], f32::NAN);
+                    let _ = renderer.render_tuned(&[note], seconds);
+                    let _ = renderer.render_chord(&pitches, Edo::THIRTY_ONE, seconds);
+                    let _ = renderer.render_melody(&pitches, Edo::THIRTY_ONE, seconds);
+                }
+            }
+        }
+    }
+    let _ = audio::to_i16(&[0.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY]);
+}
@@ -1,42 +1,10 @@
-use num_traits::FromPrimitive;
 use proptest::prelude::*;
-use tonality::{Interval, Key, Step, Tpc};
-
-fn tpcs() -> BoxedStrategy<Tpc> {
-    let min = Tpc::MIN as i8;
-    let max = Tpc::MAX as i8;
-    (min..=max)
-        .prop_map(|v| FromPrimitive::from_i8(v).unwrap())
-        .boxed()
-}
-
-fn keys() -> BoxedStrategy<Key> {
-    let min = Key::MIN as i8;
-    let max = Key::MAX as i8;
-    (min..=max)
-        .prop_map(|v| FromPrimitive::from_i8(v).unwrap())
-        .boxed()
-}
-
-fn steps() -> BoxedStrategy<Step> {
-    let min = Step::MIN as i8;
-    let max = Step::MAX as i8;
-    (min..=max)
-        .prop_map(|v| FromPrimitive::from_i8(v).unwrap())
-        .boxed()
-}
-
-fn intervals() -> BoxedStrategy<Interval> {
-    let min = Interval::MIN as i8;
-    let max = Interval::MAX as i8;
-    (min..=max)
-        .prop_map(|v| FromPrimitive::from_i8(v).unwrap())
-        .boxed()
-}
+use tonality::testing::{any_interval, any_key, any_policy, any_step, any_tpc};
+use tonality::Pitch;
 
 proptest! {
     #[test]
-    fn prop_alter_keeps_step(tpc in tpcs(), alter in -3..=3_i8) {
+    fn prop_alter_keeps_step(tpc in any_tpc(), alter in -3..=3_i8) {
         if let Some(altered) = tpc.alter(alter) {
             assert_eq!(tpc.step(), altered.step())
         }
@@ -45,14 +13,14 @@ proptest! {
 
 proptest! {
     #[test]
-    fn prop_adding_key_keeps_step(step in steps(), key in keys()) {
+    fn prop_adding_key_keeps_step(step in any_step(), key in any_key()) {
         assert_eq!(step, step.with_key(key).step())
     }
 }
 
 proptest! {
     #[test]
-    fn tpc_interval_interval_associative(tpc in tpcs(), i1 in intervals(), i2 in intervals()) {
+    fn tpc_interval_interval_associative(tpc in any_tpc(), i1 in any_interval(), i2 in any_interval()) {
         // One branch can fail while the other succeeds
         let res1 = (i1 + i2).map(|i| tpc + i);
         let res2 = (tpc + i1).map(|t| t + i2);
@@ -64,7 +32,7 @@ proptest! {
 
 proptest! {
     #[test]
-    fn steps_accidentals_can_recompose(tpc in tpcs(), key in keys()) {
+    fn steps_accidentals_can_recompose(tpc in any_tpc(), key in any_key()) {
         let (step, acc) = tpc.altered_step(Some(key));
         let reconstructed = match acc {
             None => step.with_key(key),
@@ -76,7 +44,29 @@ proptest! {
 
 proptest! {
     #[test]
-    fn first_scale_degree_is_root(key in keys()) {
+    fn first_scale_degree_is_root(key in any_key()) {
         assert_eq!(key.root(), key.scale_degree(0));
     }
 }
+
+proptest! {
+    #[test]
+    fn diatonic_distance_reaches_other_step(s1 in any_step(), s2 in any_step()) {
+        let distance = s1.diatonic_distance(s2);
+        assert_eq!(s2, s1 + distance as isize);
+    }
+}
+
+proptest! {
+    #[test]
+    fn respelling_is_enharmonic(tpc in any_tpc(), policy in any_policy()) {
+        assert!(tpc.enharmonic(tpc.respell(policy)));
+    }
+}
+
+proptest! {
+    #[test]
+    fn midi_spelling_roundtrips(note in 0..=127_u8, policy in any_policy()) {
+        assert_eq!(i16::from(note), Pitch::from_midi(note, policy).midi());
+    }
+}
@@ -0,0 +1,7 @@
+//! The `tonality` C library
+//!
+//! Build it with `cargo build --release` in this directory, which gives
+//! `libtonality_ffi.a` and `libtonality_ffi.so`. The functions are declared
+//! in `include/tonality.h` and documented in the `ffi` module of the
+//! `tonality` crate.
+pub use tonality::ffi::*;
@@ -0,0 +1,12 @@
+//! The `tonality` Python extension module
+//!
+//! Build it with `maturin build` or `maturin develop` in this directory. The
+//! classes are documented in the `python` module of the `tonality` crate.
+use pyo3::prelude::*;
+
+/// The `tonality` Python module
+#[pymodule]
+#[pyo3(name = "tonality")]
+fn tonality_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    tonality::python::register(module)
+}